@@ -1,5 +1,5 @@
 pub mod tools {
-    use std::fs::File;
+    use std::fs::{create_dir_all, File};
     use std::path::Path;
 
     use std::i16;
@@ -8,6 +8,8 @@ pub mod tools {
     use serde::Deserialize;
     use wav::{BitDepth, Header, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM};
 
+    use crate::markers::tools::{describe_markers, load_markers};
+
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     // Bufor audio, występuje w dwóch rodzajach: mono i stereo.
@@ -100,9 +102,12 @@ pub mod tools {
                         min_level_abs
                     };
 
-                    _buffer[0].iter_mut().for_each(|_sample_value| {
-                        *_sample_value = (_level * *_sample_value) / max_deviation
-                    });
+                    // Bufor zawierający ciszę pozostaje bez zmian.
+                    if max_deviation > 0.0 {
+                        _buffer[0].iter_mut().for_each(|_sample_value| {
+                            *_sample_value = (_level * *_sample_value) / max_deviation
+                        });
+                    }
                 }
                 // --- Stereo --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- --- ---
                 AudioBuffer::Stereo(_buffer) => {
@@ -146,12 +151,14 @@ pub mod tools {
                         max_deviation_right
                     };
 
-                    _buffer[0].iter_mut().for_each(|_sample_value| {
-                        *_sample_value = (_level * *_sample_value) / max_deviation
-                    });
-                    _buffer[1].iter_mut().for_each(|_sample_value| {
-                        *_sample_value = (_level * *_sample_value) / max_deviation
-                    });
+                    if max_deviation > 0.0 {
+                        _buffer[0].iter_mut().for_each(|_sample_value| {
+                            *_sample_value = (_level * *_sample_value) / max_deviation
+                        });
+                        _buffer[1].iter_mut().for_each(|_sample_value| {
+                            *_sample_value = (_level * *_sample_value) / max_deviation
+                        });
+                    }
                 }
             }
         }
@@ -163,6 +170,23 @@ pub mod tools {
             _sampling_rate: u32,
             _byte_rate: u16,
         ) -> Result<(), String> {
+            // Utworzenie brakujących katalogów nadrzędnych.
+            match Path::new(_output_file_path).parent() {
+                Some(_parent_directory) if _parent_directory.as_os_str().is_empty() == false => {
+                    match create_dir_all(_parent_directory) {
+                        Err(_system_error) => {
+                            return Err(format!(
+                                "Błąd ->\n\tnie można utworzyć katalogu \'{}\'.\n\tSystem error: {} :/",
+                                _parent_directory.display(),
+                                _system_error
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+
             let output_file_result = File::create(Path::new(_output_file_path));
 
             let mut output_file = match output_file_result {
@@ -344,5 +368,153 @@ pub mod tools {
     
             return Ok((normalized_audio_buffer, header.sampling_rate));
         }
+
+        // Mnoży wszystkie próbki bufora przez podane wzmocnienie.
+        pub fn amplify(&mut self, _gain: f64) {
+            match self {
+                AudioBuffer::Mono(_buffer) => {
+                    _buffer[0].iter_mut().for_each(|_sample_value| *_sample_value *= _gain);
+                }
+                AudioBuffer::Stereo(_buffer) => {
+                    _buffer
+                        .iter_mut()
+                        .flat_map(|_channel| _channel.iter_mut())
+                        .for_each(|_sample_value| *_sample_value *= _gain);
+                }
+            }
+        }
+
+        // Zwraca największą bezwzględną wartość próbki w buforze.
+        pub fn peak(&self) -> f64 {
+            match self {
+                AudioBuffer::Mono(_buffer) => {
+                    return _buffer[0].iter().fold(0.0, |_peak, _sample_value| {
+                        f64::max(_peak, _sample_value.abs())
+                    });
+                }
+                AudioBuffer::Stereo(_buffer) => {
+                    return _buffer[0]
+                        .iter()
+                        .chain(_buffer[1].iter())
+                        .fold(0.0, |_peak, _sample_value| f64::max(_peak, _sample_value.abs()));
+                }
+            }
+        }
+
+        // Zwraca obwiednię amplitudy sygnału: wartości skuteczne (RMS) kolejnych okien o podanej długości (w próbkach),
+        // kanały sygnału stereo są uśredniane.
+        pub fn envelope(&self, _window_length: usize) -> Vec<f64> {
+            let window_length = _window_length.max(1);
+
+            let channels: Vec<&Vec<f64>> = match self {
+                AudioBuffer::Mono(_buffer) => vec![&_buffer[0]],
+                AudioBuffer::Stereo(_buffer) => vec![&_buffer[0], &_buffer[1]],
+            };
+
+            return (0..self.len())
+                .step_by(window_length)
+                .map(|_window_start| {
+                    let window_end = (_window_start + window_length).min(self.len());
+                    let sum_of_squares: f64 = channels
+                        .iter()
+                        .map(|_channel| {
+                            _channel[_window_start..window_end]
+                                .iter()
+                                .map(|_sample_value| _sample_value * _sample_value)
+                                .sum::<f64>()
+                        })
+                        .sum();
+
+                    (sum_of_squares / ((window_end - _window_start) * channels.len()) as f64).sqrt()
+                })
+                .collect();
+        }
+
+        // Zwraca wartość skuteczną (RMS) sygnału w buforze.
+        pub fn rms(&self) -> f64 {
+            let (sum_of_squares, samples_count): (f64, usize) = match self {
+                AudioBuffer::Mono(_buffer) => (
+                    _buffer[0].iter().map(|_sample_value| _sample_value * _sample_value).sum(),
+                    _buffer[0].len(),
+                ),
+                AudioBuffer::Stereo(_buffer) => (
+                    _buffer[0]
+                        .iter()
+                        .chain(_buffer[1].iter())
+                        .map(|_sample_value| _sample_value * _sample_value)
+                        .sum(),
+                    _buffer[0].len() * 2,
+                ),
+            };
+
+            if samples_count == 0 {
+                return 0.0;
+            }
+
+            return (sum_of_squares / samples_count as f64).sqrt();
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Buduje opis pliku .wav: format, liczba kanałów, częstotliwość próbkowania, długość i poziomy sygnału.
+    pub fn describe_audio_file(_wav_file_path: &String) -> Result<String, String> {
+        let header = match File::open(Path::new(_wav_file_path)) {
+            Ok(mut _wav_file) => match wav::read(&mut _wav_file) {
+                Ok((_header, _)) => _header,
+                Err(_wav_error) => {
+                    return Err(format!(
+                        "Błąd odczytu ->\n\tnie można odczytać danych dźwiękowych z pliku: \'{}\'.\n\tWAV error: {} :/",
+                        _wav_file_path, _wav_error
+                    ))
+                }
+            },
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd odczytu ->\n\tplik: \'{}\' nie został znaleziony.\n\tSystem error: {} :/",
+                    _wav_file_path, _system_error
+                ))
+            }
+        };
+
+        let (audio_buffer, sampling_rate) = match AudioBuffer::load_audio(_wav_file_path) {
+            Ok(_audio) => _audio,
+            Err(_error) => {
+                return Err(_error);
+            }
+        };
+
+        let format_name: &str = match header.audio_format {
+            WAV_FORMAT_PCM => "PCM",
+            WAV_FORMAT_IEEE_FLOAT => "IEEE float",
+            _ => "nieznany",
+        };
+
+        let peak = audio_buffer.peak();
+        let rms = audio_buffer.rms();
+
+        // Znaczniki pliku ('cue', pętle 'smpl'), które mogą wskazywać fragmenty sampla.
+        let markers = match load_markers(_wav_file_path) {
+            Ok(_markers) if _markers.is_empty() == false => {
+                format!("\n\t# znaczniki:{}", describe_markers(&_markers, sampling_rate))
+            }
+            _ => String::new(),
+        };
+
+        return Ok(format!(
+            "Plik: \'{}\'\n\t# format: {}, {} bit\n\t# kanały: {}\n\t# częstotliwość próbkowania: {} Hz\n\t# długość: {} próbek ({:.3} s)\n\t# poziom szczytowy: {:.4} ({:.2} dBFS)\n\t# poziom RMS: {:.4} ({:.2} dBFS){}",
+            _wav_file_path,
+            format_name,
+            header.bits_per_sample,
+            header.channel_count,
+            sampling_rate,
+            audio_buffer.len(),
+            audio_buffer.len() as f64 / sampling_rate as f64,
+            peak,
+            20.0 * peak.log10(),
+            rms,
+            20.0 * rms.log10(),
+            markers
+        ));
     }
 }
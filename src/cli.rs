@@ -0,0 +1,309 @@
+pub mod tools {
+    use std::env::current_dir;
+
+    use serde_json::{json, Value};
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    const USAGE: &str = "Użycie:
+    granular_synth [render] <plik konfiguracyjny> [opcje]   synteza ścieżek
+    granular_synth validate <plik konfiguracyjny> [opcje]   sprawdzenie konfiguracji bez syntezy
+    granular_synth events <plik konfiguracyjny> [opcje]     wypisanie sekwencji zdarzeń
+    granular_synth convert <plik wejściowy> <plik wyjściowy> konwersja pliku konfiguracyjnego między formatami
+    granular_synth info <plik.wav>                          informacje o pliku dźwiękowym
+    granular_synth schema                                   schemat JSON pliku konfiguracyjnego
+
+Pliki konfiguracyjne: .json, .json5, .toml, .yaml (.yml) - format wynika z rozszerzenia pliku.
+
+Opcje:
+    -o, --output-directory <katalog>   nadpisuje 'output_directory'
+        --engine-rate <Hz>             nadpisuje 'engine_sampling_rate'
+        --output-rate <Hz>             nadpisuje 'output_sampling_rate'
+        --bit-depth <bit>              nadpisuje 'output_bit_depth'
+        --seed <liczba>                nadpisuje 'seed'
+        --variations <liczba>          nadpisuje 'variations' (liczba wariantów każdej ścieżki)
+        --export-events <formaty>      nadpisuje 'export_events' (lista formatów: csv,json,midi)
+    -t, --track <nazwa>                syntetyzuje tylko wskazaną ścieżkę (opcję można powtarzać)
+    -q, --quiet                        wypisuje wyłącznie błędy
+    -v, --verbose                      wypisuje szczegóły syntezy
+    -h, --help                         wyświetla tę pomoc";
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Polecenie wybrane w linii komend.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Command {
+        Render,
+        Validate,
+        Events,
+        Convert { output_file_path: String },
+        Info { wav_file_path: String },
+        Schema,
+        Help,
+    }
+
+    // Poziom szczegółowości wypisywanych komunikatów.
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    pub enum Verbosity {
+        Quiet,
+        Normal,
+        Verbose,
+    }
+
+    // Wartości nadpisujące ustawienia z sekcji 'SynthConfiguration'.
+    #[derive(Debug, Default)]
+    pub struct ConfigOverrides {
+        pub output_directory: Option<String>,
+        pub engine_sampling_rate: Option<u32>,
+        pub output_sampling_rate: Option<u32>,
+        pub output_bit_depth: Option<u16>,
+        pub seed: Option<u64>,
+        pub variations: Option<usize>,
+        pub export_events: Option<Vec<String>>,
+    }
+
+    impl ConfigOverrides {
+        // Nadpisuje wartości w obiekcie Value przed walidacją konfiguracji.
+        pub fn apply(&self, _json_file_value: &mut Value) {
+            let synth_configuration = &mut _json_file_value["SynthConfiguration"];
+
+            // Katalog podany w linii komend jest wyznaczany względem bieżącego katalogu roboczego.
+            if let Some(_output_directory) = &self.output_directory {
+                let output_directory = match current_dir() {
+                    Ok(_working_directory) => _working_directory
+                        .join(_output_directory)
+                        .to_string_lossy()
+                        .to_string(),
+                    Err(_) => _output_directory.clone(),
+                };
+
+                synth_configuration["output_directory"] = json!(output_directory);
+            }
+            if let Some(_engine_sampling_rate) = self.engine_sampling_rate {
+                synth_configuration["engine_sampling_rate"] = json!(_engine_sampling_rate);
+            }
+            if let Some(_output_sampling_rate) = self.output_sampling_rate {
+                synth_configuration["output_sampling_rate"] = json!(_output_sampling_rate);
+            }
+            if let Some(_output_bit_depth) = self.output_bit_depth {
+                synth_configuration["output_bit_depth"] = json!(_output_bit_depth);
+            }
+            if let Some(_seed) = self.seed {
+                synth_configuration["seed"] = json!(_seed);
+            }
+            if let Some(_variations) = self.variations {
+                synth_configuration["variations"] = json!(_variations);
+            }
+            if let Some(_export_events) = &self.export_events {
+                synth_configuration["export_events"] = json!(_export_events);
+            }
+        }
+    }
+
+    // Sparsowane argumenty linii komend.
+    #[derive(Debug)]
+    pub struct Arguments {
+        pub command: Command,
+        pub config_file_path: Option<String>,
+        pub overrides: ConfigOverrides,
+        pub selected_tracks: Vec<String>,
+        pub verbosity: Verbosity,
+    }
+
+    impl Arguments {
+        // Domyślne argumenty (synteza wszystkich ścieżek bez nadpisywania konfiguracji).
+        pub fn default() -> Self {
+            return Arguments {
+                command: Command::Render,
+                config_file_path: None,
+                overrides: ConfigOverrides::default(),
+                selected_tracks: Vec::new(),
+                verbosity: Verbosity::Normal,
+            };
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Zwraca tekst pomocy.
+    pub fn usage() -> &'static str {
+        return USAGE;
+    }
+
+    // Pobiera wartość opcji i konwertuje ją na wymagany typ.
+    fn option_value<T: std::str::FromStr>(
+        _arguments: &mut dyn Iterator<Item = String>,
+        _option: &str,
+    ) -> Result<T, String> {
+        let value = match _arguments.next() {
+            Some(_value) => _value,
+            None => {
+                return Err(format!(
+                    "Błąd danych ->\n\tbrak wartości opcji: \'{}\' :/",
+                    _option
+                ))
+            }
+        };
+
+        match value.parse::<T>() {
+            Ok(_parsed_value) => {
+                return Ok(_parsed_value);
+            }
+            Err(_) => {
+                return Err(format!(
+                    "Błąd danych ->\n\tnieprawidłowa wartość opcji: \'{}\': \'{}\' :/",
+                    _option, value
+                ));
+            }
+        }
+    }
+
+    // Parsuje argumenty linii komend (bez nazwy programu).
+    pub fn parse_arguments(_raw_arguments: Vec<String>) -> Result<Arguments, String> {
+        let mut arguments = Arguments::default();
+        let mut positional: Vec<String> = Vec::new();
+        let mut raw_arguments = _raw_arguments.into_iter();
+
+        while let Some(_argument) = raw_arguments.next() {
+            match _argument.as_str() {
+                "-o" | "--output-directory" => {
+                    arguments.overrides.output_directory =
+                        Some(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "--engine-rate" => {
+                    arguments.overrides.engine_sampling_rate =
+                        Some(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "--output-rate" => {
+                    arguments.overrides.output_sampling_rate =
+                        Some(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "--bit-depth" => {
+                    arguments.overrides.output_bit_depth =
+                        Some(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "--seed" => {
+                    arguments.overrides.seed = Some(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "--variations" => {
+                    arguments.overrides.variations =
+                        Some(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "--export-events" => {
+                    let formats: String = option_value(&mut raw_arguments, &_argument)?;
+
+                    arguments.overrides.export_events = Some(
+                        formats
+                            .split(',')
+                            .map(|_format| _format.trim().to_string())
+                            .filter(|_format| _format.is_empty() == false)
+                            .collect(),
+                    );
+                }
+                "-t" | "--track" => {
+                    arguments
+                        .selected_tracks
+                        .push(option_value(&mut raw_arguments, &_argument)?);
+                }
+                "-q" | "--quiet" => {
+                    arguments.verbosity = Verbosity::Quiet;
+                }
+                "-v" | "--verbose" => {
+                    arguments.verbosity = Verbosity::Verbose;
+                }
+                "-h" | "--help" => {
+                    arguments.command = Command::Help;
+                    return Ok(arguments);
+                }
+                _ => {
+                    if _argument.starts_with('-') && _argument.len() > 1 {
+                        return Err(format!(
+                            "Błąd danych ->\n\tnieznana opcja: \'{}\' :/\n\n{}",
+                            _argument, USAGE
+                        ));
+                    }
+
+                    positional.push(_argument);
+                }
+            }
+        }
+
+        // Wybór polecenia, ścieżka do pliku konfiguracyjnego bez polecenia oznacza syntezę.
+        let mut positional = positional.into_iter();
+
+        let file_path: Option<String> = match positional.next() {
+            Some(_word) => match _word.as_str() {
+                "render" => {
+                    arguments.command = Command::Render;
+                    positional.next()
+                }
+                "validate" => {
+                    arguments.command = Command::Validate;
+                    positional.next()
+                }
+                "events" => {
+                    arguments.command = Command::Events;
+                    positional.next()
+                }
+                "convert" => {
+                    let input_file_path = positional.next();
+
+                    match positional.next() {
+                        Some(_output_file_path) => {
+                            arguments.command = Command::Convert {
+                                output_file_path: _output_file_path,
+                            };
+                            input_file_path
+                        }
+                        None => {
+                            return Err(format!(
+                                "Błąd danych ->\n\tnie podano ścieżki do pliku wejściowego i wyjściowego konwersji :/\n\n{}",
+                                USAGE
+                            ));
+                        }
+                    }
+                }
+                "info" => match positional.next() {
+                    Some(_wav_file_path) => {
+                        arguments.command = Command::Info {
+                            wav_file_path: _wav_file_path,
+                        };
+                        None
+                    }
+                    None => {
+                        return Err(
+                            "Błąd danych ->\n\tnie podano ścieżki do pliku dźwiękowego (wymagana ścieżka do pliku \'.wav\') :/".to_string()
+                        );
+                    }
+                },
+                "schema" => {
+                    arguments.command = Command::Schema;
+                    None
+                }
+                _ => Some(_word),
+            },
+            None => None,
+        };
+
+        if let Some(_unexpected) = positional.next() {
+            return Err(format!(
+                "Błąd danych ->\n\tnieoczekiwany argument: \'{}\' :/\n\n{}",
+                _unexpected, USAGE
+            ));
+        }
+
+        match arguments.command {
+            Command::Render | Command::Validate | Command::Events if file_path.is_none() == true => {
+                return Err(format!(
+                    "Błąd danych ->\n\tnie podano ścieżki do pliku konfiguracyjnego (wymagana ścieżka do pliku \'.json\', \'.json5\', \'.toml\' lub \'.yaml\') :/\n\n{}",
+                    USAGE
+                ));
+            }
+            _ => {}
+        }
+
+        arguments.config_file_path = file_path;
+
+        return Ok(arguments);
+    }
+}
@@ -0,0 +1,828 @@
+pub mod tools {
+    use std::fs::canonicalize;
+    use std::path::PathBuf;
+
+    use serde_json::Value;
+
+    use crate::granular_synth_config::tools::{
+        config_directory, load_config_file, resolve_path, ConfigError,
+    };
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    const MAX_BEAT_REPEAT: u64 = 10_000;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Scala obiekt nakładki z obiektem bazowym, obiekty są scalane rekurencyjnie, pozostałe wartości zastępowane.
+    pub fn merge_values(_base: &mut Value, _overlay: Value) {
+        match (_base, _overlay) {
+            (Value::Object(_base_object), Value::Object(_overlay_object)) => {
+                for (_key, _overlay_field) in _overlay_object.into_iter() {
+                    match _base_object.get_mut(&_key) {
+                        Some(_base_field) => merge_values(_base_field, _overlay_field),
+                        None => {
+                            _base_object.insert(_key, _overlay_field);
+                        }
+                    }
+                }
+            }
+            (_base_value, _overlay_value) => {
+                *_base_value = _overlay_value;
+            }
+        }
+    }
+
+    // Scala plik dołączany z bieżącym obiektem Value, ścieżki z sekcji 'Tracks' są dopisywane po ścieżkach plików dołączanych.
+    fn merge_included(_base: &mut Value, mut _overlay: Value) {
+        if _base["Tracks"].is_array() == true && _overlay["Tracks"].is_array() == true {
+            if let (Some(Value::Array(_base_tracks)), Some(Value::Array(_overlay_tracks))) = (
+                _base.get_mut("Tracks"),
+                _overlay
+                    .as_object_mut()
+                    .and_then(|_object| _object.remove("Tracks")),
+            ) {
+                _base_tracks.extend(_overlay_tracks);
+            }
+        }
+
+        merge_values(_base, _overlay);
+    }
+
+    // Wczytuje pliki wskazane w zmiennej 'include' (rekurencyjnie) i scala je z obiektem Value pliku.
+    fn resolve_includes(
+        mut _json_file_value: Value,
+        _json_file_path: &String,
+        _include_stack: &mut Vec<PathBuf>,
+    ) -> Result<Value, Vec<ConfigError>> {
+        let include = match _json_file_value.as_object_mut() {
+            Some(_root) => _root.remove("include"),
+            None => None,
+        };
+
+        let (include_paths, single_include): (Vec<Value>, bool) = match include {
+            None => {
+                return Ok(_json_file_value);
+            }
+            Some(Value::String(_include_path)) => (vec![Value::String(_include_path)], true),
+            Some(Value::Array(_include_paths)) => (_include_paths, false),
+            Some(_) => {
+                return Err(vec![ConfigError::new(
+                    "/include".to_string(),
+                    format!(
+                        "Błąd danych - \'include\' (plik: \'{}\') ->\n\tnieprawidłowa wartość zmiennej: \'include\' (wymagana ścieżka lub lista ścieżek do plików konfiguracyjnych) :/",
+                        _json_file_path
+                    ),
+                )]);
+            }
+        };
+
+        let base_directory = config_directory(_json_file_path);
+        let mut merged_json_file_value: Option<Value> = None;
+
+        for (_include_number, _include_path) in include_paths.iter().enumerate() {
+            let pointer = if single_include == true {
+                "/include".to_string()
+            } else {
+                format!("/include/{}", _include_number)
+            };
+
+            let include_path = match _include_path.as_str() {
+                Some(_include_path) => _include_path,
+                None => {
+                    return Err(vec![ConfigError::new(
+                        pointer,
+                        format!(
+                            "Błąd danych - \'include\' (plik: \'{}\') ->\n\tnieprawidłowa wartość zmiennej: \'include\' (wymagana ścieżka do pliku konfiguracyjnego) :/",
+                            _json_file_path
+                        ),
+                    )]);
+                }
+            };
+
+            let included_file_path = match resolve_path(include_path, &base_directory) {
+                Ok(_included_file_path) => _included_file_path,
+                Err(_error) => {
+                    return Err(vec![ConfigError::new(pointer, _error)]);
+                }
+            };
+
+            let canonical_path =
+                canonicalize(&included_file_path).unwrap_or(PathBuf::from(&included_file_path));
+
+            if _include_stack.contains(&canonical_path) == true {
+                return Err(vec![ConfigError::new(
+                    pointer,
+                    format!(
+                        "Błąd danych - \'include\' (plik: \'{}\') ->\n\tcykliczne dołączanie pliku: \'{}\' :/",
+                        _json_file_path, included_file_path
+                    ),
+                )]);
+            }
+
+            let included_json_file_value = match load_config_file(&included_file_path) {
+                Ok(_included_json_file_value) => _included_json_file_value,
+                Err(_error) => {
+                    return Err(vec![ConfigError::new(pointer, _error)]);
+                }
+            };
+
+            _include_stack.push(canonical_path);
+
+            let included_json_file_value = match resolve_includes(
+                included_json_file_value,
+                &included_file_path,
+                _include_stack,
+            ) {
+                Ok(_included_json_file_value) => _included_json_file_value,
+                Err(_errors) => {
+                    return Err(_errors);
+                }
+            };
+
+            _include_stack.pop();
+
+            match &mut merged_json_file_value {
+                Some(_merged_json_file_value) => {
+                    merge_included(_merged_json_file_value, included_json_file_value)
+                }
+                None => {
+                    merged_json_file_value = Some(included_json_file_value);
+                }
+            }
+        }
+
+        match merged_json_file_value {
+            Some(mut _merged_json_file_value) => {
+                merge_included(&mut _merged_json_file_value, _json_file_value);
+                return Ok(_merged_json_file_value);
+            }
+            None => {
+                return Ok(_json_file_value);
+            }
+        }
+    }
+
+    // Stany łańcucha Markowa ścieżki (konfiguracje beatów) wraz z ich wskaźnikami JSON.
+    fn markov_states_mut<'a>(
+        _markov: &'a mut Value,
+        _track_pointer: &String,
+    ) -> Vec<(String, &'a mut Value)> {
+        match _markov
+            .get_mut("states")
+            .and_then(|_states| _states.as_object_mut())
+        {
+            Some(_states) => {
+                return _states
+                    .iter_mut()
+                    .map(|(_state, _beat)| {
+                        (
+                            format!(
+                                "{}/markov/states/{}",
+                                _track_pointer,
+                                _state.replace('~', "~0").replace('/', "~1")
+                            ),
+                            _beat,
+                        )
+                    })
+                    .collect();
+            }
+            None => {
+                return Vec::new();
+            }
+        }
+    }
+
+    // Wszystkie konfiguracje beatów ścieżki (sekwencja beatów i stany łańcucha Markowa) wraz z ich wskaźnikami JSON.
+    fn track_beats_mut<'a>(
+        _track: &'a mut Value,
+        _track_pointer: &String,
+    ) -> Vec<(String, &'a mut Value)> {
+        let mut beats: Vec<(String, &'a mut Value)> = Vec::new();
+
+        if let Some(_track) = _track.as_object_mut() {
+            for (_field_name, _field) in _track.iter_mut() {
+                match (_field_name.as_str(), _field) {
+                    ("beat_sequence", Value::Array(_beat_sequence)) => {
+                        beats.extend(_beat_sequence.iter_mut().enumerate().map(
+                            |(_beat_number, _beat)| {
+                                (
+                                    format!("{}/beat_sequence/{}", _track_pointer, _beat_number),
+                                    _beat,
+                                )
+                            },
+                        ));
+                    }
+                    ("markov", _markov) => {
+                        beats.extend(markov_states_mut(_markov, _track_pointer));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        return beats;
+    }
+
+    // Zastępuje obiekt odwołujący się do szablonu ('preset') kopią szablonu scaloną z pozostałymi zmiennymi obiektu.
+    fn apply_preset(
+        _value: &mut Value,
+        _presets: &Value,
+        _presets_group: &str,
+        _pointer: &String,
+    ) -> Result<(), ConfigError> {
+        let preset_name = match _value
+            .as_object_mut()
+            .and_then(|_object| _object.remove("preset"))
+        {
+            None => {
+                return Ok(());
+            }
+            Some(Value::String(_preset_name)) => _preset_name,
+            Some(_) => {
+                return Err(ConfigError::new(
+                    format!("{}/preset", _pointer),
+                    "Błąd danych - \'preset\' ->\n\tnieprawidłowa wartość zmiennej: \'preset\' (wymagana nazwa szablonu) :/"
+                        .to_string(),
+                ));
+            }
+        };
+
+        let mut preset = match _presets[_presets_group].get(&preset_name) {
+            Some(_preset) if _preset.is_object() == true => _preset.clone(),
+            _ => {
+                return Err(ConfigError::new(
+                    format!("{}/preset", _pointer),
+                    format!(
+                        "Błąd danych - \'preset\' ->\n\tszablon: \'{}\' nie występuje w sekcji \'Presets/{}\' :/",
+                        preset_name, _presets_group
+                    ),
+                ));
+            }
+        };
+
+        merge_values(&mut preset, _value.take());
+        *_value = preset;
+
+        return Ok(());
+    }
+
+    // Rozwija szablony ścieżek, właściwości granulek i beatów oraz powtórzenia beatów ('repeat').
+    fn expand_presets(
+        _json_file_value: &mut Value,
+        _presets: &Value,
+    ) -> Result<(), Vec<ConfigError>> {
+        let mut errors: Vec<ConfigError> = Vec::new();
+
+        let tracks = match _json_file_value
+            .get_mut("Tracks")
+            .and_then(|_tracks| _tracks.as_array_mut())
+        {
+            Some(_tracks) => _tracks,
+            None => {
+                return Ok(());
+            }
+        };
+
+        for (_track_number, _track) in tracks.iter_mut().enumerate() {
+            let track_pointer = format!("/Tracks/{}", _track_number);
+
+            match apply_preset(_track, _presets, "tracks", &track_pointer) {
+                Err(_error) => {
+                    errors.push(_error);
+                    continue;
+                }
+                _ => {}
+            }
+
+            if let Some(_grains_properties) = _track.get_mut("grains_properties") {
+                match apply_preset(
+                    _grains_properties,
+                    _presets,
+                    "grains",
+                    &format!("{}/grains_properties", track_pointer),
+                ) {
+                    Err(_error) => {
+                        errors.push(_error);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(_markov) = _track.get_mut("markov") {
+                for (_state_pointer, _state) in markov_states_mut(_markov, &track_pointer) {
+                    match apply_preset(_state, _presets, "beats", &_state_pointer) {
+                        Err(_error) => {
+                            errors.push(_error);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let beat_sequence = match _track
+                .get_mut("beat_sequence")
+                .and_then(|_beat_sequence| _beat_sequence.as_array_mut())
+            {
+                Some(_beat_sequence) => _beat_sequence,
+                None => {
+                    continue;
+                }
+            };
+
+            let mut expanded_beat_sequence: Vec<Value> = Vec::with_capacity(beat_sequence.len());
+
+            for (_beat_number, mut _beat) in beat_sequence.drain(..).enumerate() {
+                let beat_pointer = format!("{}/beat_sequence/{}", track_pointer, _beat_number);
+
+                match apply_preset(&mut _beat, _presets, "beats", &beat_pointer) {
+                    Err(_error) => {
+                        errors.push(_error);
+                        continue;
+                    }
+                    _ => {}
+                }
+
+                let repeat = match _beat
+                    .as_object_mut()
+                    .and_then(|_beat| _beat.remove("repeat"))
+                {
+                    None => 1,
+                    Some(_repeat) => match _repeat.as_u64() {
+                        Some(_repeat) if (1..=MAX_BEAT_REPEAT).contains(&_repeat) => _repeat,
+                        _ => {
+                            errors.push(ConfigError::new(
+                                format!("{}/repeat", beat_pointer),
+                                format!(
+                                    "Błąd danych - ścieżka [{}] - beat [{}] ->\n\tnieprawidłowa wartość zmiennej: \'repeat\' (1 ... {}) :/",
+                                    _track_number + 1, _beat_number + 1, MAX_BEAT_REPEAT
+                                ),
+                            ));
+                            continue;
+                        }
+                    },
+                };
+
+                for _ in 0..repeat {
+                    expanded_beat_sequence.push(_beat.clone());
+                }
+            }
+
+            *beat_sequence = expanded_beat_sequence;
+        }
+
+        if errors.is_empty() == true {
+            return Ok(());
+        } else {
+            return Err(errors);
+        }
+    }
+
+    // Uzupełnia ścieżki wartościami z sekcji 'defaults' (właściwości ścieżki, granulek i beatów).
+    // Wartości podane w ścieżce lub w jej szablonach mają pierwszeństwo przed wartościami domyślnymi.
+    fn apply_defaults(_json_file_value: &mut Value) -> Result<(), Vec<ConfigError>> {
+        let defaults = match _json_file_value.as_object_mut() {
+            Some(_root) => _root.remove("defaults"),
+            None => None,
+        };
+
+        let defaults = match defaults {
+            None => {
+                return Ok(());
+            }
+            Some(Value::Object(_defaults)) => _defaults,
+            Some(_) => {
+                return Err(vec![ConfigError::new(
+                    "/defaults".to_string(),
+                    "Błąd danych - 'defaults' ->\n\tnieprawidłowa wartość zmiennej: 'defaults' (wymagany obiekt z sekcjami 'track_properties', 'grains_properties', 'beat_sequence') :/"
+                        .to_string(),
+                )]);
+            }
+        };
+
+        let mut errors: Vec<ConfigError> = Vec::new();
+
+        for (_section, _section_defaults) in defaults.iter() {
+            if matches!(
+                _section.as_str(),
+                "track_properties" | "grains_properties" | "beat_sequence"
+            ) == false
+                || _section_defaults.is_object() == false
+            {
+                errors.push(ConfigError::new(
+                    format!("/defaults/{}", _section),
+                    format!(
+                        "Błąd danych - 'defaults' ->\n\tnieprawidłowa sekcja: '{}' (dozwolone obiekty: 'track_properties', 'grains_properties', 'beat_sequence') :/",
+                        _section
+                    ),
+                ));
+            }
+        }
+
+        if errors.is_empty() == false {
+            return Err(errors);
+        }
+
+        let tracks = match _json_file_value
+            .get_mut("Tracks")
+            .and_then(|_tracks| _tracks.as_array_mut())
+        {
+            Some(_tracks) => _tracks,
+            None => {
+                return Ok(());
+            }
+        };
+
+        for (_track_number, _track) in tracks.iter_mut().enumerate() {
+            if _track.is_object() == false {
+                continue;
+            }
+
+            for _section in ["track_properties", "grains_properties"].iter() {
+                if let Some(_section_defaults) = defaults.get(*_section) {
+                    let mut section = _section_defaults.clone();
+
+                    if let Some(_track_section) = _track.get_mut(*_section) {
+                        merge_values(&mut section, _track_section.take());
+                    }
+
+                    _track[*_section] = section;
+                }
+            }
+
+            if let Some(_beat_defaults) = defaults.get("beat_sequence") {
+                for (_, _beat) in track_beats_mut(_track, &format!("/Tracks/{}", _track_number)) {
+                    let mut beat = _beat_defaults.clone();
+
+                    merge_values(&mut beat, _beat.take());
+                    *_beat = beat;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Zastępuje odwołania do szablonów groove beatów ich treścią: nazwę szablonu z sekcji 'Presets/grooves'
+    // lub obiekt '{ "file": ścieżka }' wskazujący plik konfiguracyjny z szablonem (względem katalogu pliku konfiguracyjnego).
+    fn resolve_grooves(
+        _json_file_value: &mut Value,
+        _presets: &Value,
+        _json_file_path: &String,
+    ) -> Result<(), Vec<ConfigError>> {
+        let base_directory = config_directory(_json_file_path);
+        let mut errors: Vec<ConfigError> = Vec::new();
+
+        let tracks = match _json_file_value
+            .get_mut("Tracks")
+            .and_then(|_tracks| _tracks.as_array_mut())
+        {
+            Some(_tracks) => _tracks,
+            None => {
+                return Ok(());
+            }
+        };
+
+        for (_track_number, _track) in tracks.iter_mut().enumerate() {
+            for (_beat_pointer, _beat) in track_beats_mut(_track, &format!("/Tracks/{}", _track_number)) {
+                let pointer = format!("{}/groove", _beat_pointer);
+
+                let groove = match _beat.get_mut("groove") {
+                    Some(_groove) => _groove,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let groove_file_path = match groove.get("file") {
+                    Some(_groove_file_path) => _groove_file_path.as_str(),
+                    None => None,
+                };
+
+                if let Some(_groove_name) = groove.as_str() {
+                    match _presets["grooves"].get(_groove_name) {
+                        Some(_preset) if _preset.is_object() == true => {
+                            *groove = _preset.clone();
+                        }
+                        _ => {
+                            errors.push(ConfigError::new(
+                                pointer,
+                                format!(
+                                    "Błąd danych - \'groove\' ->\n\tszablon: \'{}\' nie występuje w sekcji \'Presets/grooves\' :/",
+                                    _groove_name
+                                ),
+                            ));
+                        }
+                    }
+                } else if let Some(_groove_file_path) = groove_file_path {
+                    let groove_file_path = match resolve_path(_groove_file_path, &base_directory) {
+                        Ok(_groove_file_path) => _groove_file_path,
+                        Err(_error) => {
+                            errors.push(ConfigError::new(format!("{}/file", pointer), _error));
+                            continue;
+                        }
+                    };
+
+                    match load_config_file(&groove_file_path) {
+                        Ok(_groove) if _groove.is_object() == true => {
+                            *groove = _groove;
+                        }
+                        Ok(_) => {
+                            errors.push(ConfigError::new(
+                                format!("{}/file", pointer),
+                                format!(
+                                    "Błąd danych - \'groove\' ->\n\tplik: \'{}\' nie zawiera szablonu groove (wymagany obiekt ze zmienną \'steps\') :/",
+                                    groove_file_path
+                                ),
+                            ));
+                        }
+                        Err(_error) => {
+                            errors.push(ConfigError::new(format!("{}/file", pointer), _error));
+                        }
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() == true {
+            return Ok(());
+        } else {
+            return Err(errors);
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Rozwija dołączane pliki ('include'), szablony ('Presets', 'preset'), powtórzenia beatów ('repeat')
+    // i wartości domyślne ścieżek ('defaults') oraz wczytuje szablony groove beatów.
+    // Wynikowy obiekt Value ma postać zwykłego pliku konfiguracyjnego i podlega dalszej walidacji.
+    pub fn expand_configuration(
+        _json_file_value: Value,
+        _json_file_path: &String,
+    ) -> Result<Value, Vec<ConfigError>> {
+        let mut include_stack: Vec<PathBuf> = Vec::new();
+
+        if let Ok(_canonical_path) = canonicalize(_json_file_path) {
+            include_stack.push(_canonical_path);
+        }
+
+        let mut json_file_value =
+            match resolve_includes(_json_file_value, _json_file_path, &mut include_stack) {
+                Ok(_json_file_value) => _json_file_value,
+                Err(_errors) => {
+                    return Err(_errors);
+                }
+            };
+
+        let presets = match json_file_value.as_object_mut() {
+            Some(_root) => _root.remove("Presets").unwrap_or(Value::Null),
+            None => Value::Null,
+        };
+
+        match expand_presets(&mut json_file_value, &presets) {
+            Err(_errors) => {
+                return Err(_errors);
+            }
+            _ => {}
+        }
+
+        match apply_defaults(&mut json_file_value) {
+            Err(_errors) => {
+                return Err(_errors);
+            }
+            _ => {}
+        }
+
+        match resolve_grooves(&mut json_file_value, &presets, _json_file_path) {
+            Err(_errors) => {
+                return Err(_errors);
+            }
+            _ => {}
+        }
+
+        return Ok(json_file_value);
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::fs::{create_dir_all, remove_dir_all, write};
+
+        use serde_json::json;
+
+        use super::*;
+
+        // Zapisuje pliki konfiguracyjne w katalogu tymczasowym testu i zwraca ścieżkę pierwszego z nich.
+        fn config_files(_test_name: &str, _files: &[(&str, Value)]) -> (PathBuf, String) {
+            let directory = std::env::temp_dir()
+                .join(format!("granular_synth_expansion_{}_{}", _test_name, std::process::id()));
+
+            create_dir_all(&directory).unwrap();
+
+            for (_file_name, _value) in _files.iter() {
+                write(directory.join(_file_name), _value.to_string()).unwrap();
+            }
+
+            let file_path = directory.join(_files[0].0).to_string_lossy().to_string();
+
+            return (directory, file_path);
+        }
+
+        fn expand(_test_name: &str, _files: &[(&str, Value)]) -> Result<Value, Vec<ConfigError>> {
+            let (directory, file_path) = config_files(_test_name, _files);
+            let expanded = expand_configuration(load_config_file(&file_path).unwrap(), &file_path);
+
+            remove_dir_all(&directory).unwrap();
+
+            return expanded;
+        }
+
+        #[test]
+        fn includes_are_merged_in_order() {
+            let expanded = expand(
+                "include",
+                &[
+                    (
+                        "main.json",
+                        json!({
+                            "include": ["base.json", "tracks.json"],
+                            "SynthConfiguration": { "beat_length_ms": 250.0, "output_directory": "main" },
+                            "Tracks": [{ "track_properties": { "track_name": "main" } }]
+                        }),
+                    ),
+                    (
+                        "base.json",
+                        json!({
+                            "SynthConfiguration": { "seed": 7, "output_directory": "base" },
+                            "Tracks": [{ "track_properties": { "track_name": "base" } }]
+                        }),
+                    ),
+                    (
+                        "tracks.json",
+                        json!({ "Tracks": [{ "track_properties": { "track_name": "included" } }] }),
+                    ),
+                ],
+            )
+            .unwrap();
+
+            assert_eq!(
+                expanded["SynthConfiguration"],
+                json!({ "seed": 7, "beat_length_ms": 250.0, "output_directory": "main" })
+            );
+            assert_eq!(
+                expanded["Tracks"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|_track| _track["track_properties"]["track_name"].as_str().unwrap())
+                    .collect::<Vec<&str>>(),
+                vec!["base", "included", "main"]
+            );
+            assert!(expanded.get("include").is_none());
+        }
+
+        #[test]
+        fn include_cycles_are_rejected() {
+            let errors = expand(
+                "cycle",
+                &[
+                    ("a.json", json!({ "include": "b.json" })),
+                    ("b.json", json!({ "include": ["c.json"] })),
+                    ("c.json", json!({ "include": "a.json" })),
+                ],
+            )
+            .unwrap_err();
+
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].message.contains("cykliczne dołączanie pliku"));
+
+            let errors = expand("self", &[("a.json", json!({ "include": "a.json" }))]).unwrap_err();
+
+            assert_eq!(errors[0].pointer, "/include");
+        }
+
+        #[test]
+        fn track_values_override_presets() {
+            let expanded = expand(
+                "presets",
+                &[(
+                    "main.json",
+                    json!({
+                        "Presets": {
+                            "tracks": { "pad": { "track_properties": { "track_name": "pad", "track_panorama": -0.5 } } },
+                            "grains": { "dense": { "grains_count": 64, "sample_file_path": "a.wav" } },
+                            "beats": { "busy": { "subdivisions": 8, "swing_percent": 20.0 } }
+                        },
+                        "Tracks": [{
+                            "preset": "pad",
+                            "track_properties": { "track_panorama": 0.25 },
+                            "grains_properties": { "preset": "dense", "grains_count": 16 },
+                            "beat_sequence": [{ "preset": "busy", "subdivisions": 3 }]
+                        }]
+                    }),
+                )],
+            )
+            .unwrap();
+
+            let track = &expanded["Tracks"][0];
+
+            assert_eq!(track["track_properties"], json!({ "track_name": "pad", "track_panorama": 0.25 }));
+            assert_eq!(track["grains_properties"], json!({ "grains_count": 16, "sample_file_path": "a.wav" }));
+            assert_eq!(track["beat_sequence"], json!([{ "subdivisions": 3, "swing_percent": 20.0 }]));
+            assert!(expanded.get("Presets").is_none() && track.get("preset").is_none());
+
+            let errors = expand(
+                "missing_preset",
+                &[("main.json", json!({ "Tracks": [{ "beat_sequence": [{}, { "preset": "none" }] }] }))],
+            )
+            .unwrap_err();
+
+            assert_eq!(errors[0].pointer, "/Tracks/0/beat_sequence/1/preset");
+        }
+
+        #[test]
+        fn repeat_copies_beats_within_bounds() {
+            let expanded = expand(
+                "repeat",
+                &[(
+                    "main.json",
+                    json!({
+                        "Tracks": [{
+                            "beat_sequence": [{ "subdivisions": 1 }, { "subdivisions": 2, "repeat": 3 }, { "subdivisions": 3, "repeat": 1 }]
+                        }]
+                    }),
+                )],
+            )
+            .unwrap();
+
+            assert_eq!(
+                expanded["Tracks"][0]["beat_sequence"],
+                json!([{ "subdivisions": 1 }, { "subdivisions": 2 }, { "subdivisions": 2 }, { "subdivisions": 2 }, { "subdivisions": 3 }])
+            );
+
+            let errors = expand(
+                "repeat_bounds",
+                &[(
+                    "main.json",
+                    json!({
+                        "Tracks": [{
+                            "beat_sequence": [
+                                { "repeat": 0 },
+                                { "repeat": MAX_BEAT_REPEAT },
+                                { "repeat": MAX_BEAT_REPEAT + 1 },
+                                { "repeat": 2.5 }
+                            ]
+                        }]
+                    }),
+                )],
+            )
+            .unwrap_err();
+
+            assert_eq!(
+                errors.iter().map(|_error| _error.pointer.as_str()).collect::<Vec<&str>>(),
+                vec![
+                    "/Tracks/0/beat_sequence/0/repeat",
+                    "/Tracks/0/beat_sequence/2/repeat",
+                    "/Tracks/0/beat_sequence/3/repeat"
+                ]
+            );
+        }
+
+        #[test]
+        fn defaults_fill_values_missing_after_presets() {
+            let expanded = expand(
+                "defaults",
+                &[(
+                    "main.json",
+                    json!({
+                        "defaults": {
+                            "track_properties": { "track_panorama": 0.5, "track_normalization_level": 0.8 },
+                            "beat_sequence": { "subdivisions": 4, "swing_percent": 10.0, "coverage_percentage": 50.0 }
+                        },
+                        "Presets": { "beats": { "busy": { "subdivisions": 8 } } },
+                        "Tracks": [{
+                            "track_properties": { "track_panorama": -0.5 },
+                            "beat_sequence": [{ "preset": "busy", "swing_percent": 30.0 }, {}]
+                        }]
+                    }),
+                )],
+            )
+            .unwrap();
+
+            let track = &expanded["Tracks"][0];
+
+            assert_eq!(
+                track["track_properties"],
+                json!({ "track_panorama": -0.5, "track_normalization_level": 0.8 })
+            );
+            // Wartość ścieżki ma pierwszeństwo przed szablonem, a szablon - przed wartością domyślną.
+            assert_eq!(
+                track["beat_sequence"],
+                json!([
+                    { "subdivisions": 8, "swing_percent": 30.0, "coverage_percentage": 50.0 },
+                    { "subdivisions": 4, "swing_percent": 10.0, "coverage_percentage": 50.0 }
+                ])
+            );
+            assert!(expanded.get("defaults").is_none());
+        }
+    }
+}
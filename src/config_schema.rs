@@ -0,0 +1,888 @@
+pub mod tools {
+    use serde_json::{json, Value};
+
+    use crate::granular_synth_config::tools::{
+        default_beat_length_ms, default_coverage_percentage, default_descriptor_neighbours, default_engine_sampling_rate,
+        default_grains_alignment, default_grains_count, default_grains_laudness_normalization, default_grains_length_ms,
+        default_grains_length_multiplier, default_grains_pitch, default_grains_pitch_multiplier, default_guide_depth_percents, default_guide_window_ms, default_midi_follow_pitch, default_midi_root_note, default_mosaic_features, default_mosaic_frame_ms, default_mosaic_match_loudness, default_mosaic_silence_db, default_onset_jitter_ms, default_onset_min_interval_ms, default_onset_threshold, default_output_bit_depth, default_output_directory, default_psola_periods, default_psola_pitch_shift, default_psola_time_stretch,
+        default_output_file_template, default_source_gain, default_source_pitch, default_subdivisions, default_time_signature, default_track_normalization_level,
+        default_variations, default_window_function, default_yin_threshold, BEAT_LENGTH_MS_RANGE, DESCRIPTOR_NEIGHBOURS_RANGE, GRAINS_COUNT_RANGE, GRAINS_LENGTH_MS_MIN, GRAINS_LENGTH_MULTIPLIER_RANGE, GRAINS_PITCH_RANGE,
+        GROOVE_TIMING_PERCENT_RANGE, GROOVE_VELOCITY_PERCENT_RANGE, GUIDE_WINDOW_MS_RANGE, HUMANIZATION_PERCENTS_RANGE, MARKOV_BEATS_RANGE, MIDI_CHANNEL_RANGE, MIDI_NOTE_RANGE, MOSAIC_FRAME_MS_RANGE, MOSAIC_SILENCE_DB_RANGE, ONSET_JITTER_MS_RANGE, ONSET_MIN_INTERVAL_MS_RANGE, ONSET_THRESHOLD_RANGE, OUTPUT_BIT_DEPTHS, PERCENTAGE_RANGE, PSOLA_PERIODS_RANGE, PSOLA_TIME_STRETCH_RANGE,
+        SAMPLING_RATE_RANGE, SOURCE_GAIN_RANGE, SWING_PERCENT_RANGE, TEMPO_BPM_RANGE, TIME_SIGNATURE_DENOMINATORS, TIME_SIGNATURE_NUMERATOR_RANGE,
+        TRACK_NORMALIZATION_LEVEL_RANGE, TRACK_PANORAMA_RANGE, VARIATIONS_RANGE, WINDOW_SLOPE_MIN, YIN_THRESHOLD_RANGE,
+    };
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Uzupełnia schematy zmiennych o wartości domyślne.
+    fn with_defaults(mut _schema: Value, _defaults: Value) -> Value {
+        if let Value::Object(_defaults) = _defaults {
+            for (_property, _default) in _defaults.into_iter() {
+                _schema["properties"][_property]["default"] = _default;
+            }
+        }
+
+        return _schema;
+    }
+
+    // Schemat czasu trwania w beatach, taktach lub milisekundach.
+    fn musical_duration_schema() -> Value {
+        return json!({
+            "type": "object",
+            "minProperties": 1,
+            "maxProperties": 1,
+            "properties": {
+                "beats": { "type": "number", "exclusiveMinimum": 0 },
+                "bars": { "type": "number", "exclusiveMinimum": 0 },
+                "ms": { "type": "number", "exclusiveMinimum": 0 }
+            },
+            "additionalProperties": false
+        });
+    }
+
+    // Schemat sekcji 'SynthConfiguration', pominięta 'output_sampling_rate' jest równa 'engine_sampling_rate'.
+    fn synth_configuration_schema() -> Value {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "beat_length_ms": {
+                    "type": "number",
+                    "minimum": BEAT_LENGTH_MS_RANGE.0,
+                    "maximum": BEAT_LENGTH_MS_RANGE.1
+                },
+                "tempo_bpm": {
+                    "type": "number",
+                    "minimum": TEMPO_BPM_RANGE.0,
+                    "maximum": TEMPO_BPM_RANGE.1
+                },
+                "time_signature": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer",
+                            "minimum": TIME_SIGNATURE_NUMERATOR_RANGE.0,
+                            "maximum": TIME_SIGNATURE_NUMERATOR_RANGE.1
+                        },
+                        { "type": "integer", "enum": TIME_SIGNATURE_DENOMINATORS }
+                    ],
+                    "minItems": 2,
+                    "maxItems": 2
+                },
+                "tempo_map": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["at", "tempo_bpm"],
+                        "properties": {
+                            "at": {
+                                "type": "object",
+                                "minProperties": 1,
+                                "maxProperties": 1,
+                                "properties": {
+                                    "beats": { "type": "number", "minimum": 0 },
+                                    "bars": { "type": "number", "minimum": 0 }
+                                },
+                                "additionalProperties": false
+                            },
+                            "tempo_bpm": {
+                                "type": "number",
+                                "minimum": TEMPO_BPM_RANGE.0,
+                                "maximum": TEMPO_BPM_RANGE.1
+                            },
+                            "ramp": { "type": "boolean" }
+                        }
+                    }
+                },
+                "engine_sampling_rate": {
+                    "type": "integer",
+                    "minimum": SAMPLING_RATE_RANGE.0,
+                    "maximum": SAMPLING_RATE_RANGE.1
+                },
+                "output_directory": { "type": "string" },
+                "output_sampling_rate": {
+                    "type": "integer",
+                    "minimum": SAMPLING_RATE_RANGE.0,
+                    "maximum": SAMPLING_RATE_RANGE.1
+                },
+                "output_bit_depth": { "type": "integer", "enum": OUTPUT_BIT_DEPTHS },
+                "seed": { "type": ["integer", "null"], "minimum": 0 },
+                "overwrite": { "type": "string", "enum": ["always", "never", "increment"] },
+                "output_file_template": { "type": "string", "minLength": 1 },
+                "sample_search_paths": { "type": "array", "items": { "type": "string" } },
+                "variations": {
+                    "type": "integer",
+                    "minimum": VARIATIONS_RANGE.0,
+                    "maximum": VARIATIONS_RANGE.1
+                },
+                "export_events": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["csv", "json", "midi"] },
+                    "uniqueItems": true
+                }
+            }
+        });
+
+        return with_defaults(
+            schema,
+            json!({
+                "beat_length_ms": default_beat_length_ms(),
+                "time_signature": default_time_signature(),
+                "tempo_map": [],
+                "engine_sampling_rate": default_engine_sampling_rate(),
+                "output_directory": default_output_directory(),
+                "output_bit_depth": default_output_bit_depth(),
+                "seed": null,
+                "overwrite": "always",
+                "output_file_template": default_output_file_template(),
+                "sample_search_paths": [],
+                "variations": default_variations(),
+                "export_events": []
+            }),
+        );
+    }
+
+    // Schemat właściwości ścieżki, pominięta 'track_name' przyjmuje postać 'track_{numer ścieżki}'.
+    fn track_properties_schema() -> Value {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "track_name": { "type": "string", "minLength": 1, "pattern": "^(?!\\.{1,2}$)[^/\\\\]+$" },
+                "track_normalization_level": {
+                    "type": "number",
+                    "minimum": TRACK_NORMALIZATION_LEVEL_RANGE.0,
+                    "maximum": TRACK_NORMALIZATION_LEVEL_RANGE.1
+                },
+                "track_panorama": {
+                    "type": "number",
+                    "minimum": TRACK_PANORAMA_RANGE.0,
+                    "maximum": TRACK_PANORAMA_RANGE.1
+                }
+            }
+        });
+
+        return with_defaults(
+            schema,
+            json!({
+                "track_normalization_level": default_track_normalization_level(),
+                "track_panorama": 0.0
+            }),
+        );
+    }
+
+    // Schemat właściwości granulek.
+    fn grains_properties_schema() -> Value {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "sample_file_path": { "type": "string", "minLength": 1 },
+                "grains_count": {
+                    "type": "integer",
+                    "minimum": GRAINS_COUNT_RANGE.0,
+                    "maximum": GRAINS_COUNT_RANGE.1
+                },
+                "grains_length_ms": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "required": ["type", "equal"],
+                            "properties": {
+                                "type": { "const": "Fixed" },
+                                "equal": { "type": "number", "minimum": GRAINS_LENGTH_MS_MIN }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "required": ["type", "from", "to"],
+                            "properties": {
+                                "type": { "const": "Range" },
+                                "from": { "type": "number", "minimum": GRAINS_LENGTH_MS_MIN },
+                                "to": { "type": "number", "minimum": GRAINS_LENGTH_MS_MIN }
+                            }
+                        }
+                    ]
+                },
+                "window_function": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "required": ["type", "slope"],
+                            "properties": {
+                                "type": { "const": "SmoothstepRegular" },
+                                "slope": { "type": "number", "minimum": WINDOW_SLOPE_MIN }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "required": ["type", "slope_attack", "slope_release"],
+                            "properties": {
+                                "type": { "const": "SmoothstepUnregular" },
+                                "slope_attack": { "type": "number", "minimum": WINDOW_SLOPE_MIN },
+                                "slope_release": { "type": "number", "minimum": WINDOW_SLOPE_MIN }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "required": ["type"],
+                            "properties": { "type": { "const": "Symmetric" } }
+                        }
+                    ]
+                },
+                "grains_laudness_normalization": { "type": "boolean" },
+                "grains_pitch": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "required": ["type"],
+                            "properties": { "type": { "const": "Fixed" } }
+                        },
+                        {
+                            "type": "object",
+                            "required": ["type", "steps"],
+                            "properties": {
+                                "type": { "const": "Steps" },
+                                "steps": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "array",
+                                        "prefixItems": [
+                                            {
+                                                "type": "number",
+                                                "minimum": GRAINS_PITCH_RANGE.0,
+                                                "maximum": GRAINS_PITCH_RANGE.1
+                                            },
+                                            {
+                                                "type": "number",
+                                                "minimum": PERCENTAGE_RANGE.0,
+                                                "maximum": PERCENTAGE_RANGE.1
+                                            }
+                                        ],
+                                        "minItems": 2,
+                                        "maxItems": 2
+                                    }
+                                }
+                            }
+                        }
+                    ]
+                },
+                "grains_alignment": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "required": ["type"],
+                            "properties": { "type": { "const": "Random" } }
+                        },
+                        {
+                            "type": "object",
+                            "required": ["type"],
+                            "properties": {
+                                "type": { "const": "Onsets" },
+                                "jitter_ms": {
+                                    "type": "number",
+                                    "minimum": ONSET_JITTER_MS_RANGE.0,
+                                    "maximum": ONSET_JITTER_MS_RANGE.1,
+                                    "default": default_onset_jitter_ms()
+                                },
+                                "threshold": {
+                                    "type": "number",
+                                    "minimum": ONSET_THRESHOLD_RANGE.0,
+                                    "maximum": ONSET_THRESHOLD_RANGE.1,
+                                    "default": default_onset_threshold()
+                                },
+                                "min_interval_ms": {
+                                    "type": "number",
+                                    "minimum": ONSET_MIN_INTERVAL_MS_RANGE.0,
+                                    "maximum": ONSET_MIN_INTERVAL_MS_RANGE.1,
+                                    "default": default_onset_min_interval_ms()
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "required": ["type"],
+                            "properties": { "type": { "const": "ZeroCrossing" } }
+                        }
+                    ]
+                },
+                "region": sample_region_schema(),
+                "regions": { "type": "array", "items": sample_region_schema() },
+                "sources": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": sample_source_schema()
+                },
+                "descriptor_selection": descriptor_selection_schema()
+            }
+        });
+
+        return with_defaults(
+            schema,
+            json!({
+                "grains_count": default_grains_count(),
+                "grains_length_ms": default_grains_length_ms(),
+                "window_function": default_window_function(),
+                "grains_laudness_normalization": default_grains_laudness_normalization(),
+                "grains_pitch": default_grains_pitch(),
+                "grains_alignment": default_grains_alignment()
+            }),
+        );
+    }
+
+    // Schemat odchylenia: liczba (rozkład jednostajny) lub rozkład prawdopodobieństwa, '_limit' to maksymalne odchylenie w procentach.
+    fn deviation_schema(_limit: f64) -> Value {
+        let percents = json!({ "type": "number", "minimum": 0.0, "maximum": _limit });
+        let distribution = |_type: &str, _required: Value, _properties: Value| {
+            let mut properties = _properties;
+
+            properties["type"] = json!({ "const": _type });
+
+            return json!({
+                "type": "object",
+                "required": _required,
+                "properties": properties
+            });
+        };
+
+        return json!({
+            "oneOf": [
+                percents.clone(),
+                distribution("Uniform", json!(["type", "percents"]), json!({ "percents": percents })),
+                distribution(
+                    "Gaussian",
+                    json!(["type", "sigma_percents"]),
+                    json!({ "sigma_percents": { "type": "number", "exclusiveMinimum": 0.0, "maximum": _limit } })
+                ),
+                distribution(
+                    "Triangular",
+                    json!(["type", "percents"]),
+                    json!({
+                        "percents": percents,
+                        "mode_percents": { "type": "number", "minimum": -_limit, "maximum": _limit, "default": 0.0 }
+                    })
+                ),
+                distribution(
+                    "Beta",
+                    json!(["type", "percents", "alpha", "beta"]),
+                    json!({
+                        "percents": percents,
+                        "alpha": { "type": "number", "exclusiveMinimum": 0.0 },
+                        "beta": { "type": "number", "exclusiveMinimum": 0.0 }
+                    })
+                ),
+                distribution(
+                    "Discrete",
+                    json!(["type", "values"]),
+                    json!({
+                        "values": {
+                            "type": "array",
+                            "minItems": 1,
+                            "items": {
+                                "type": "array",
+                                "prefixItems": [
+                                    { "type": "number", "minimum": -_limit, "maximum": _limit },
+                                    { "type": "number", "minimum": 0.0 }
+                                ],
+                                "minItems": 2,
+                                "maxItems": 2
+                            }
+                        }
+                    })
+                )
+            ]
+        });
+    }
+
+    // Schemat szablonu groove.
+    fn groove_template_schema() -> Value {
+        return json!({
+            "type": "object",
+            "required": ["steps"],
+            "properties": {
+                "steps": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "timing_percent": {
+                                "type": "number",
+                                "minimum": GROOVE_TIMING_PERCENT_RANGE.0,
+                                "maximum": GROOVE_TIMING_PERCENT_RANGE.1,
+                                "default": 0.0
+                            },
+                            "velocity_percent": {
+                                "type": "number",
+                                "minimum": GROOVE_VELOCITY_PERCENT_RANGE.0,
+                                "maximum": GROOVE_VELOCITY_PERCENT_RANGE.1,
+                                "default": 0.0
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    // Schemat konfiguracji beatu.
+    fn beat_configuration_schema() -> Value {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "subdivisions": { "type": "integer", "minimum": 1 },
+                "coverage_percentage": {
+                    "type": "number",
+                    "minimum": PERCENTAGE_RANGE.0,
+                    "maximum": PERCENTAGE_RANGE.1
+                },
+                "humanization_percents": deviation_schema(HUMANIZATION_PERCENTS_RANGE.1),
+                "volume_deviation_percents": deviation_schema(PERCENTAGE_RANGE.1),
+                "panorama_deviation_percents": deviation_schema(PERCENTAGE_RANGE.1),
+                "duration": musical_duration_schema(),
+                "grains_pitch_multiplier": {
+                    "type": "number",
+                    "minimum": GRAINS_PITCH_RANGE.0,
+                    "maximum": GRAINS_PITCH_RANGE.1
+                },
+                "grains_length_multiplier": {
+                    "type": "number",
+                    "minimum": GRAINS_LENGTH_MULTIPLIER_RANGE.0,
+                    "maximum": GRAINS_LENGTH_MULTIPLIER_RANGE.1
+                },
+                "transition": { "enum": ["step", "linear", "exponential"] },
+                "swing_percent": {
+                    "type": "number",
+                    "minimum": SWING_PERCENT_RANGE.0,
+                    "maximum": SWING_PERCENT_RANGE.1
+                },
+                "groove": {
+                    "oneOf": [
+                        { "type": "string", "minLength": 1 },
+                        {
+                            "type": "object",
+                            "required": ["file"],
+                            "properties": { "file": { "type": "string", "minLength": 1 } },
+                            "additionalProperties": false
+                        },
+                        groove_template_schema()
+                    ]
+                }
+            }
+        });
+
+        return with_defaults(
+            schema,
+            json!({
+                "subdivisions": default_subdivisions(),
+                "coverage_percentage": default_coverage_percentage(),
+                "humanization_percents": 0.0,
+                "volume_deviation_percents": 0.0,
+                "panorama_deviation_percents": 0.0,
+                "swing_percent": 0.0,
+                "grains_pitch_multiplier": default_grains_pitch_multiplier(),
+                "grains_length_multiplier": default_grains_length_multiplier(),
+                "transition": "step"
+            }),
+        );
+    }
+
+    // Schemat łańcucha Markowa generującego sekwencję beatów.
+    fn markov_chain_schema() -> Value {
+        return json!({
+            "type": "object",
+            "required": ["states", "transitions"],
+            "properties": {
+                "states": {
+                    "type": "object",
+                    "minProperties": 1,
+                    "additionalProperties": with_preset(beat_configuration_schema())
+                },
+                "transitions": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "object",
+                        "minProperties": 1,
+                        "additionalProperties": { "type": "number", "minimum": 0.0 }
+                    }
+                },
+                "initial": { "type": "string", "minLength": 1 },
+                "beats": {
+                    "type": "integer",
+                    "minimum": MARKOV_BEATS_RANGE.0,
+                    "maximum": MARKOV_BEATS_RANGE.1
+                },
+                "duration": musical_duration_schema()
+            },
+            "oneOf": [{ "required": ["beats"] }, { "required": ["duration"] }]
+        });
+    }
+
+    // Schemat sekwencji zdarzeń wczytywanej z pliku MIDI.
+    fn midi_sequence_schema() -> Value {
+        return json!({
+            "type": "object",
+            "required": ["file"],
+            "properties": {
+                "file": { "type": "string", "minLength": 1 },
+                "track": { "type": "integer", "minimum": 1 },
+                "channel": {
+                    "type": "integer",
+                    "minimum": MIDI_CHANNEL_RANGE.0,
+                    "maximum": MIDI_CHANNEL_RANGE.1
+                },
+                "root_note": {
+                    "type": "integer",
+                    "minimum": MIDI_NOTE_RANGE.0,
+                    "maximum": MIDI_NOTE_RANGE.1,
+                    "default": default_midi_root_note()
+                },
+                "follow_pitch": { "type": "boolean", "default": default_midi_follow_pitch() }
+            }
+        });
+    }
+
+    // Schemat fragmentu sampla: zakres czasu albo etykieta znacznika pliku WAV.
+    fn sample_region_schema() -> Value {
+        return json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["start_ms", "end_ms"],
+                    "properties": {
+                        "start_ms": { "type": "number", "minimum": 0.0 },
+                        "end_ms": { "type": "number", "exclusiveMinimum": 0.0 }
+                    },
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "required": ["marker"],
+                    "properties": { "marker": { "type": "string", "minLength": 1 } },
+                    "additionalProperties": false
+                }
+            ]
+        });
+    }
+
+    // Schemat sampla źródłowego ścieżki.
+    fn sample_source_schema() -> Value {
+        return json!({
+            "type": "object",
+            "required": ["file_path"],
+            "properties": {
+                "file_path": { "type": "string", "minLength": 1 },
+                "weight": curve_schema(),
+                "pitch": {
+                    "type": "number",
+                    "minimum": GRAINS_PITCH_RANGE.0,
+                    "maximum": GRAINS_PITCH_RANGE.1,
+                    "default": default_source_pitch()
+                },
+                "gain": {
+                    "type": "number",
+                    "exclusiveMinimum": SOURCE_GAIN_RANGE.0,
+                    "maximum": SOURCE_GAIN_RANGE.1,
+                    "default": default_source_gain()
+                },
+                "region": sample_region_schema(),
+                "regions": { "type": "array", "items": sample_region_schema() }
+            }
+        });
+    }
+
+    // Schemat krzywej wartości w funkcji położenia w ścieżce: liczba, zmiana liniowa {from, to}
+    // lub lista punktów [położenie w ścieżce, wartość].
+    fn curve_schema() -> Value {
+        return json!({
+            "oneOf": [
+                { "type": "number" },
+                {
+                    "type": "object",
+                    "required": ["from", "to"],
+                    "properties": { "from": { "type": "number" }, "to": { "type": "number" } }
+                },
+                {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "array",
+                        "prefixItems": [
+                            { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                            { "type": "number" }
+                        ],
+                        "minItems": 2,
+                        "maxItems": 2
+                    }
+                }
+            ]
+        });
+    }
+
+    // Schemat wyboru granulek na podstawie deskryptorów: wartość docelowa deskryptora jest krzywą.
+    fn descriptor_selection_schema() -> Value {
+        let curve = curve_schema();
+
+        return json!({
+            "type": "object",
+            "required": ["targets"],
+            "properties": {
+                "targets": {
+                    "type": "object",
+                    "minProperties": 1,
+                    "properties": {
+                        "rms": curve,
+                        "centroid": curve,
+                        "zero_crossing_rate": curve,
+                        "flatness": curve,
+                        "pitch": curve
+                    },
+                    "additionalProperties": false
+                },
+                "neighbours": {
+                    "type": "integer",
+                    "minimum": DESCRIPTOR_NEIGHBOURS_RANGE.0,
+                    "maximum": DESCRIPTOR_NEIGHBOURS_RANGE.1,
+                    "default": default_descriptor_neighbours()
+                }
+            }
+        });
+    }
+
+    // Schemat ustawień analizy obwiedni pliku 'guide_file'.
+    fn guide_envelope_schema() -> Value {
+        return json!({
+            "type": "object",
+            "properties": {
+                "window_ms": {
+                    "type": "number",
+                    "minimum": GUIDE_WINDOW_MS_RANGE.0,
+                    "maximum": GUIDE_WINDOW_MS_RANGE.1,
+                    "default": default_guide_window_ms()
+                },
+                "density_percents": {
+                    "type": "number",
+                    "minimum": PERCENTAGE_RANGE.0,
+                    "maximum": PERCENTAGE_RANGE.1,
+                    "default": default_guide_depth_percents()
+                },
+                "volume_percents": {
+                    "type": "number",
+                    "minimum": PERCENTAGE_RANGE.0,
+                    "maximum": PERCENTAGE_RANGE.1,
+                    "default": default_guide_depth_percents()
+                }
+            }
+        });
+    }
+
+    // Schemat mozaiki: plik dźwięku docelowego, podział na ramki i cechy dopasowania granulek.
+    fn mosaic_schema() -> Value {
+        return json!({
+            "type": "object",
+            "required": ["target_file"],
+            "properties": {
+                "target_file": { "type": "string", "minLength": 1 },
+                "frame_ms": {
+                    "type": "number",
+                    "minimum": MOSAIC_FRAME_MS_RANGE.0,
+                    "maximum": MOSAIC_FRAME_MS_RANGE.1,
+                    "default": default_mosaic_frame_ms()
+                },
+                "hop_ms": {
+                    "type": "number",
+                    "minimum": 1.0,
+                    "maximum": MOSAIC_FRAME_MS_RANGE.1
+                },
+                "features": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": { "enum": ["mfcc", "centroid", "loudness"] },
+                    "default": default_mosaic_features()
+                },
+                "neighbours": {
+                    "type": "integer",
+                    "minimum": DESCRIPTOR_NEIGHBOURS_RANGE.0,
+                    "maximum": DESCRIPTOR_NEIGHBOURS_RANGE.1,
+                    "default": default_descriptor_neighbours()
+                },
+                "match_loudness": { "type": "boolean", "default": default_mosaic_match_loudness() },
+                "silence_db": {
+                    "type": "number",
+                    "minimum": MOSAIC_SILENCE_DB_RANGE.0,
+                    "maximum": MOSAIC_SILENCE_DB_RANGE.1,
+                    "default": default_mosaic_silence_db()
+                }
+            }
+        });
+    }
+
+    // Schemat syntezy synchronicznej z okresem: długość granulek w okresach, zmiana wysokości i rozciągnięcie w czasie.
+    fn pitch_synchronous_schema() -> Value {
+        return json!({
+            "type": "object",
+            "properties": {
+                "periods": {
+                    "type": "integer",
+                    "minimum": PSOLA_PERIODS_RANGE.0,
+                    "maximum": PSOLA_PERIODS_RANGE.1,
+                    "default": default_psola_periods()
+                },
+                "pitch_shift": {
+                    "type": "number",
+                    "minimum": GRAINS_PITCH_RANGE.0,
+                    "maximum": GRAINS_PITCH_RANGE.1,
+                    "default": default_psola_pitch_shift()
+                },
+                "time_stretch": {
+                    "type": "number",
+                    "minimum": PSOLA_TIME_STRETCH_RANGE.0,
+                    "maximum": PSOLA_TIME_STRETCH_RANGE.1,
+                    "default": default_psola_time_stretch()
+                },
+                "yin_threshold": {
+                    "type": "number",
+                    "minimum": YIN_THRESHOLD_RANGE.0,
+                    "maximum": YIN_THRESHOLD_RANGE.1,
+                    "default": default_yin_threshold()
+                }
+            }
+        });
+    }
+
+    // Schemat ścieżki.
+    fn track_schema() -> Value {
+        let mut beat_schema = with_preset(beat_configuration_schema());
+
+        beat_schema["properties"]["repeat"] = json!({ "type": "integer", "minimum": 1 });
+
+        return json!({
+            "type": "object",
+            "anyOf": [
+                { "required": ["beat_sequence"] },
+                { "required": ["markov"] },
+                { "required": ["midi"] },
+                { "required": ["sequence_file"] },
+                { "required": ["mosaic"] },
+                { "required": ["pitch_synchronous"] }
+            ],
+            "properties": {
+                "track_properties": track_properties_schema(),
+                "grains_properties": with_preset(grains_properties_schema()),
+                "beat_sequence": {
+                    "type": "array",
+                    "items": beat_schema
+                },
+                "markov": markov_chain_schema(),
+                "midi": midi_sequence_schema(),
+                "sequence_file": { "type": "string", "minLength": 1 },
+                "guide_file": { "type": "string", "minLength": 1 },
+                "guide_envelope": guide_envelope_schema(),
+                "mosaic": mosaic_schema(),
+                "pitch_synchronous": pitch_synchronous_schema()
+            }
+        });
+    }
+
+    // Dopuszcza odwołanie do szablonu ('preset'), zmienne wymagane mogą wtedy pochodzić z szablonu.
+    fn with_preset(mut _schema: Value) -> Value {
+        if let Some(_required) = _schema
+            .as_object_mut()
+            .and_then(|_schema| _schema.remove("required"))
+        {
+            _schema["anyOf"] = json!([{ "required": _required }, { "required": ["preset"] }]);
+        }
+
+        _schema["properties"]["preset"] = json!({ "type": "string", "minLength": 1 });
+
+        return _schema;
+    }
+
+    // Schemat szablonu, wszystkie zmienne szablonu są opcjonalne.
+    fn preset_schema(mut _schema: Value) -> Value {
+        if let Some(_schema) = _schema.as_object_mut() {
+            _schema.remove("required");
+        }
+
+        return _schema;
+    }
+
+    // Schemat parametru przeglądu.
+    fn sweep_parameter_schema() -> Value {
+        return json!({
+            "type": "object",
+            "required": ["pointer"],
+            "properties": {
+                "pointer": { "type": "string", "pattern": "^/" },
+                "values": { "type": "array", "minItems": 1 },
+                "range": {
+                    "type": "object",
+                    "required": ["from", "to", "step"],
+                    "properties": {
+                        "from": { "type": "number" },
+                        "to": { "type": "number" },
+                        "step": { "type": "number", "exclusiveMinimum": 0 }
+                    }
+                }
+            },
+            "oneOf": [{ "required": ["values"] }, { "required": ["range"] }]
+        });
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Buduje schemat JSON (draft 2020-12) pliku konfiguracyjnego wraz z zakresami wartości sprawdzanymi podczas walidacji.
+    pub fn configuration_schema() -> Value {
+        return json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "granular_synth",
+            "type": "object",
+            "required": ["Tracks"],
+            "properties": {
+                "SynthConfiguration": synth_configuration_schema(),
+                "include": {
+                    "oneOf": [
+                        { "type": "string", "minLength": 1 },
+                        { "type": "array", "items": { "type": "string", "minLength": 1 } }
+                    ]
+                },
+                "defaults": {
+                    "type": "object",
+                    "properties": {
+                        "track_properties": preset_schema(track_properties_schema()),
+                        "grains_properties": preset_schema(grains_properties_schema()),
+                        "beat_sequence": preset_schema(beat_configuration_schema())
+                    },
+                    "additionalProperties": false
+                },
+                "Presets": {
+                    "type": "object",
+                    "properties": {
+                        "tracks": {
+                            "type": "object",
+                            "additionalProperties": preset_schema(track_schema())
+                        },
+                        "grains": {
+                            "type": "object",
+                            "additionalProperties": preset_schema(grains_properties_schema())
+                        },
+                        "beats": {
+                            "type": "object",
+                            "additionalProperties": preset_schema(beat_configuration_schema())
+                        },
+                        "grooves": {
+                            "type": "object",
+                            "additionalProperties": groove_template_schema()
+                        }
+                    }
+                },
+                "Tracks": {
+                    "type": "array",
+                    "items": with_preset(track_schema())
+                },
+                "Sweep": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": sweep_parameter_schema()
+                }
+            }
+        });
+    }
+}
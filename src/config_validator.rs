@@ -0,0 +1,827 @@
+pub mod tools {
+    use std::fs::read_to_string;
+    use std::path::Path;
+
+    use serde_json::{from_str, Value};
+
+    use crate::audio::tools::AudioBuffer;
+    use crate::cli::tools::ConfigOverrides;
+    use crate::config_expansion::tools::expand_configuration;
+    use crate::descriptors::core::{MosaicTarget, PitchMarks};
+    use crate::events_export::tools::{check_event_sources, load_event_list};
+    use crate::granular_synth_config::tools::{
+        load_config_file, load_tracks_configurations, resolve_configuration_paths, ConfigError,
+        ConfigFormat,
+        GrainsLength, GrainsPitch, SynthConfiguration, Track,
+    };
+    use crate::markers::tools::checked_source_regions;
+    use crate::midi::core::load_midi_notes;
+    use crate::sequencer::core::{walk_markov_chain, Envelope, Sequencer};
+    use crate::sweep::tools::Sweep;
+    use crate::timeline::core::Timeline;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Lokalizator wartości w tekście pliku .json na podstawie wskaźnika JSON.
+    struct JsonLocator {
+        characters: Vec<char>,
+        index: usize,
+        line: usize,
+        column: usize,
+    }
+
+    impl JsonLocator {
+        fn new(_source: &str) -> Self {
+            return JsonLocator {
+                characters: _source.chars().collect(),
+                index: 0,
+                line: 1,
+                column: 1,
+            };
+        }
+
+        // Przesuwa kursor o jeden znak, aktualizując numer linii i kolumny.
+        fn advance(&mut self) -> Option<char> {
+            let character = *self.characters.get(self.index)?;
+
+            self.index += 1;
+
+            if character == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+
+            return Some(character);
+        }
+
+        fn peek(&self) -> Option<char> {
+            return self.characters.get(self.index).cloned();
+        }
+
+        fn skip_whitespace(&mut self) {
+            while let Some(_character) = self.peek() {
+                if _character.is_whitespace() == false {
+                    break;
+                }
+
+                self.advance();
+            }
+        }
+
+        // Wczytuje łańcuch znaków (kursor ustawiony na otwierającym cudzysłowie).
+        fn read_string(&mut self) -> String {
+            let mut content = String::new();
+
+            self.advance();
+
+            while let Some(_character) = self.advance() {
+                match _character {
+                    '"' => break,
+                    '\\' => match self.advance() {
+                        Some('n') => content.push('\n'),
+                        Some('t') => content.push('\t'),
+                        Some('r') => content.push('\r'),
+                        Some('b') => content.push('\u{8}'),
+                        Some('f') => content.push('\u{c}'),
+                        Some('u') => {
+                            let code: String = (0..4).filter_map(|_| self.advance()).collect();
+
+                            match u32::from_str_radix(&code, 16)
+                                .ok()
+                                .and_then(std::char::from_u32)
+                            {
+                                Some(_decoded) => content.push(_decoded),
+                                None => {}
+                            }
+                        }
+                        Some(_escaped) => content.push(_escaped),
+                        None => break,
+                    },
+                    _ => content.push(_character),
+                }
+            }
+
+            return content;
+        }
+
+        // Pomija dowolną wartość (liczbę, literał, łańcuch, obiekt lub tablicę).
+        fn skip_value(&mut self) {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some('"') => {
+                    self.read_string();
+                }
+                Some('{') | Some('[') => {
+                    let mut depth: usize = 0;
+
+                    while let Some(_character) = self.peek() {
+                        match _character {
+                            '"' => {
+                                self.read_string();
+                                continue;
+                            }
+                            '{' | '[' => depth += 1,
+                            '}' | ']' => {
+                                depth -= 1;
+
+                                if depth == 0 {
+                                    self.advance();
+                                    break;
+                                }
+                            }
+                            _ => {}
+                        }
+
+                        self.advance();
+                    }
+                }
+                _ => {
+                    while let Some(_character) = self.peek() {
+                        if matches!(_character, ',' | '}' | ']') || _character.is_whitespace() {
+                            break;
+                        }
+
+                        self.advance();
+                    }
+                }
+            }
+        }
+
+        // Wyszukuje wartość wskazaną przez kolejne segmenty wskaźnika. Jeżeli wartość nie istnieje,
+        // zwraca położenie najgłębszej istniejącej wartości nadrzędnej.
+        fn locate(&mut self, _segments: &[String]) -> Option<(usize, usize)> {
+            self.skip_whitespace();
+
+            let position = (self.line, self.column);
+
+            if _segments.is_empty() == true {
+                return Some(position);
+            }
+
+            match self.peek() {
+                Some('{') => {
+                    self.advance();
+
+                    loop {
+                        self.skip_whitespace();
+
+                        match self.peek() {
+                            Some('"') => {}
+                            _ => return Some(position),
+                        }
+
+                        let key = self.read_string();
+
+                        self.skip_whitespace();
+                        self.advance();
+
+                        if key == _segments[0] {
+                            return self.locate(&_segments[1..]).or(Some(position));
+                        }
+
+                        self.skip_value();
+                        self.skip_whitespace();
+
+                        match self.advance() {
+                            Some(',') => {}
+                            _ => return Some(position),
+                        }
+                    }
+                }
+                Some('[') => {
+                    self.advance();
+
+                    let wanted_index: usize = match _segments[0].parse() {
+                        Ok(_index) => _index,
+                        Err(_) => return Some(position),
+                    };
+                    let mut current_index: usize = 0;
+
+                    loop {
+                        self.skip_whitespace();
+
+                        if self.peek() == Some(']') {
+                            return Some(position);
+                        }
+                        if current_index == wanted_index {
+                            return self.locate(&_segments[1..]).or(Some(position));
+                        }
+
+                        self.skip_value();
+                        self.skip_whitespace();
+
+                        match self.advance() {
+                            Some(',') => current_index += 1,
+                            _ => return Some(position),
+                        }
+                    }
+                }
+                _ => {
+                    return Some(position);
+                }
+            }
+        }
+    }
+
+    // Wyznacza linię i kolumnę (numerowane od 1) wartości wskazanej przez wskaźnik JSON.
+    pub fn json_pointer_position(_source: &str, _pointer: &str) -> Option<(usize, usize)> {
+        let segments: Vec<String> = _pointer
+            .split('/')
+            .skip(1)
+            .map(|_segment| _segment.replace("~1", "/").replace("~0", "~"))
+            .collect();
+
+        return JsonLocator::new(_source).locate(&segments);
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Treść pliku konfiguracyjnego i jej obiekt Value przed rozwinięciem, używane do wyznaczania położenia błędów.
+    // Położenie (linia, kolumna) wyznaczane jest wyłącznie dla plików .json - parsery JSON5, TOML i YAML
+    // nie udostępniają położenia wartości w tekście pliku.
+    pub struct ConfigSource {
+        format: Option<ConfigFormat>,
+        text: String,
+        value: Value,
+    }
+
+    impl ConfigSource {
+        // Wczytuje treść pliku konfiguracyjnego, błąd odczytu lub parsowania oznacza brak informacji o położeniu.
+        pub fn load(_json_file_path: Option<&String>) -> Self {
+            let json_file_path = match _json_file_path {
+                Some(_json_file_path) => _json_file_path,
+                None => {
+                    return ConfigSource::from_text(None, String::new());
+                }
+            };
+
+            match ConfigFormat::from_path(json_file_path) {
+                Ok(ConfigFormat::Json) => {
+                    let text = read_to_string(Path::new(json_file_path)).unwrap_or_default();
+
+                    return ConfigSource::from_text(Some(ConfigFormat::Json), text);
+                }
+                Ok(_config_format) => {
+                    return ConfigSource::from_text(Some(_config_format), String::new());
+                }
+                Err(_) => {
+                    return ConfigSource::from_text(None, String::new());
+                }
+            }
+        }
+
+        fn from_text(_format: Option<ConfigFormat>, _text: String) -> Self {
+            let value = match _format {
+                Some(ConfigFormat::Json) => from_str(&_text).unwrap_or(Value::Null),
+                _ => Value::Null,
+            };
+
+            return ConfigSource {
+                format: _format,
+                text: _text,
+                value,
+            };
+        }
+
+        // Położenie w pliku wartości wskazanej przez wskaźnik błędu. Wskaźnik odnosi się do konfiguracji _value
+        // (po rozwinięciu szablonów, powtórzeń i nadpisań) lub - gdy jej brak - do treści pliku, dlatego położenie
+        // jest wyznaczane tylko wtedy, gdy najgłębsza istniejąca wartość na drodze wskaźnika występuje w pliku
+        // pod tym samym wskaźnikiem i bez zmian.
+        fn position(&self, _pointer: &str, _value: Option<&Value>) -> Option<(usize, usize)> {
+            if self.text.is_empty() == true || self.value.is_null() == true {
+                return None;
+            }
+
+            let value = _value.unwrap_or(&self.value);
+            let pointer = existing_pointer(value, _pointer);
+
+            match (value.pointer(&pointer), self.value.pointer(&pointer)) {
+                (Some(_value), Some(_source_value)) if _value == _source_value => {
+                    return json_pointer_position(&self.text, &pointer);
+                }
+                _ => {
+                    return None;
+                }
+            }
+        }
+    }
+
+    // Wskaźnik najgłębszej wartości istniejącej w obiekcie Value na drodze wskazanej przez wskaźnik JSON.
+    fn existing_pointer(_value: &Value, _pointer: &str) -> String {
+        let mut pointer = String::new();
+
+        for _segment in _pointer.split('/').skip(1) {
+            let next_pointer = format!("{}/{}", pointer, _segment);
+
+            if _value.pointer(&next_pointer).is_none() == true {
+                break;
+            }
+
+            pointer = next_pointer;
+        }
+
+        return pointer;
+    }
+
+    // Formatuje błąd konfiguracji wraz ze wskaźnikiem JSON i położeniem w pliku źródłowym.
+    pub fn describe_error(_error: &ConfigError, _source: &ConfigSource, _value: Option<&Value>) -> String {
+        match (_source.position(&_error.pointer, _value), _source.format) {
+            (Some((_line, _column)), _) => {
+                return format!(
+                    "{}\n\twskaźnik: \'{}\' (linia: {}, kolumna: {})",
+                    _error.message, _error.pointer, _line, _column
+                );
+            }
+            (None, Some(ConfigFormat::Json)) | (None, None) => {
+                return format!("{}\n\twskaźnik: \'{}\'", _error.message, _error.pointer);
+            }
+            (None, Some(_)) => {
+                return format!(
+                    "{}\n\twskaźnik: \'{}\' (położenie w pliku wyznaczane jest tylko dla plików .json)",
+                    _error.message, _error.pointer
+                );
+            }
+        }
+    }
+
+    // Zamienia listę błędów konfiguracji na listę komunikatów.
+    pub fn config_errors_messages(
+        _errors: &Vec<ConfigError>,
+        _source: &ConfigSource,
+        _value: Option<&Value>,
+    ) -> Vec<String> {
+        return _errors
+            .iter()
+            .map(|_error| describe_error(_error, _source, _value))
+            .collect();
+    }
+
+    // Szacunkowy rozmiar ścieżki po syntezie: długość w sekundach i zużycie pamięci w bajtach.
+    // Długość syntezy ścieżki (w próbkach): na podstawie sekwencji beatów, przykładowej sekwencji łańcucha Markowa,
+    // nut pliku MIDI, wykazu zdarzeń, ramek dźwięku docelowego mozaiki lub znaczników okresów sampla (wczytanie pliku
+    // sprawdza także jego poprawność).
+    fn track_canva_size(
+        _track: &Track,
+        _track_number: usize,
+        _synth_configuration: &SynthConfiguration,
+    ) -> Result<usize, String> {
+        if let Some(_sequence_file) = &_track.sequence_file {
+            let event_list = match load_event_list(_sequence_file) {
+                Ok(_event_list) => _event_list,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let mut sequencer = Sequencer::default();
+
+            sequencer.replay_sequence(&event_list, _synth_configuration);
+
+            return Ok(sequencer.length);
+        }
+
+        if let Some(_mosaic) = &_track.mosaic {
+            let mosaic_target = match MosaicTarget::load(_mosaic) {
+                Ok(_mosaic_target) => _mosaic_target,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let mut sequencer = Sequencer::default();
+
+            sequencer.generate_mosaic_sequence(&mosaic_target, _mosaic, _synth_configuration);
+
+            return Ok(sequencer.length);
+        }
+
+        if let Some(_pitch_synchronous) = &_track.pitch_synchronous {
+            let pitch_marks = match PitchMarks::load(&_track.grains_properties, _pitch_synchronous, _track_number) {
+                Ok(_pitch_marks) => _pitch_marks,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let mut sequencer = Sequencer::default();
+
+            sequencer.generate_pitch_synchronous_sequence(&pitch_marks, _pitch_synchronous, _synth_configuration);
+
+            return Ok(sequencer.length);
+        }
+
+        if let Some(_midi) = &_track.midi {
+            let midi_notes = match load_midi_notes(_midi) {
+                Ok(_midi_notes) => _midi_notes,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let mut sequencer = Sequencer::default();
+
+            sequencer.generate_midi_sequence(&midi_notes, _midi, _synth_configuration);
+
+            return Ok(sequencer.length);
+        }
+
+        if let Some(_markov) = &_track.markov {
+            let (beat_sequence, _) = walk_markov_chain(
+                _markov,
+                _synth_configuration,
+                _synth_configuration.seed.unwrap_or(0),
+            );
+
+            return Ok(Timeline::new(_synth_configuration).render_length(&beat_sequence));
+        }
+
+        return Ok(Timeline::new(_synth_configuration).render_length(&_track.beat_sequence));
+    }
+
+    fn estimate_track(
+        _track: &Track,
+        _synth_configuration: &SynthConfiguration,
+        _canva_size: usize,
+        _samples: &Vec<(usize, u32, usize)>,
+    ) -> (f64, usize) {
+        let engine_rate = _synth_configuration.engine_sampling_rate as f64;
+
+        let canva_size = _canva_size as f64;
+        let render_length_s = canva_size / engine_rate;
+
+        // Granulki mają liczbę kanałów najszerszego sampla źródłowego.
+        let channels = _samples.iter().map(|(_, _, _channels)| *_channels).max().unwrap_or(1);
+
+        let average_grain_ms = match _track.grains_properties.grains_length_ms {
+            GrainsLength::Fixed { equal } => equal,
+            GrainsLength::Range { from, to } => (from + to) / 2.0,
+        };
+        let grains_size = _track.grains_properties.grains_count as f64
+            * (average_grain_ms / 1000.0)
+            * engine_rate
+            * channels as f64;
+
+        // Sampel przepróbkowany dla każdej wysokości dźwięku jest przechowywany przez cały czas syntezy.
+        let pitch_buffers_factor: f64 = match &_track.grains_properties.grains_pitch {
+            GrainsPitch::Fixed => 1.0,
+            GrainsPitch::Steps { steps } => steps.iter().map(|_step| 1.0 / _step.0).sum(),
+        };
+        let sources = _track.grains_properties.sample_sources();
+        let resampled_size: f64 = _samples
+            .iter()
+            .zip(sources.iter())
+            .map(|((_sample_length, _sample_sampling_rate, _sample_channels), _sample_source)| {
+                (*_sample_length as f64 * *_sample_channels as f64)
+                    * (engine_rate / *_sample_sampling_rate as f64)
+                    * pitch_buffers_factor
+                    / _sample_source.pitch
+            })
+            .sum();
+        let samples_size: usize = _samples
+            .iter()
+            .map(|(_sample_length, _, _sample_channels)| _sample_length * _sample_channels)
+            .sum();
+
+        let output_size =
+            2.0 * canva_size * (_synth_configuration.output_sampling_rate as f64 / engine_rate);
+
+        let memory_bytes = (8.0
+            * (2.0 * canva_size
+                + grains_size
+                + resampled_size
+                + samples_size as f64))
+            + 4.0 * output_size;
+
+        return (render_length_s, memory_bytes.round() as usize);
+    }
+
+    // Sprawdza poprawność pliku konfiguracyjnego i sampli bez syntezy dźwięku, wypisuje raport.
+    pub fn validate_configuration(
+        _json_file_path: &String,
+        _overrides: &ConfigOverrides,
+    ) -> Result<(), Vec<String>> {
+        let json_file_value = match load_config_file(_json_file_path) {
+            Ok(_json_file_value) => _json_file_value,
+            Err(_error) => {
+                return Err(vec![_error]);
+            }
+        };
+
+        let source = ConfigSource::load(Some(_json_file_path));
+
+        // Wskaźniki błędów wykrytych przy rozwinięciu szablonów i powtórzeń odnoszą się do treści pliku.
+        let mut json_file_value = match expand_configuration(json_file_value, _json_file_path) {
+            Ok(_json_file_value) => _json_file_value,
+            Err(_errors) => {
+                return Err(config_errors_messages(&_errors, &source, None));
+            }
+        };
+
+        _overrides.apply(&mut json_file_value);
+
+        // Przy przeglądzie parametrów sprawdzane są konfiguracje wszystkich punktów, sample - dla pierwszego z nich.
+        let sweep = match Sweep::load(&json_file_value) {
+            Ok(_sweep) => _sweep,
+            Err(_errors) => {
+                return Err(config_errors_messages(&_errors, &source, Some(&json_file_value)));
+            }
+        };
+
+        if let Some(_sweep) = &sweep {
+            let mut errors: Vec<String> = Vec::new();
+
+            for point in _sweep.points.iter() {
+                match point.validate() {
+                    Err(_errors) => {
+                        errors.push(format!(
+                            "Punkt przeglądu [{}] ({}):",
+                            point.index,
+                            point.label()
+                        ));
+                        errors.extend(config_errors_messages(
+                            &_errors,
+                            &source,
+                            Some(&point.json_file_value),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+
+            if errors.is_empty() == false {
+                return Err(errors);
+            }
+
+            json_file_value = _sweep.points[0].json_file_value.clone();
+        }
+
+        let mut synth_configuration = match SynthConfiguration::load(&json_file_value) {
+            Ok(_synth_configuration) => _synth_configuration,
+            Err(_errors) => {
+                return Err(config_errors_messages(&_errors, &source, Some(&json_file_value)));
+            }
+        };
+
+        let mut tracks = match load_tracks_configurations(&json_file_value, &synth_configuration) {
+            Ok(_tracks) => _tracks,
+            Err(_errors) => {
+                return Err(config_errors_messages(&_errors, &source, Some(&json_file_value)));
+            }
+        };
+
+        match resolve_configuration_paths(&mut synth_configuration, &mut tracks, _json_file_path) {
+            Err(_errors) => {
+                return Err(config_errors_messages(&_errors, &source, Some(&json_file_value)));
+            }
+            _ => {}
+        }
+
+        let mut errors: Vec<String> = Vec::new();
+        let mut estimations: Vec<(f64, usize)> = Vec::with_capacity(tracks.len());
+
+        // Sprawdzenie istnienia i długości sampli.
+        for (_track_number, _track) in tracks.iter().enumerate() {
+            // Długość, częstotliwość próbkowania i liczba kanałów każdego sampla źródłowego.
+            let mut samples: Vec<(usize, u32, usize)> = Vec::new();
+
+            for (_source_index, _sample_source) in
+                _track.grains_properties.sample_sources().iter().enumerate()
+            {
+                let (sample_audio_buffer, sample_sampling_rate) =
+                    match AudioBuffer::load_audio(&_sample_source.file_path) {
+                        Ok(_sample) => _sample,
+                        Err(_error) => {
+                            errors.push(describe_error(
+                                &ConfigError::new(
+                                    _track
+                                        .grains_properties
+                                        .source_pointer(_track_number + 1, _source_index),
+                                    _error,
+                                ),
+                                &source,
+                                Some(&json_file_value),
+                            ));
+                            continue;
+                        }
+                    };
+
+                match checked_source_regions(
+                    &_track.grains_properties,
+                    _track_number + 1,
+                    _source_index,
+                    sample_audio_buffer.len(),
+                    sample_sampling_rate,
+                ) {
+                    Err(_error) => {
+                        errors.push(describe_error(&_error, &source, Some(&json_file_value)));
+                        continue;
+                    }
+                    _ => {}
+                }
+
+                let sample_channels: usize = match sample_audio_buffer {
+                    AudioBuffer::Mono(_) => 1,
+                    AudioBuffer::Stereo(_) => 2,
+                };
+
+                samples.push((sample_audio_buffer.len(), sample_sampling_rate, sample_channels));
+            }
+
+            if samples.len() != _track.grains_properties.sample_sources().len() {
+                continue;
+            }
+
+            // Położenia granulek odtwarzanego wykazu zdarzeń (błąd wczytania wykazu zgłasza szacowanie długości ścieżki).
+            if let Some(_sequence_file) = &_track.sequence_file {
+                if let Ok(_event_list) = load_event_list(_sequence_file) {
+                    let source_lengths_s: Vec<f64> = samples
+                        .iter()
+                        .map(|(_length, _sampling_rate, _)| *_length as f64 / *_sampling_rate as f64)
+                        .collect();
+
+                    match check_event_sources(&_event_list, &source_lengths_s) {
+                        Err(_error) => {
+                            errors.push(describe_error(
+                                &ConfigError::new(format!("/Tracks/{}/sequence_file", _track_number), _error),
+                                &source,
+                                Some(&json_file_value),
+                            ));
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let canva_size = match track_canva_size(_track, _track_number + 1, &synth_configuration) {
+                Ok(_canva_size) => _canva_size,
+                Err(_error) => {
+                    let pointer = if _track.sequence_file.is_some() == true {
+                        format!("/Tracks/{}/sequence_file", _track_number)
+                    } else if _track.mosaic.is_some() == true {
+                        format!("/Tracks/{}/mosaic/target_file", _track_number)
+                    } else if _track.pitch_synchronous.is_some() == true {
+                        format!("/Tracks/{}/pitch_synchronous", _track_number)
+                    } else {
+                        format!("/Tracks/{}/midi/file", _track_number)
+                    };
+
+                    errors.push(describe_error(
+                        &ConfigError::new(pointer, _error),
+                        &source,
+                        Some(&json_file_value),
+                    ));
+                    continue;
+                }
+            };
+
+            if let Some(_guide_file) = &_track.guide_file {
+                match Envelope::load(_guide_file, &_track.guide_envelope.clone().unwrap_or_default()) {
+                    Err(_error) => {
+                        errors.push(describe_error(
+                            &ConfigError::new(format!("/Tracks/{}/guide_file", _track_number), _error),
+                            &source,
+                            Some(&json_file_value),
+                        ));
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            estimations.push(estimate_track(
+                _track,
+                &synth_configuration,
+                canva_size,
+                &samples,
+            ));
+        }
+
+        if errors.is_empty() == false {
+            return Err(errors);
+        }
+
+        // Wypisanie podsumowania.
+        println!(
+            "Plik konfiguracyjny: \'{}\' jest poprawny.\n\nSzacowany rozmiar syntezy:",
+            _json_file_path
+        );
+
+        let mut total_memory_bytes: usize = 0;
+
+        for (_track, (_render_length_s, _memory_bytes)) in tracks.iter().zip(estimations.iter()) {
+            println!(
+                "\t# ścieżka: \'{}\' -> długość: {:.3} s, pamięć: {:.1} MiB",
+                _track.track_properties.track_name,
+                _render_length_s,
+                *_memory_bytes as f64 / (1024.0 * 1024.0)
+            );
+
+            total_memory_bytes += _memory_bytes;
+        }
+
+        println!(
+            "\t# łącznie -> pamięć: {:.1} MiB",
+            total_memory_bytes as f64 / (1024.0 * 1024.0)
+        );
+
+        if let Some(_sweep) = &sweep {
+            println!(
+                "\nPrzegląd parametrów: {} punktów (każdy punkt jest syntetyzowany osobno, rozmiar dotyczy pierwszego z nich).",
+                _sweep.points.len()
+            );
+        }
+
+        return Ok(());
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const SOURCE: &str = "{\n  \"a/b\": {\"c~d\": 1, \"e\\\"f\": [2, \"]}\"]},\n  \"Tracks\": [\n    [1, [2, 3]],\n    {\"x\": null}\n  ]\n}";
+
+        fn json_source() -> ConfigSource {
+            return ConfigSource::from_text(Some(ConfigFormat::Json), SOURCE.to_string());
+        }
+
+        #[test]
+        fn json_pointer_position_unescapes_keys() {
+            assert_eq!(json_pointer_position(SOURCE, "/a~1b/c~0d"), Some((2, 18)));
+            assert_eq!(json_pointer_position(SOURCE, "/a~1b/e\"f/1"), Some((2, 33)));
+        }
+
+        #[test]
+        fn json_pointer_position_walks_nested_arrays() {
+            assert_eq!(json_pointer_position(SOURCE, "/Tracks/0/1/1"), Some((4, 13)));
+            assert_eq!(json_pointer_position(SOURCE, "/Tracks/1/x"), Some((5, 11)));
+            assert_eq!(json_pointer_position(SOURCE, ""), Some((1, 1)));
+        }
+
+        #[test]
+        fn json_pointer_position_falls_back_to_parent_of_missing_leaf() {
+            assert_eq!(json_pointer_position(SOURCE, "/Tracks/1/y"), Some((5, 5)));
+            assert_eq!(json_pointer_position(SOURCE, "/Tracks/7"), Some((3, 13)));
+            assert_eq!(json_pointer_position(SOURCE, "/Tracks/0/1/1/z"), Some((4, 13)));
+        }
+
+        #[test]
+        fn describe_error_reports_position_of_unchanged_value() {
+            let source = json_source();
+            let value: Value = from_str(SOURCE).unwrap();
+            let error = ConfigError::new("/Tracks/1/y".to_string(), "Błąd".to_string());
+
+            assert_eq!(
+                describe_error(&error, &source, Some(&value)),
+                "Błąd\n\twskaźnik: \'/Tracks/1/y\' (linia: 5, kolumna: 5)"
+            );
+            assert_eq!(
+                describe_error(&error, &source, None),
+                "Błąd\n\twskaźnik: \'/Tracks/1/y\' (linia: 5, kolumna: 5)"
+            );
+        }
+
+        #[test]
+        fn describe_error_omits_position_of_expanded_value() {
+            let source = json_source();
+            let mut value: Value = from_str(SOURCE).unwrap();
+
+            // Wartość zmieniona przy rozwinięciu konfiguracji (np. szablon lub powtórzenie beatu).
+            value["Tracks"][1]["x"] = Value::from(5);
+
+            let error = ConfigError::new("/Tracks/1/x".to_string(), "Błąd".to_string());
+
+            assert_eq!(
+                describe_error(&error, &source, Some(&value)),
+                "Błąd\n\twskaźnik: \'/Tracks/1/x\'"
+            );
+
+            // Wartość dodana przy rozwinięciu - jej rodzic w pliku ma inną zawartość.
+            let error = ConfigError::new("/Tracks/2/x".to_string(), "Błąd".to_string());
+            value["Tracks"].as_array_mut().unwrap().push(Value::from(0));
+
+            assert_eq!(
+                describe_error(&error, &source, Some(&value)),
+                "Błąd\n\twskaźnik: \'/Tracks/2/x\'"
+            );
+        }
+
+        #[test]
+        fn describe_error_marks_positions_as_json_only() {
+            let source = ConfigSource::from_text(Some(ConfigFormat::Yaml), "Tracks: []".to_string());
+            let error = ConfigError::new("/Tracks".to_string(), "Błąd".to_string());
+
+            assert_eq!(
+                describe_error(&error, &source, None),
+                "Błąd\n\twskaźnik: \'/Tracks\' (położenie w pliku wyznaczane jest tylko dla plików .json)"
+            );
+            assert_eq!(
+                describe_error(&error, &ConfigSource::load(None), None),
+                "Błąd\n\twskaźnik: \'/Tracks\'"
+            );
+        }
+    }
+}
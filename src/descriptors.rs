@@ -0,0 +1,1076 @@
+pub mod core {
+    use std::cmp::Ordering;
+    use std::sync::Arc;
+
+    use rustfft::num_complex::Complex;
+    use rustfft::{Fft, FftPlanner};
+
+    use crate::audio::tools::AudioBuffer;
+    use crate::granular_synth_config::tools::{
+        Descriptor, GrainsProperties, Mosaic, MosaicFeature, PitchSynchronous,
+    };
+    use crate::markers::tools::checked_source_regions;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Zakres wyszukiwania częstotliwości podstawowej i minimalna wartość znormalizowanej autokorelacji,
+    // poniżej której granulka jest uznawana za pozbawioną wysokości dźwięku (wysokość 0 Hz).
+    const PITCH_RANGE_HZ: (f64, f64) = (50.0, 2000.0);
+    const PITCH_CLARITY_MIN: f64 = 0.3;
+    const POWER_FLOOR: f64 = 1e-12;
+
+    // Długość ramki analizy onsetów (w sekundach), liczba ramek średniej lokalnej strumienia widmowego po każdej
+    // stronie ramki i minimalny strumień onsetu względem największej wartości strumienia.
+    const ONSET_FRAME_S: f64 = 0.02;
+    const ONSET_MEAN_FRAMES: usize = 8;
+    const ONSET_FLUX_FLOOR: f64 = 0.05;
+
+    // Odstęp kolejnych ramek analizy wysokości dźwięku metodą YIN i odstęp znaczników we fragmentach bezdźwięcznych
+    // (w sekundach).
+    const YIN_HOP_S: f64 = 0.005;
+    const UNVOICED_PERIOD_S: f64 = 0.01;
+
+    // Liczba filtrów melowych i współczynników MFCC (bez współczynnika zerowego, zależnego od głośności).
+    const MEL_FILTERS: usize = 26;
+    pub const MFCC_COEFFICIENTS: usize = 12;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Deskryptory dźwięku granulki.
+    #[derive(Debug, Clone, Copy)]
+    pub struct GrainDescriptors {
+        pub rms: f64,
+        pub centroid_hz: f64,
+        pub zero_crossing_rate: f64,
+        pub flatness: f64,
+        pub pitch_hz: f64,
+        pub mfcc: [f64; MFCC_COEFFICIENTS],
+    }
+
+    impl GrainDescriptors {
+        // Wartość wskazanego deskryptora.
+        pub fn value(&self, _descriptor: Descriptor) -> f64 {
+            match _descriptor {
+                Descriptor::Rms => {
+                    return self.rms;
+                }
+                Descriptor::Centroid => {
+                    return self.centroid_hz;
+                }
+                Descriptor::ZeroCrossingRate => {
+                    return self.zero_crossing_rate;
+                }
+                Descriptor::Flatness => {
+                    return self.flatness;
+                }
+                Descriptor::Pitch => {
+                    return self.pitch_hz;
+                }
+            }
+        }
+    }
+
+    // Wektor cech granulki lub ramki porównywany przy mozaice: współczynniki MFCC, centroid widma w skali
+    // logarytmicznej (oktawy względem 1 Hz) i głośność w decybelach.
+    pub fn mosaic_features(_descriptors: &GrainDescriptors, _features: &Vec<MosaicFeature>) -> Vec<f64> {
+        let mut values: Vec<f64> = Vec::new();
+
+        for feature in _features {
+            match feature {
+                MosaicFeature::Mfcc => values.extend_from_slice(&_descriptors.mfcc),
+                MosaicFeature::Centroid => values.push(_descriptors.centroid_hz.max(1.0).log2()),
+                MosaicFeature::Loudness => {
+                    values.push(20.0 * (_descriptors.rms + POWER_FLOOR).log10())
+                }
+            }
+        }
+
+        return values;
+    }
+
+    // Wagi wymiarów wektora cech mozaiki - współczynniki MFCC dzielą między siebie wagę jednej cechy.
+    pub fn mosaic_weights(_features: &Vec<MosaicFeature>) -> Vec<f64> {
+        let mut weights: Vec<f64> = Vec::new();
+
+        for feature in _features {
+            match feature {
+                MosaicFeature::Mfcc => weights.extend(std::iter::repeat_n(
+                    1.0 / (MFCC_COEFFICIENTS as f64).sqrt(),
+                    MFCC_COEFFICIENTS,
+                )),
+                _ => weights.push(1.0),
+            }
+        }
+
+        return weights;
+    }
+
+    // Dźwięk docelowy mozaiki podzielony na ramki wraz z ich deskryptorami.
+    #[derive(Debug, Clone, Default)]
+    pub struct MosaicTarget {
+        pub frames: Vec<GrainDescriptors>,
+        pub hop_s: f64,
+        pub length_s: f64,
+    }
+
+    impl MosaicTarget {
+        // Wczytuje plik dźwięku docelowego i wyznacza deskryptory jego kolejnych ramek.
+        pub fn load(_mosaic: &Mosaic) -> Result<MosaicTarget, String> {
+            let (audio_buffer, sampling_rate) = match AudioBuffer::load_audio(&_mosaic.target_file) {
+                Ok(_audio) => _audio,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let frame_length = ((_mosaic.frame_ms / 1000.0) * sampling_rate as f64).round() as usize;
+            let hop_length = (((_mosaic.hop_ms() / 1000.0) * sampling_rate as f64).round() as usize).max(1);
+
+            if audio_buffer.len() < frame_length || audio_buffer.peak() <= 0.0 {
+                return Err(format!(
+                    "Błąd danych ->\n\tplik: \'{}\' jest krótszy od ramki analizy albo nie zawiera sygnału :/",
+                    _mosaic.target_file
+                ));
+            }
+
+            let frames = DescriptorAnalyser::new().analyse_frames(
+                &audio_buffer,
+                sampling_rate as f64,
+                frame_length,
+                hop_length,
+            );
+
+            return Ok(MosaicTarget {
+                frames,
+                hop_s: hop_length as f64 / sampling_rate as f64,
+                length_s: audio_buffer.len() as f64 / sampling_rate as f64,
+            });
+        }
+    }
+
+    // Znacznik okresu sampla: położenie (w sekundach, w maksimum sygnału okresu), długość okresu (w sekundach)
+    // i dźwięczność fragmentu (znaczniki fragmentów bezdźwięcznych są rozmieszczone co 'UNVOICED_PERIOD_S').
+    #[derive(Debug, Clone, Copy)]
+    pub struct PitchMark {
+        pub position_s: f64,
+        pub period_s: f64,
+        pub voiced: bool,
+    }
+
+    // Znaczniki okresów sampla ścieżki z syntezą synchroniczną z okresem.
+    #[derive(Debug, Clone, Default)]
+    pub struct PitchMarks {
+        pub marks: Vec<PitchMark>,
+    }
+
+    impl PitchMarks {
+        // Wczytuje sampel ścieżki i wyznacza znaczniki jego okresów. We fragmentach sampla pozostają znaczniki,
+        // których granulki ('periods' okresów wokół znacznika) mieszczą się w całości we fragmencie.
+        pub fn load(
+            _grains_properties: &GrainsProperties,
+            _pitch_synchronous: &PitchSynchronous,
+            _track_number: usize,
+        ) -> Result<PitchMarks, String> {
+            let source = &_grains_properties.sample_sources()[0];
+            let (audio_buffer, sampling_rate) = match AudioBuffer::load_audio(&source.file_path) {
+                Ok(_audio) => _audio,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let regions = match checked_source_regions(
+                _grains_properties,
+                _track_number,
+                0,
+                audio_buffer.len(),
+                sampling_rate,
+            ) {
+                Ok(_regions) => _regions,
+                Err(_error) => {
+                    return Err(_error.to_string());
+                }
+            };
+
+            let sampling_rate = sampling_rate as f64;
+            let marks: Vec<PitchMark> = DescriptorAnalyser::new()
+                .pitch_marks(&audio_buffer, sampling_rate, _pitch_synchronous.yin_threshold)
+                .into_iter()
+                .filter(|_mark| {
+                    let half_length = 0.5 * _pitch_synchronous.periods as f64 * _mark.period_s;
+
+                    return regions.is_empty() == true
+                        || regions.iter().any(|(_start, _end)| {
+                            _mark.position_s - half_length >= *_start as f64 / sampling_rate
+                                && _mark.position_s + half_length <= *_end as f64 / sampling_rate
+                        });
+                })
+                .collect();
+
+            if marks.iter().any(|_mark| _mark.voiced == true) == false {
+                return Err(format!(
+                    "Błąd danych ->\n\tw samplu: \'{}\' nie wykryto fragmentów o określonej wysokości dźwięku (zmienna \'yin_threshold\') :/",
+                    source.file_path
+                ));
+            }
+
+            return Ok(PitchMarks { marks });
+        }
+    }
+
+    // Analizator deskryptorów, przechowujący plany transformat Fouriera dla kolejnych długości granulek.
+    pub struct DescriptorAnalyser {
+        planner: FftPlanner<f64>,
+    }
+
+    impl DescriptorAnalyser {
+        pub fn new() -> Self {
+            return DescriptorAnalyser {
+                planner: FftPlanner::new(),
+            };
+        }
+
+        // Wyznacza deskryptory granulki: RMS, środek ciężkości widma (w Hz), częstość przejść przez zero
+        // (w przejściach na próbkę), płaskość widma (0 ... 1) i wysokość dźwięku (w Hz, metodą autokorelacji).
+        pub fn analyse(&mut self, _grain: &AudioBuffer, _sampling_rate: f64) -> GrainDescriptors {
+            let signal = mono_mix(_grain);
+
+            if signal.len() < 2 {
+                return GrainDescriptors {
+                    rms: 0.0,
+                    centroid_hz: 0.0,
+                    zero_crossing_rate: 0.0,
+                    flatness: 0.0,
+                    pitch_hz: 0.0,
+                    mfcc: [0.0; MFCC_COEFFICIENTS],
+                };
+            }
+
+            let rms = (signal.iter().map(|_value| _value * _value).sum::<f64>() / signal.len() as f64).sqrt();
+
+            let zero_crossings = signal
+                .windows(2)
+                .filter(|_pair| (_pair[0] >= 0.0) != (_pair[1] >= 0.0))
+                .count();
+            let zero_crossing_rate = zero_crossings as f64 / (signal.len() - 1) as f64;
+
+            // Widmo mocy sygnału z oknem Hanna, uzupełnionego zerami do potęgi dwójki.
+            let fft_length = signal.len().next_power_of_two();
+            let fft = self.planner.plan_fft_forward(fft_length);
+            let window_scale = 2.0 * std::f64::consts::PI / (signal.len() - 1) as f64;
+
+            let mut spectrum: Vec<Complex<f64>> = signal
+                .iter()
+                .enumerate()
+                .map(|(_index, _value)| {
+                    Complex::new(_value * (0.5 - 0.5 * (window_scale * _index as f64).cos()), 0.0)
+                })
+                .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+                .take(fft_length)
+                .collect();
+
+            fft.process(&mut spectrum);
+
+            let power: Vec<f64> = spectrum[1..fft_length / 2 + 1]
+                .iter()
+                .map(|_bin| _bin.norm_sqr())
+                .collect();
+            let bin_hz = _sampling_rate / fft_length as f64;
+
+            let magnitude_sum: f64 = power.iter().map(|_power| _power.sqrt()).sum();
+            let centroid_hz = if magnitude_sum > 0.0 {
+                power
+                    .iter()
+                    .enumerate()
+                    .map(|(_bin, _power)| (_bin + 1) as f64 * bin_hz * _power.sqrt())
+                    .sum::<f64>()
+                    / magnitude_sum
+            } else {
+                0.0
+            };
+
+            let power_mean = power.iter().sum::<f64>() / power.len() as f64;
+            let flatness = if power_mean > POWER_FLOOR {
+                let log_mean = power
+                    .iter()
+                    .map(|_power| (_power + POWER_FLOOR).ln())
+                    .sum::<f64>()
+                    / power.len() as f64;
+
+                (log_mean.exp() / power_mean).min(1.0)
+            } else {
+                0.0
+            };
+
+            return GrainDescriptors {
+                rms,
+                centroid_hz,
+                zero_crossing_rate,
+                flatness,
+                pitch_hz: self.estimate_pitch(&signal, _sampling_rate),
+                mfcc: mfcc(&power, bin_hz, _sampling_rate),
+            };
+        }
+
+        // Wyznacza deskryptory kolejnych ramek sygnału o długości '_frame_length' co '_hop_length' próbek.
+        pub fn analyse_frames(
+            &mut self,
+            _audio_buffer: &AudioBuffer,
+            _sampling_rate: f64,
+            _frame_length: usize,
+            _hop_length: usize,
+        ) -> Vec<GrainDescriptors> {
+            let signal = mono_mix(_audio_buffer);
+
+            return (0..signal.len())
+                .step_by(_hop_length.max(1))
+                .map(|_frame_start| {
+                    let frame_end = (_frame_start + _frame_length).min(signal.len());
+                    let frame = AudioBuffer::Mono([signal[_frame_start..frame_end].to_vec()]);
+
+                    self.analyse(&frame, _sampling_rate)
+                })
+                .collect();
+        }
+
+        // Wykrywa początki dźwięków (onsety) metodą strumienia widmowego: suma dodatnich przyrostów amplitud widma
+        // kolejnych ramek (ok. 20 ms co 1/4 ramki) jest porównywana z '_threshold' x średnią lokalną strumienia.
+        // Kolejne onsety są oddalone co najmniej o '_min_interval_s', wynikiem są indeksy próbek.
+        pub fn detect_onsets(
+            &mut self,
+            _audio_buffer: &AudioBuffer,
+            _sampling_rate: f64,
+            _threshold: f64,
+            _min_interval_s: f64,
+        ) -> Vec<usize> {
+            let signal = mono_mix(_audio_buffer);
+            let frame_length = ((ONSET_FRAME_S * _sampling_rate) as usize).next_power_of_two().max(4);
+            let hop_length = frame_length / 4;
+
+            if signal.len() < frame_length {
+                return Vec::new();
+            }
+
+            let fft = self.planner.plan_fft_forward(frame_length);
+            let window_scale = 2.0 * std::f64::consts::PI / (frame_length - 1) as f64;
+            let mut previous: Vec<f64> = vec![0.0; frame_length / 2 + 1];
+
+            let flux: Vec<f64> = (0..=(signal.len() - frame_length) / hop_length)
+                .map(|_frame| {
+                    let mut spectrum: Vec<Complex<f64>> = signal[_frame * hop_length.._frame * hop_length + frame_length]
+                        .iter()
+                        .enumerate()
+                        .map(|(_index, _value)| {
+                            Complex::new(_value * (0.5 - 0.5 * (window_scale * _index as f64).cos()), 0.0)
+                        })
+                        .collect();
+
+                    fft.process(&mut spectrum);
+
+                    let magnitude: Vec<f64> = spectrum[..frame_length / 2 + 1]
+                        .iter()
+                        .map(|_bin| _bin.norm())
+                        .collect();
+                    let frame_flux = magnitude
+                        .iter()
+                        .zip(previous.iter())
+                        .map(|(_current, _previous)| (_current - _previous).max(0.0))
+                        .sum::<f64>();
+
+                    previous = magnitude;
+
+                    frame_flux
+                })
+                .collect();
+
+            // Wybór lokalnych maksimów strumienia powyżej progu adaptacyjnego.
+            let peak_flux = flux.iter().fold(0.0, |_peak: f64, _value| _peak.max(*_value));
+            let min_interval = ((_min_interval_s * _sampling_rate) / hop_length as f64).ceil() as usize;
+            let mut onsets: Vec<usize> = Vec::new();
+            let mut last_frame: Option<usize> = None;
+
+            for frame in 0..flux.len() {
+                let (from, to) = (frame.saturating_sub(ONSET_MEAN_FRAMES), (frame + ONSET_MEAN_FRAMES + 1).min(flux.len()));
+                let local_mean = flux[from..to].iter().sum::<f64>() / (to - from) as f64;
+                let is_peak = (frame == 0 || flux[frame] > flux[frame - 1])
+                    && (frame + 1 == flux.len() || flux[frame] >= flux[frame + 1]);
+
+                if is_peak == false
+                    || flux[frame] <= _threshold * local_mean
+                    || flux[frame] < ONSET_FLUX_FLOOR * peak_flux
+                {
+                    continue;
+                }
+
+                if let Some(_last_frame) = last_frame {
+                    if frame - _last_frame < min_interval {
+                        continue;
+                    }
+                }
+
+                // Przyrost energii przypada na ostatni odstęp ramki, onset jest umieszczany przed środkiem okna.
+                onsets.push(frame * hop_length + (frame_length - hop_length) / 2);
+                last_frame = Some(frame);
+            }
+
+            return onsets;
+        }
+
+        // Wyznacza znaczniki kolejnych okresów sygnału. Okres jest szacowany metodą YIN w ramkach co 'YIN_HOP_S',
+        // a znacznik umieszczany w maksimum sygnału w otoczeniu (+/- 1/4 okresu) położenia przewidywanego na podstawie
+        // poprzedniego znacznika. Pierwszy znacznik fragmentu dźwięcznego jest maksimum pierwszego okresu fragmentu.
+        pub fn pitch_marks(&mut self, _audio_buffer: &AudioBuffer, _sampling_rate: f64, _threshold: f64) -> Vec<PitchMark> {
+            let signal = mono_mix(_audio_buffer);
+            let min_lag = ((_sampling_rate / PITCH_RANGE_HZ.1).floor() as usize).max(2);
+            let max_lag = (_sampling_rate / PITCH_RANGE_HZ.0).ceil() as usize;
+            let segment_length = 2 * max_lag;
+            let hop_length = ((YIN_HOP_S * _sampling_rate).round() as usize).max(1);
+            let unvoiced_period = (UNVOICED_PERIOD_S * _sampling_rate).round();
+
+            let frame_periods: Vec<Option<f64>> = if signal.len() >= segment_length {
+                (0..=(signal.len() - segment_length) / hop_length)
+                    .map(|_frame| {
+                        let start = _frame * hop_length;
+
+                        self.yin_period(&signal[start..start + segment_length], min_lag, max_lag, _threshold)
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            // Okres ramki, której środek jest najbliższy położeniu '_position'.
+            let period_at = |_position: f64| -> Option<f64> {
+                if frame_periods.is_empty() == true {
+                    return None;
+                }
+
+                let frame = ((_position - segment_length as f64 / 2.0) / hop_length as f64)
+                    .round()
+                    .max(0.0) as usize;
+
+                return frame_periods[frame.min(frame_periods.len() - 1)];
+            };
+
+            let mut marks: Vec<PitchMark> = Vec::new();
+            let mut position: f64 = 0.0;
+            let mut previous_voiced = false;
+
+            while (position as usize) < signal.len() {
+                match period_at(position) {
+                    Some(_period) => {
+                        let (from, to) = if previous_voiced == true {
+                            ((position - _period / 4.0) as usize, (position + _period / 4.0) as usize + 1)
+                        } else {
+                            (position as usize, (position + _period) as usize)
+                        };
+                        let to = to.min(signal.len()).max(from + 1);
+
+                        if from >= signal.len() {
+                            break;
+                        }
+
+                        let peak = (from..to)
+                            .max_by(|a, b| signal[*a].partial_cmp(&signal[*b]).unwrap_or(Ordering::Equal))
+                            .unwrap();
+
+                        marks.push(PitchMark {
+                            position_s: peak as f64 / _sampling_rate,
+                            period_s: _period / _sampling_rate,
+                            voiced: true,
+                        });
+
+                        position = peak as f64 + _period;
+                        previous_voiced = true;
+                    }
+                    None => {
+                        marks.push(PitchMark {
+                            position_s: position / _sampling_rate,
+                            period_s: unvoiced_period / _sampling_rate,
+                            voiced: false,
+                        });
+
+                        position += unvoiced_period;
+                        previous_voiced = false;
+                    }
+                }
+            }
+
+            return marks;
+        }
+
+        // Okres sygnału (w próbkach) metodą YIN: skumulowana, znormalizowana funkcja różnicowa pierwszej połowy
+        // '_segment' jest wyznaczana przez transformatę Fouriera, okresem jest pierwsze minimum lokalne poniżej progu
+        // '_threshold' (z interpolacją paraboliczną). Brak takiego minimum oznacza fragment bezdźwięczny.
+        fn yin_period(&mut self, _segment: &[f64], _min_lag: usize, _max_lag: usize, _threshold: f64) -> Option<f64> {
+            let window = _segment.len() - _max_lag;
+            let fft_length = _segment.len().next_power_of_two();
+            let fft: Arc<dyn Fft<f64>> = self.planner.plan_fft_forward(fft_length);
+            let ifft: Arc<dyn Fft<f64>> = self.planner.plan_fft_inverse(fft_length);
+
+            let padded = |_values: &[f64]| -> Vec<Complex<f64>> {
+                return _values
+                    .iter()
+                    .map(|_value| Complex::new(*_value, 0.0))
+                    .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+                    .take(fft_length)
+                    .collect();
+            };
+
+            let mut head = padded(&_segment[..window]);
+            let mut whole = padded(_segment);
+
+            fft.process(&mut head);
+            fft.process(&mut whole);
+
+            let mut correlation: Vec<Complex<f64>> = head
+                .iter()
+                .zip(whole.iter())
+                .map(|(_head, _whole)| _head.conj() * _whole)
+                .collect();
+
+            ifft.process(&mut correlation);
+
+            let mut squares: Vec<f64> = vec![0.0; _segment.len() + 1];
+
+            for (_index, _value) in _segment.iter().enumerate() {
+                squares[_index + 1] = squares[_index] + _value * _value;
+            }
+
+            if squares[window] <= POWER_FLOOR * window as f64 {
+                return None;
+            }
+
+            // Funkcja różnicowa d(t) = E(0) + E(t) - 2 r(t) i jej postać skumulowana, znormalizowana.
+            let mut normalized: Vec<f64> = vec![1.0; _max_lag + 1];
+            let mut cumulative: f64 = 0.0;
+
+            for lag in 1..=_max_lag {
+                let difference = (squares[window] + squares[lag + window] - squares[lag]
+                    - 2.0 * correlation[lag].re / fft_length as f64)
+                    .max(0.0);
+
+                cumulative += difference;
+                normalized[lag] = if cumulative > 0.0 {
+                    difference * lag as f64 / cumulative
+                } else {
+                    1.0
+                };
+            }
+
+            let mut lag = match (_min_lag.._max_lag).find(|_lag| normalized[*_lag] < _threshold) {
+                Some(_lag) => _lag,
+                None => {
+                    return None;
+                }
+            };
+
+            while lag + 1 < _max_lag && normalized[lag + 1] < normalized[lag] {
+                lag += 1;
+            }
+
+            // Interpolacja paraboliczna położenia minimum.
+            let (previous, current, next) = (normalized[lag - 1], normalized[lag], normalized[lag + 1]);
+            let denominator = previous - 2.0 * current + next;
+
+            if denominator.abs() > POWER_FLOOR {
+                return Some(lag as f64 + 0.5 * (previous - next) / denominator);
+            }
+
+            return Some(lag as f64);
+        }
+
+        // Szacuje częstotliwość podstawową na podstawie maksimum znormalizowanej autokorelacji (wyznaczanej
+        // przez transformatę Fouriera) w zakresie 'PITCH_RANGE_HZ'.
+        fn estimate_pitch(&mut self, _signal: &Vec<f64>, _sampling_rate: f64) -> f64 {
+            let min_lag = (_sampling_rate / PITCH_RANGE_HZ.1).floor() as usize;
+            let max_lag = ((_sampling_rate / PITCH_RANGE_HZ.0).ceil() as usize).min(_signal.len() / 2);
+
+            if min_lag < 1 || max_lag <= min_lag {
+                return 0.0;
+            }
+
+            let fft_length = (2 * _signal.len()).next_power_of_two();
+            let fft: Arc<dyn Fft<f64>> = self.planner.plan_fft_forward(fft_length);
+            let ifft: Arc<dyn Fft<f64>> = self.planner.plan_fft_inverse(fft_length);
+
+            let mut buffer: Vec<Complex<f64>> = _signal
+                .iter()
+                .map(|_value| Complex::new(*_value, 0.0))
+                .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+                .take(fft_length)
+                .collect();
+
+            fft.process(&mut buffer);
+            buffer
+                .iter_mut()
+                .for_each(|_bin| *_bin = Complex::new(_bin.norm_sqr(), 0.0));
+            ifft.process(&mut buffer);
+
+            let energy = buffer[0].re;
+
+            if energy <= POWER_FLOOR {
+                return 0.0;
+            }
+
+            // Autokorelacja znormalizowana liczbą nakładających się próbek.
+            let normalized = |_lag: usize| -> f64 {
+                return buffer[_lag].re / energy * _signal.len() as f64
+                    / (_signal.len() - _lag) as f64;
+            };
+
+            let mut best_lag: usize = 0;
+            let mut best_value: f64 = PITCH_CLARITY_MIN;
+
+            for lag in min_lag..=max_lag {
+                let value = normalized(lag);
+
+                if value > best_value {
+                    best_value = value;
+                    best_lag = lag;
+                }
+            }
+
+            if best_lag == 0 {
+                return 0.0;
+            }
+
+            // Interpolacja paraboliczna położenia maksimum.
+            let mut lag = best_lag as f64;
+
+            if best_lag > min_lag && best_lag < max_lag {
+                let (previous, current, next) =
+                    (normalized(best_lag - 1), best_value, normalized(best_lag + 1));
+                let denominator = previous - 2.0 * current + next;
+
+                if denominator.abs() > POWER_FLOOR {
+                    lag += 0.5 * (previous - next) / denominator;
+                }
+            }
+
+            return _sampling_rate / lag;
+        }
+    }
+
+    impl std::fmt::Debug for DescriptorAnalyser {
+        fn fmt(&self, _formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            return _formatter.write_str("DescriptorAnalyser");
+        }
+    }
+
+    // Współczynniki cepstralne w skali melowej (MFCC 1 ... 12): logarytmy energii trójkątnych filtrów melowych
+    // rozłożonych do połowy częstotliwości próbkowania, przekształcone dyskretną transformatą kosinusową (DCT-II).
+    fn mfcc(_power: &Vec<f64>, _bin_hz: f64, _sampling_rate: f64) -> [f64; MFCC_COEFFICIENTS] {
+        let hz_to_mel = |_hz: f64| -> f64 { 2595.0 * (1.0 + _hz / 700.0).log10() };
+        let mel_to_hz = |_mel: f64| -> f64 { 700.0 * (10f64.powf(_mel / 2595.0) - 1.0) };
+
+        let max_mel = hz_to_mel(_sampling_rate / 2.0);
+        let edges_hz: Vec<f64> = (0..MEL_FILTERS + 2)
+            .map(|_edge| mel_to_hz(max_mel * _edge as f64 / (MEL_FILTERS + 1) as f64))
+            .collect();
+
+        let log_energies: Vec<f64> = (0..MEL_FILTERS)
+            .map(|_filter| {
+                let (low, center, high) =
+                    (edges_hz[_filter], edges_hz[_filter + 1], edges_hz[_filter + 2]);
+
+                let energy: f64 = _power
+                    .iter()
+                    .enumerate()
+                    .map(|(_bin, _bin_power)| {
+                        let frequency = (_bin + 1) as f64 * _bin_hz;
+
+                        if frequency <= low || frequency >= high {
+                            0.0
+                        } else if frequency <= center {
+                            _bin_power * (frequency - low) / (center - low)
+                        } else {
+                            _bin_power * (high - frequency) / (high - center)
+                        }
+                    })
+                    .sum();
+
+                (energy + POWER_FLOOR).ln()
+            })
+            .collect();
+
+        let mut coefficients = [0.0; MFCC_COEFFICIENTS];
+
+        for (_index, _coefficient) in coefficients.iter_mut().enumerate() {
+            *_coefficient = log_energies
+                .iter()
+                .enumerate()
+                .map(|(_filter, _log_energy)| {
+                    _log_energy
+                        * (std::f64::consts::PI * (_index + 1) as f64 * (_filter as f64 + 0.5)
+                            / MEL_FILTERS as f64)
+                            .cos()
+                })
+                .sum();
+        }
+
+        return coefficients;
+    }
+
+    // Sygnał granulki jako średnia kanałów.
+    fn mono_mix(_audio_buffer: &AudioBuffer) -> Vec<f64> {
+        match _audio_buffer {
+            AudioBuffer::Mono(_buffer) => {
+                return _buffer[0].clone();
+            }
+            AudioBuffer::Stereo(_buffer) => {
+                return _buffer[0]
+                    .iter()
+                    .zip(_buffer[1].iter())
+                    .map(|(_left, _rigth)| 0.5 * (_left + _rigth))
+                    .collect();
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Przestrzeń deskryptorów: wektory cech granulek znormalizowane średnią i odchyleniem standardowym każdego wymiaru
+    // (podzielonym przez wagę wymiaru) oraz drzewo k-d pozwalające wyszukać granulki najbliższe wektorowi docelowemu.
+    #[derive(Debug)]
+    pub struct DescriptorSpace {
+        scales: Vec<(f64, f64)>,
+        tree: KdTree,
+    }
+
+    impl DescriptorSpace {
+        pub fn build(_vectors: &Vec<Vec<f64>>, _weights: &Vec<f64>) -> DescriptorSpace {
+            let count = _vectors.len().max(1) as f64;
+
+            let scales: Vec<(f64, f64)> = _weights
+                .iter()
+                .enumerate()
+                .map(|(_dimension, _weight)| {
+                    let mean = _vectors.iter().map(|_vector| _vector[_dimension]).sum::<f64>() / count;
+                    let deviation = (_vectors
+                        .iter()
+                        .map(|_vector| (_vector[_dimension] - mean).powi(2))
+                        .sum::<f64>()
+                        / count)
+                        .sqrt();
+
+                    (mean, if deviation > 0.0 { deviation / _weight } else { 1.0 })
+                })
+                .collect();
+
+            let mut space = DescriptorSpace {
+                scales,
+                tree: KdTree::build(Vec::new()),
+            };
+
+            let points: Vec<(Vec<f64>, usize)> = _vectors
+                .iter()
+                .enumerate()
+                .map(|(_index, _vector)| (space.normalize(_vector), _index))
+                .collect();
+
+            space.tree = KdTree::build(points);
+
+            return space;
+        }
+
+        fn normalize(&self, _vector: &[f64]) -> Vec<f64> {
+            return _vector
+                .iter()
+                .zip(self.scales.iter())
+                .map(|(_value, (_mean, _deviation))| (_value - _mean) / _deviation)
+                .collect();
+        }
+
+        // Indeksy '_count' wektorów najbliższych wektorowi docelowemu, uporządkowane od najbliższego.
+        pub fn nearest(&self, _target: &[f64], _count: usize) -> Vec<usize> {
+            return self.tree.nearest(&self.normalize(_target), _count);
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Węzeł drzewa k-d: punkt, indeks granulki i oś podziału.
+    #[derive(Debug)]
+    struct KdNode {
+        point: Vec<f64>,
+        index: usize,
+        axis: usize,
+        left: Option<usize>,
+        right: Option<usize>,
+    }
+
+    // Drzewo k-d punktów w przestrzeni deskryptorów, pozwalające wyszukać granulki najbliższe wartościom docelowym.
+    #[derive(Debug)]
+    struct KdTree {
+        nodes: Vec<KdNode>,
+        root: Option<usize>,
+        dimensions: usize,
+    }
+
+    impl KdTree {
+        // Buduje zrównoważone drzewo z punktów i odpowiadających im indeksów.
+        fn build(_points: Vec<(Vec<f64>, usize)>) -> KdTree {
+            let dimensions = _points.first().map_or(0, |(_point, _)| _point.len());
+            let mut tree = KdTree {
+                nodes: Vec::with_capacity(_points.len()),
+                root: None,
+                dimensions,
+            };
+
+            tree.root = tree.build_node(_points, 0);
+
+            return tree;
+        }
+
+        fn build_node(&mut self, mut _points: Vec<(Vec<f64>, usize)>, _depth: usize) -> Option<usize> {
+            if _points.is_empty() == true || self.dimensions == 0 {
+                return None;
+            }
+
+            let axis = _depth % self.dimensions;
+
+            _points.sort_by(|(_a, _), (_b, _)| {
+                _a[axis].partial_cmp(&_b[axis]).unwrap_or(Ordering::Equal)
+            });
+
+            let median = _points.len() / 2;
+            let right_points = _points.split_off(median + 1);
+            let (point, index) = _points.pop().unwrap();
+
+            let node_index = self.nodes.len();
+
+            self.nodes.push(KdNode {
+                point,
+                index,
+                axis,
+                left: None,
+                right: None,
+            });
+
+            let left = self.build_node(_points, _depth + 1);
+            let right = self.build_node(right_points, _depth + 1);
+
+            self.nodes[node_index].left = left;
+            self.nodes[node_index].right = right;
+
+            return Some(node_index);
+        }
+
+        // Zwraca indeksy '_count' punktów najbliższych punktowi docelowemu (odległość euklidesowa),
+        // uporządkowane od najbliższego.
+        fn nearest(&self, _target: &[f64], _count: usize) -> Vec<usize> {
+            let mut found: Vec<(f64, usize)> = Vec::with_capacity(_count + 1);
+
+            if _count > 0 {
+                self.search(self.root, _target, _count, &mut found);
+            }
+
+            return found.into_iter().map(|(_, _index)| _index).collect();
+        }
+
+        fn search(
+            &self,
+            _node: Option<usize>,
+            _target: &[f64],
+            _count: usize,
+            _found: &mut Vec<(f64, usize)>,
+        ) {
+            let node = match _node {
+                Some(_node_index) => &self.nodes[_node_index],
+                None => {
+                    return;
+                }
+            };
+
+            let distance: f64 = node
+                .point
+                .iter()
+                .zip(_target.iter())
+                .map(|(_a, _b)| (_a - _b) * (_a - _b))
+                .sum();
+
+            if _found.len() < _count || distance < _found.last().unwrap().0 {
+                let position = _found
+                    .iter()
+                    .position(|(_distance, _)| distance < *_distance)
+                    .unwrap_or(_found.len());
+
+                _found.insert(position, (distance, node.index));
+                _found.truncate(_count);
+            }
+
+            let difference = _target[node.axis] - node.point[node.axis];
+            let (near, far) = if difference < 0.0 {
+                (node.left, node.right)
+            } else {
+                (node.right, node.left)
+            };
+
+            self.search(near, _target, _count, _found);
+
+            if _found.len() < _count || difference * difference < _found.last().unwrap().0 {
+                self.search(far, _target, _count, _found);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use rand::prelude::{Rng, SeedableRng, StdRng};
+
+        use super::*;
+
+        fn random_vectors(_count: usize, _dimensions: usize, _seed: u64) -> Vec<Vec<f64>> {
+            let mut randomness_source = StdRng::seed_from_u64(_seed);
+
+            return (0.._count)
+                .map(|_| (0.._dimensions).map(|_| randomness_source.gen_range(-10.0..10.0)).collect())
+                .collect();
+        }
+
+        // Indeksy '_count' punktów najbliższych punktowi docelowemu wyznaczone przez przegląd wszystkich punktów.
+        fn brute_force(_points: &Vec<Vec<f64>>, _target: &[f64], _count: usize) -> Vec<usize> {
+            let mut distances: Vec<(f64, usize)> = _points
+                .iter()
+                .enumerate()
+                .map(|(_index, _point)| {
+                    let distance: f64 = _point.iter().zip(_target.iter()).map(|(_a, _b)| (_a - _b).powi(2)).sum();
+
+                    (distance, _index)
+                })
+                .collect();
+
+            distances.sort_by(|(_a, _), (_b, _)| _a.partial_cmp(_b).unwrap());
+
+            return distances.into_iter().take(_count).map(|(_, _index)| _index).collect();
+        }
+
+        #[test]
+        fn kd_tree_nearest_matches_brute_force() {
+            let points = random_vectors(500, 4, 1);
+            let tree = KdTree::build(points.iter().cloned().zip(0..points.len()).collect());
+
+            for _target in random_vectors(50, 4, 2).iter() {
+                for _count in [1, 3, 10].iter() {
+                    assert_eq!(tree.nearest(_target, *_count), brute_force(&points, _target, *_count));
+                }
+            }
+        }
+
+        #[test]
+        fn kd_tree_handles_empty_and_oversized_queries() {
+            let empty = KdTree::build(Vec::new());
+            let points = random_vectors(5, 2, 3);
+            let tree = KdTree::build(points.iter().cloned().zip(0..points.len()).collect());
+
+            assert!(empty.nearest(&[0.0, 0.0], 3).is_empty());
+            assert!(tree.nearest(&[0.0, 0.0], 0).is_empty());
+            assert_eq!(tree.nearest(&[0.0, 0.0], 10), brute_force(&points, &[0.0, 0.0], 10));
+        }
+
+        #[test]
+        fn descriptor_space_matches_brute_force_in_normalized_space() {
+            let vectors: Vec<Vec<f64>> = random_vectors(300, 3, 4)
+                .into_iter()
+                .map(|_vector| vec![_vector[0] * 1000.0, _vector[1], _vector[2] * 0.01])
+                .collect();
+            let weights = vec![1.0, 2.0, 0.5];
+            let space = DescriptorSpace::build(&vectors, &weights);
+            let normalized: Vec<Vec<f64>> = vectors.iter().map(|_vector| space.normalize(_vector)).collect();
+
+            for (_index, _vector) in vectors.iter().enumerate().step_by(30) {
+                assert_eq!(space.nearest(_vector, 1), vec![_index]);
+                assert_eq!(space.nearest(_vector, 5), brute_force(&normalized, &normalized[_index], 5));
+            }
+        }
+
+        const SAMPLING_RATE: f64 = 44100.0;
+
+        // Cichy szum z krótkimi, zanikającymi impulsami w położeniach '_clicks' (w próbkach).
+        fn clicks(_length: usize, _clicks: &[usize]) -> AudioBuffer {
+            let mut randomness_source = StdRng::seed_from_u64(5);
+            let mut signal: Vec<f64> = (0.._length).map(|_| randomness_source.gen_range(-0.001..0.001)).collect();
+
+            for _click in _clicks.iter() {
+                for _index in 0..200.min(_length - _click) {
+                    signal[_click + _index] += randomness_source.gen_range(-1.0..1.0) * (-(_index as f64) / 40.0).exp();
+                }
+            }
+
+            return AudioBuffer::Mono([signal]);
+        }
+
+        #[test]
+        fn onsets_are_detected_at_clicks() {
+            let positions = vec![4410, 15435, 26460, 30870];
+            let onsets = DescriptorAnalyser::new().detect_onsets(&clicks(44100, &positions), SAMPLING_RATE, 1.5, 0.05);
+
+            // Onset jest umieszczany przed impulsem, nie dalej niż o połowę ramki (512 próbek).
+            let precedes = |_onset: usize, _position: usize| _onset <= _position && _position - _onset < 512;
+
+            assert_eq!(onsets.len(), positions.len());
+            assert!(onsets.iter().zip(positions.iter()).all(|(_onset, _position)| precedes(*_onset, *_position)));
+
+            // Onset odległy od poprzedniego o mniej niż '_min_interval_s' jest pomijany.
+            let onsets = DescriptorAnalyser::new().detect_onsets(&clicks(44100, &positions), SAMPLING_RATE, 1.5, 0.2);
+
+            assert_eq!(onsets.len(), 3);
+            assert!(precedes(onsets[2], positions[2]));
+        }
+
+        #[test]
+        fn onsets_are_not_detected_in_short_or_steady_signals() {
+            let mut analyser = DescriptorAnalyser::new();
+
+            assert!(analyser.detect_onsets(&clicks(500, &[100]), SAMPLING_RATE, 1.5, 0.05).is_empty());
+            assert!(analyser.detect_onsets(&AudioBuffer::Mono([vec![0.0; 44100]]), SAMPLING_RATE, 1.5, 0.05).is_empty());
+        }
+
+        fn sine(_frequency: f64, _length: usize) -> Vec<f64> {
+            return (0.._length)
+                .map(|_index| (2.0 * std::f64::consts::PI * _frequency * _index as f64 / SAMPLING_RATE).sin())
+                .collect();
+        }
+
+        #[test]
+        fn yin_period_matches_sine_frequency() {
+            let mut analyser = DescriptorAnalyser::new();
+            let (min_lag, max_lag) = (22, 882);
+
+            for _frequency in [110.0, 220.0, 441.0, 1000.0].iter() {
+                let period = analyser.yin_period(&sine(*_frequency, 2 * max_lag), min_lag, max_lag, 0.15).unwrap();
+
+                assert!((period - SAMPLING_RATE / _frequency).abs() < 0.1, "{} Hz: {}", _frequency, period);
+            }
+
+            assert_eq!(analyser.yin_period(&vec![0.0; 2 * max_lag], min_lag, max_lag, 0.15), None);
+        }
+
+        #[test]
+        fn pitch_marks_follow_sine_periods() {
+            let mut signal = sine(220.0, 22050);
+
+            signal.extend(vec![0.0; 4410]);
+
+            let marks = DescriptorAnalyser::new().pitch_marks(&AudioBuffer::Mono([signal.clone()]), SAMPLING_RATE, 0.15);
+            let voiced: Vec<&PitchMark> = marks.iter().filter(|_mark| _mark.voiced == true).collect();
+            // Znaczniki oddalone od końca sinusa o mniej niż długość segmentu YIN (40 ms) mogą mieć zaburzony okres.
+            let inner: Vec<&PitchMark> = voiced.iter().cloned().filter(|_mark| _mark.position_s < 0.46).collect();
+
+            // Znaczniki dźwięcznego fragmentu leżą w maksimach kolejnych okresów sinusa.
+            assert_eq!(inner.len(), 101);
+            assert!(inner.iter().all(|_mark| (_mark.period_s * 220.0 - 1.0).abs() < 1e-4));
+            assert!(inner
+                .iter()
+                .all(|_mark| signal[(_mark.position_s * SAMPLING_RATE).round() as usize] > 0.999));
+            assert!(inner
+                .windows(2)
+                .all(|_pair| (_pair[1].position_s - _pair[0].position_s - 1.0 / 220.0).abs() < 1.0 / SAMPLING_RATE));
+            assert!(voiced.iter().all(|_mark| _mark.position_s < 0.52));
+
+            // Cisza na końcu jest oznaczana znacznikami bezdźwięcznymi.
+            assert!(marks.last().is_some_and(|_mark| _mark.voiced == false && _mark.position_s > 0.5));
+        }
+    }
+}
@@ -0,0 +1,651 @@
+pub mod tools {
+    use std::fs::{read_to_string, write};
+    use std::path::Path;
+
+    use midly::num::{u15, u24, u28, u4, u7};
+    use midly::{
+        Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind,
+    };
+    use serde::Deserialize;
+    use serde_json::{from_str, json, to_string_pretty, to_value, Value};
+
+    use crate::granular_synth_config::tools::{
+        EventsFormat, GWFunction, SynthConfiguration, Track,
+    };
+    use crate::sequencer::core::{Event, EventGrain};
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Rozdzielczość eksportowanego pliku MIDI (liczba taktów zegara na beat).
+    const MIDI_TICKS_PER_BEAT: u16 = 480;
+    // Największa długość beatu (w mikrosekundach) zapisywalna w 24-bitowym zdarzeniu tempa.
+    const MIDI_MAX_US_PER_BEAT: f64 = 16_777_215.0;
+    const MIDI_ROOT_NOTE: f64 = 60.0;
+    const PAN_CONTROLLER: u8 = 10;
+
+    // Kolumny wykazu zdarzeń '.csv' wymagane do odtworzenia sekwencji.
+    const CSV_COLUMNS: [&str; 6] = [
+        "start_s",
+        "volume",
+        "panorama",
+        "pitch",
+        "source_offset_s",
+        "length_s",
+    ];
+    // Kolumny granulki sprzed transpozycji o wysokość zdarzenia (opcjonalne, wymagane razem).
+    const CSV_GRAIN_COLUMNS: [&str; 3] = ["grain_pitch", "grain_length_s", "event_pitch"];
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Zdarzenie wczytanego wykazu zdarzeń: czas rozpoczęcia, głośność, panorama oraz wysokość dźwięku,
+    // położenie w samplu i długość granulki (czasy w sekundach, niezależne od częstotliwości próbkowania)
+    // i indeks sampla źródłowego ścieżki (domyślnie pierwszy sampel). Wysokość i długość granulki sprzed
+    // transpozycji oraz wysokość zdarzenia pozwalają odtworzyć granulkę dokładnie, a gdy ich brak - granulka
+    // jest wycinana z wysokością i długością zdarzenia.
+    #[derive(Debug, Deserialize)]
+    pub struct RecordedEvent {
+        pub start_s: f64,
+        pub volume: f64,
+        pub panorama: f64,
+        pub pitch: f64,
+        pub source_offset_s: f64,
+        pub length_s: f64,
+
+        #[serde(default)]
+        pub source: usize,
+
+        #[serde(default)]
+        pub grain_pitch: Option<f64>,
+
+        #[serde(default)]
+        pub grain_length_s: Option<f64>,
+
+        #[serde(default)]
+        pub event_pitch: Option<f64>,
+    }
+
+    // Wczytany wykaz zdarzeń wraz z oknem czasowym granulek i długością syntezy (tylko w wykazie '.json').
+    #[derive(Debug, Deserialize)]
+    pub struct EventList {
+        pub events: Vec<RecordedEvent>,
+
+        #[serde(default)]
+        pub window_function: Option<GWFunction>,
+
+        #[serde(default)]
+        pub length_s: Option<f64>,
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Sprawdza, czy granulki zdarzeń wykazu zaczynają się wewnątrz sampli źródłowych o długościach '_source_lengths_s'
+    // (w sekundach). Błąd wskazuje numer zdarzenia (od 1).
+    pub fn check_event_sources(_event_list: &EventList, _source_lengths_s: &Vec<f64>) -> Result<(), String> {
+        for (_event_index, _event) in _event_list.events.iter().enumerate() {
+            match _source_lengths_s.get(_event.source) {
+                Some(_source_length_s) => {
+                    if _event.source_offset_s < 0.0 || _event.source_offset_s >= *_source_length_s {
+                        return Err(format!(
+                            "Błąd danych ->\n\tzdarzenie: [{}] - \'source_offset_s\': {} s wykracza poza sampel źródłowy: [{}] (długość: {:.6} s) :/",
+                            _event_index + 1,
+                            _event.source_offset_s,
+                            _event.source + 1,
+                            _source_length_s
+                        ));
+                    }
+                }
+                None => {
+                    return Err(format!(
+                        "Błąd danych ->\n\tzdarzenie: [{}] wskazuje sampel źródłowy: [{}], ścieżka ma sampli źródłowych: {} :/",
+                        _event_index + 1,
+                        _event.source + 1,
+                        _source_lengths_s.len()
+                    ));
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Zapisuje wykaz zdarzeń ścieżki w podanym formacie.
+    pub fn save_events(
+        _track: &Track,
+        _synth_configuration: &SynthConfiguration,
+        _seed: u64,
+        _format: EventsFormat,
+        _file_path: &String,
+    ) -> Result<(), String> {
+        let events = &_track.sequencer.sequence;
+        let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+
+        match _format {
+            EventsFormat::Csv => {
+                return save_file(_file_path, events_csv(events, sampling_rate).as_bytes());
+            }
+            EventsFormat::Json => {
+                let events_value = json!({
+                    "track": _track.track_properties.track_name,
+                    "sample_file_path": _track.grains_properties.sample_file_path,
+                    "sources": _track
+                        .grains_properties
+                        .sample_sources()
+                        .iter()
+                        .map(|_source| _source.file_path.clone())
+                        .collect::<Vec<String>>(),
+                    "window_function": to_value(&_track.grains_properties.window_function).unwrap(),
+                    "engine_sampling_rate": _synth_configuration.engine_sampling_rate,
+                    "seed": _seed,
+                    "length_s": _track.sequencer.length as f64 / sampling_rate,
+                    "events": events
+                        .iter()
+                        .map(|_event| event_value(_event, sampling_rate))
+                        .collect::<Vec<Value>>()
+                });
+
+                return save_file(_file_path, to_string_pretty(&events_value).unwrap().as_bytes());
+            }
+            EventsFormat::Midi => {
+                return save_events_midi(
+                    events,
+                    &_track.track_properties.track_name,
+                    _synth_configuration,
+                    _file_path,
+                );
+            }
+        }
+    }
+
+    // Wczytuje wykaz zdarzeń zapisany w formacie '.csv' lub '.json' (format wynika z rozszerzenia pliku).
+    pub fn load_event_list(_file_path: &String) -> Result<EventList, String> {
+        let contents = match read_to_string(_file_path) {
+            Ok(_contents) => _contents,
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd odczytu ->\n\tplik: \'{}\' nie został znaleziony.\n\tSystem error: {} :/",
+                    _file_path, _system_error
+                ));
+            }
+        };
+
+        let extension = Path::new(_file_path)
+            .extension()
+            .map(|_extension| _extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let event_list: EventList = match extension.as_str() {
+            "csv" => match events_from_csv(&contents) {
+                Ok(_events) => EventList {
+                    events: _events,
+                    window_function: None,
+                    length_s: None,
+                },
+                Err(_error) => {
+                    return Err(format!(
+                        "Błąd danych ->\n\tplik: \'{}\' - {} :/",
+                        _file_path, _error
+                    ));
+                }
+            },
+            "json" => match from_str(&contents) {
+                Ok(_event_list) => _event_list,
+                Err(_error) => {
+                    return Err(format!(
+                        "Błąd danych ->\n\tplik: \'{}\' nie jest prawidłowym wykazem zdarzeń.\n\tSerializer error: {} :/",
+                        _file_path, _error
+                    ));
+                }
+            },
+            _ => {
+                return Err(format!(
+                    "Błąd danych ->\n\tnieobsługiwany format wykazu zdarzeń: \'{}\' (wymagany plik \'.csv\' lub \'.json\') :/",
+                    _file_path
+                ));
+            }
+        };
+
+        for (_event_number, _event) in event_list.events.iter().enumerate() {
+            if _event.start_s < 0.0
+                || _event.volume < 0.0
+                || _event.panorama < -1.0
+                || _event.panorama > 1.0
+                || _event.pitch <= 0.0
+                || _event.source_offset_s < 0.0
+                || _event.length_s <= 0.0
+                || _event.grain_pitch.is_some_and(|_grain_pitch| _grain_pitch <= 0.0)
+                || _event.grain_length_s.is_some_and(|_grain_length_s| _grain_length_s <= 0.0)
+                || _event.event_pitch.is_some_and(|_event_pitch| _event_pitch <= 0.0)
+            {
+                return Err(format!(
+                    "Błąd danych ->\n\tplik: \'{}\' - nieprawidłowe zdarzenie: [{}] (\'start_s\' >= 0.0, \'volume\' >= 0.0, \'panorama\' -1.0 ... 1.0, \'pitch\' > 0.0, \'source_offset_s\' >= 0.0, \'length_s\' > 0.0, \'grain_pitch\' > 0.0, \'grain_length_s\' > 0.0, \'event_pitch\' > 0.0) :/",
+                    _file_path,
+                    _event_number + 1
+                ));
+            }
+        }
+
+        return Ok(event_list);
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Odczytuje zdarzenia wykazu '.csv', kolejność kolumn wynika z nagłówka (kolumna 'source' i kolumny granulki
+    // sprzed transpozycji są opcjonalne).
+    fn events_from_csv(_contents: &String) -> Result<Vec<RecordedEvent>, String> {
+        let mut lines = _contents
+            .lines()
+            .enumerate()
+            .filter(|(_, _line)| _line.trim().is_empty() == false);
+
+        let header: Vec<&str> = match lines.next() {
+            Some((_, _header)) => _header.split(',').map(|_column| _column.trim()).collect(),
+            None => {
+                return Err("brak nagłówka wykazu zdarzeń".to_string());
+            }
+        };
+
+        let mut columns: Vec<usize> = Vec::with_capacity(CSV_COLUMNS.len());
+
+        for column in CSV_COLUMNS.iter() {
+            match header.iter().position(|_column| _column == column) {
+                Some(_index) => {
+                    columns.push(_index);
+                }
+                None => {
+                    return Err(format!("brak kolumny: \'{}\'", column));
+                }
+            }
+        }
+
+        let source_column = header.iter().position(|_column| *_column == "source");
+        let grain_columns: Vec<Option<usize>> = CSV_GRAIN_COLUMNS
+            .iter()
+            .map(|_column| header.iter().position(|_header_column| _header_column == _column))
+            .collect();
+        let mut events: Vec<RecordedEvent> = Vec::new();
+
+        for (_line_index, _line) in lines {
+            let fields: Vec<&str> = _line.split(',').map(|_field| _field.trim()).collect();
+            let mut values: Vec<f64> = Vec::with_capacity(columns.len());
+
+            for (_column, _index) in CSV_COLUMNS.iter().zip(columns.iter()) {
+                match fields.get(*_index).map(|_field| _field.parse::<f64>()) {
+                    Some(Ok(_value)) => {
+                        values.push(_value);
+                    }
+                    _ => {
+                        return Err(format!(
+                            "nieprawidłowa wartość kolumny: \'{}\' w wierszu: {}",
+                            _column,
+                            _line_index + 1
+                        ));
+                    }
+                }
+            }
+
+            let source: usize = match source_column {
+                Some(_index) => match fields.get(_index).map(|_field| _field.parse::<usize>()) {
+                    Some(Ok(_source)) => _source,
+                    _ => {
+                        return Err(format!(
+                            "nieprawidłowa wartość kolumny: \'source\' w wierszu: {}",
+                            _line_index + 1
+                        ));
+                    }
+                },
+                None => 0,
+            };
+
+            let mut grain_values: Vec<Option<f64>> = Vec::with_capacity(grain_columns.len());
+
+            for (_column, _index) in CSV_GRAIN_COLUMNS.iter().zip(grain_columns.iter()) {
+                match _index.map(|_index| fields.get(_index).map(|_field| _field.parse::<f64>())) {
+                    Some(Some(Ok(_value))) => {
+                        grain_values.push(Some(_value));
+                    }
+                    None => {
+                        grain_values.push(None);
+                    }
+                    _ => {
+                        return Err(format!(
+                            "nieprawidłowa wartość kolumny: \'{}\' w wierszu: {}",
+                            _column,
+                            _line_index + 1
+                        ));
+                    }
+                }
+            }
+
+            events.push(RecordedEvent {
+                start_s: values[0],
+                volume: values[1],
+                panorama: values[2],
+                pitch: values[3],
+                source_offset_s: values[4],
+                length_s: values[5],
+                source,
+                grain_pitch: grain_values[0],
+                grain_length_s: grain_values[1],
+                event_pitch: grain_values[2],
+            });
+        }
+
+        return Ok(events);
+    }
+
+    // Zapisuje dane do pliku.
+    fn save_file(_file_path: &String, _contents: &[u8]) -> Result<(), String> {
+        match write(Path::new(_file_path), _contents) {
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd ->\n\tnie można utworzyć pliku \'{}\'.\n\tSystem error: {} :/",
+                    _file_path, _system_error
+                ));
+            }
+            _ => {}
+        }
+
+        return Ok(());
+    }
+
+    // Granulka zdarzenia sprzed transpozycji, zdarzenia bez zsyntetyzowanej granulki mają granulkę o zerowej
+    // długości i wysokości dźwięku 1.0.
+    fn event_grain(_event: &Event) -> EventGrain {
+        match &_event.grain {
+            Some(_grain) => {
+                return _grain.clone();
+            }
+            None => {
+                return EventGrain {
+                    source_offset_s: 0.0,
+                    length: 0,
+                    pitch: 1.0,
+                    source: 0,
+                    transposed_length: 0,
+                };
+            }
+        }
+    }
+
+    // Zdarzenie jako obiekt Value wykazu '.json': wysokość 'pitch' i długość 'length' odtwarzanej granulki
+    // (po transpozycji) oraz granulka sprzed transpozycji i wysokość zdarzenia.
+    fn event_value(_event: &Event, _sampling_rate: f64) -> Value {
+        let grain = event_grain(_event);
+
+        return json!({
+            "start_index": _event.start_index,
+            "start_s": _event.start_index as f64 / _sampling_rate,
+            "volume": _event.volume,
+            "panorama": _event.panorama,
+            "pitch": grain.pitch * _event.pitch,
+            "source_offset_s": grain.source_offset_s,
+            "length": grain.transposed_length,
+            "length_s": grain.transposed_length as f64 / _sampling_rate,
+            "source": grain.source,
+            "grain_pitch": grain.pitch,
+            "grain_length": grain.length,
+            "grain_length_s": grain.length as f64 / _sampling_rate,
+            "event_pitch": _event.pitch
+        });
+    }
+
+    // Wykaz zdarzeń w formacie '.csv' (jeden wiersz na zdarzenie). Wartości wpływające na brzmienie granulki
+    // są zapisywane z pełną precyzją, dzięki czemu odtworzenie wykazu jest dokładne.
+    fn events_csv(_events: &Vec<Event>, _sampling_rate: f64) -> String {
+        let mut lines: Vec<String> = vec![
+            "start_index,start_s,volume,panorama,pitch,source_offset_s,length,length_s,source,grain_pitch,grain_length,grain_length_s,event_pitch".to_string(),
+        ];
+
+        for event in _events.iter() {
+            let grain = event_grain(event);
+
+            lines.push(format!(
+                "{},{:.6},{},{},{},{},{},{},{},{},{},{},{}",
+                event.start_index,
+                event.start_index as f64 / _sampling_rate,
+                event.volume,
+                event.panorama,
+                grain.pitch * event.pitch,
+                grain.source_offset_s,
+                grain.transposed_length,
+                grain.transposed_length as f64 / _sampling_rate,
+                grain.source,
+                grain.pitch,
+                grain.length,
+                grain.length as f64 / _sampling_rate,
+                event.pitch
+            ));
+        }
+
+        return lines.join("\n") + "\n";
+    }
+
+    // Zapisuje zdarzenia jako nuty pliku MIDI: wysokość granulki wyznacza numer nuty względem C4 (nuta 60),
+    // głośność prędkość, a panorama kontroler 10. Plik ma stałe tempo bazowe ('beat_length_ms'), dzięki czemu
+    // nuty pokrywają się w czasie z granulkami pliku dźwiękowego także przy mapie tempa.
+    fn save_events_midi(
+        _events: &Vec<Event>,
+        _track_name: &String,
+        _synth_configuration: &SynthConfiguration,
+        _file_path: &String,
+    ) -> Result<(), String> {
+        let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+        let mut us_per_beat = (_synth_configuration.beat_length_ms * 1000.0).round();
+
+        // Tempo w pliku MIDI mieści się w 24 bitach, dlatego przy bardzo długich uderzeniach beat MIDI jest
+        // dzielony na pół (co podwaja liczbę taktów zegara), aż długość uderzenia zmieści się w zakresie.
+        let mut beat_halvings: u32 = 0;
+
+        while us_per_beat > MIDI_MAX_US_PER_BEAT {
+            us_per_beat = (us_per_beat / 2.0).round();
+            beat_halvings += 1;
+        }
+
+        let ticks_per_second = MIDI_TICKS_PER_BEAT as f64 * 1e6 / us_per_beat;
+        let channel = u4::new(0);
+
+        // Zdarzenia MIDI wraz z położeniem (w taktach zegara), przy równym położeniu wyłączenia nut poprzedzają
+        // zmiany panoramy i włączenia nut.
+        let mut midi_events: Vec<(u64, u8, TrackEventKind)> = Vec::with_capacity(_events.len() * 3);
+
+        for event in _events.iter() {
+            let grain = event_grain(event);
+            let length = grain.transposed_length;
+
+            let key = (MIDI_ROOT_NOTE + 12.0 * (grain.pitch * event.pitch).log2())
+                .round()
+                .clamp(0.0, 127.0) as u8;
+            let velocity = (event.volume * 127.0).round().clamp(1.0, 127.0) as u8;
+            let pan = (event.panorama * 63.0 + 64.0).round().clamp(0.0, 127.0) as u8;
+
+            let start_tick = (event.start_index as f64 / sampling_rate * ticks_per_second).round() as u64;
+            let end_tick = ((event.start_index + length) as f64 / sampling_rate * ticks_per_second)
+                .round()
+                .max(start_tick as f64 + 1.0) as u64;
+
+            midi_events.push((
+                start_tick,
+                1,
+                TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::Controller {
+                        controller: u7::new(PAN_CONTROLLER),
+                        value: u7::new(pan),
+                    },
+                },
+            ));
+            midi_events.push((
+                start_tick,
+                2,
+                TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::NoteOn {
+                        key: u7::new(key),
+                        vel: u7::new(velocity),
+                    },
+                },
+            ));
+            midi_events.push((
+                end_tick,
+                0,
+                TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::NoteOff {
+                        key: u7::new(key),
+                        vel: u7::new(0),
+                    },
+                },
+            ));
+        }
+
+        midi_events.sort_by_key(|(_tick, _order, _)| (*_tick, *_order));
+
+        // Podzielone uderzenie odpowiada krótszej wartości nuty, metrum zachowuje więc długość taktu.
+        let numerator = (_synth_configuration.time_signature.0 as u64) << beat_halvings;
+        let denominator_power = (_synth_configuration.time_signature.1 as f64).log2().round() as u32 + beat_halvings;
+
+        let mut track: Vec<TrackEvent> = vec![
+            TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Meta(MetaMessage::TrackName(_track_name.as_bytes())),
+            },
+            TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::new(us_per_beat as u32))),
+            },
+            TrackEvent {
+                delta: u28::new(0),
+                kind: TrackEventKind::Meta(MetaMessage::TimeSignature(
+                    numerator.min(255) as u8,
+                    denominator_power.min(255) as u8,
+                    24,
+                    8,
+                )),
+            },
+        ];
+
+        let mut last_tick: u64 = 0;
+
+        for (_tick, _, _kind) in midi_events.into_iter() {
+            track.push(TrackEvent {
+                delta: u28::new((_tick - last_tick) as u32),
+                kind: _kind,
+            });
+            last_tick = _tick;
+        }
+
+        track.push(TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+        });
+
+        let mut smf = Smf::new(Header::new(
+            Format::SingleTrack,
+            Timing::Metrical(u15::new(MIDI_TICKS_PER_BEAT)),
+        ));
+        smf.tracks.push(track);
+
+        match smf.save(_file_path) {
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd ->\n\tnie można utworzyć pliku \'{}\'.\n\tSystem error: {} :/",
+                    _file_path, _system_error
+                ));
+            }
+            _ => {}
+        }
+
+        return Ok(());
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const SAMPLING_RATE: f64 = 48_000.0;
+
+        fn event(_start_index: usize, _source_offset_s: f64, _source: usize) -> Event {
+            return Event {
+                start_index: _start_index,
+                panorama: -0.25,
+                volume: 0.8,
+                pitch: 1.5,
+                length: 1.0,
+                grain: Some(EventGrain {
+                    source_offset_s: _source_offset_s,
+                    length: 3000,
+                    pitch: 1.25,
+                    source: _source,
+                    transposed_length: 2400,
+                }),
+                frame: None,
+                pitch_mark: None,
+            };
+        }
+
+        fn event_list(_events: &Vec<Event>) -> EventList {
+            return EventList {
+                events: events_from_csv(&events_csv(_events, SAMPLING_RATE)).unwrap(),
+                window_function: None,
+                length_s: None,
+            };
+        }
+
+        #[test]
+        fn csv_round_trip_keeps_events() {
+            let events = vec![event(0, 0.125, 0), event(24_000, 0.5, 1)];
+            let recorded = event_list(&events).events;
+
+            assert_eq!(recorded.len(), 2);
+            assert!((recorded[1].start_s - 0.5).abs() < 1e-6);
+            assert!((recorded[1].volume - 0.8).abs() < 1e-6);
+            assert!((recorded[1].panorama + 0.25).abs() < 1e-6);
+            assert!((recorded[1].pitch - 1.875).abs() < 1e-6);
+            assert!((recorded[1].source_offset_s - 0.5).abs() < 1e-6);
+            assert!((recorded[1].length_s - 0.05).abs() < 1e-6);
+            assert_eq!((recorded[0].source, recorded[1].source), (0, 1));
+            assert_eq!(recorded[1].grain_pitch, Some(1.25));
+            assert_eq!(recorded[1].grain_length_s, Some(3000.0 / SAMPLING_RATE));
+            assert_eq!(recorded[1].event_pitch, Some(1.5));
+        }
+
+        #[test]
+        fn csv_columns_follow_header() {
+            let recorded = events_from_csv(
+                &"length_s,pitch,start_s,source_offset_s,panorama,volume\n0.1,2.0,1.5,0.25,0.5,0.9\n".to_string(),
+            )
+            .unwrap();
+
+            assert_eq!(recorded.len(), 1);
+            assert!((recorded[0].start_s - 1.5).abs() < 1e-12 && (recorded[0].length_s - 0.1).abs() < 1e-12);
+            assert!((recorded[0].pitch - 2.0).abs() < 1e-12 && (recorded[0].volume - 0.9).abs() < 1e-12);
+            assert_eq!(recorded[0].source, 0);
+            assert_eq!(recorded[0].grain_pitch, None);
+        }
+
+        #[test]
+        fn csv_errors_name_column_and_row() {
+            let missing = events_from_csv(&"start_s,volume,panorama,pitch,length_s\n".to_string());
+            let invalid = events_from_csv(
+                &"start_s,volume,panorama,pitch,source_offset_s,length_s\n0,1,0,1,0,0.1\n0,1,0,x,0,0.1\n".to_string(),
+            );
+
+            assert!(missing.unwrap_err().contains("source_offset_s"));
+            assert!(invalid.unwrap_err().contains("\'pitch\' w wierszu: 3"));
+        }
+
+        #[test]
+        fn out_of_range_rows_are_rejected() {
+            let events = vec![event(0, 0.125, 0), event(4800, 2.0, 0), event(9600, 0.25, 1)];
+            let event_list = event_list(&events);
+
+            assert!(check_event_sources(&event_list, &vec![3.0, 1.0]).is_ok());
+
+            let past_end = check_event_sources(&event_list, &vec![1.0, 1.0]).unwrap_err();
+            let missing_source = check_event_sources(&event_list, &vec![3.0]).unwrap_err();
+
+            assert!(past_end.contains("zdarzenie: [2]"));
+            assert!(missing_source.contains("zdarzenie: [3]"));
+        }
+    }
+}
@@ -1,19 +1,77 @@
 pub mod synth {
+    use std::fs::write;
+    use std::path::Path;
+
+    use rand::{thread_rng, Rng};
     use rubato::{
         interpolator_avx::AvxInterpolator, InterpolationType, Resampler, SincFixedIn,
         WindowFunction,
     };
+    use serde_json::{json, to_string_pretty, to_value, Value};
 
     use crate::audio::tools::AudioBuffer;
+    use crate::cli::tools::{Arguments, Verbosity};
+    use crate::config_validator::tools::{config_errors_messages, ConfigSource};
+    use crate::descriptors::core::{
+        mosaic_features, mosaic_weights, GrainDescriptors, MosaicTarget, PitchMarks,
+    };
+    use crate::events_export::tools::{load_event_list, save_events};
     use crate::granular_synth_config::tools::{
-        load_json_file, load_tracks_configurations, SynthConfiguration, Track,
+        load_tracks_configurations, resolve_configuration_paths,
+        save_config_file, OverwritePolicy, SynthConfiguration, Track, MOSAIC_GAIN_MAX,
     };
+    use crate::midi::core::load_midi_notes;
+    use crate::sampler::core::Sampler;
+    use crate::sequencer::core::{walk_markov_chain, Envelope, EventGrain};
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     const SINC_LEN: usize = 256;
     const OVERSAMPLING_FACTOR: usize = 256;
 
+    // Przyrostek pliku pełnej konfiguracji ścieżki zapisywanego obok pliku dźwiękowego.
+    const RESOLVED_CONFIGURATION_SUFFIX: &str = ".resolved.json";
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Wyznacza ziarno generatora liczb losowych ścieżki na podstawie ziarna globalnego i nazwy ścieżki (FNV-1a),
+    // dzięki czemu wybór podzbioru ścieżek nie zmienia wyniku syntezy pozostałych.
+    fn track_seed(_seed: u64, _track_name: &String) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+        for _byte in _track_name.bytes() {
+            hash ^= _byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+
+        return _seed ^ hash;
+    }
+
+    // Sprawdza, czy obie ścieżki wskazują ten sam plik (plik docelowy nie musi jeszcze istnieć).
+    fn same_file(_file_path: &String, _other_file_path: &String) -> bool {
+        let canonical = |_path: &String| -> Option<std::path::PathBuf> {
+            let path = Path::new(_path);
+            let parent = match path.parent() {
+                Some(_parent) if _parent.as_os_str().is_empty() == false => _parent,
+                _ => Path::new("."),
+            };
+
+            return parent
+                .canonicalize()
+                .ok()
+                .and_then(|_parent| path.file_name().map(|_file_name| _parent.join(_file_name)));
+        };
+
+        match (canonical(_file_path), canonical(_other_file_path)) {
+            (Some(_path), Some(_other_path)) => {
+                return _path == _other_path;
+            }
+            _ => {
+                return false;
+            }
+        }
+    }
+
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     // Syntezator granularny.
@@ -21,82 +79,399 @@ pub mod synth {
     pub struct GranularSynth {
         pub synth_configuration: SynthConfiguration,
         pub tracks: Vec<Track>,
+        pub seed: u64,
+        pub verbosity: Verbosity,
+        pub manifest: Vec<Value>,
+
+        // Plik konfiguracyjny syntezy, którego nie wolno nadpisać plikami wyjściowymi.
+        config_file_path: Option<String>,
     }
 
     impl GranularSynth {
-        // Wczytuje plik konfiguracyjny .json i sprawdza poprawność danych.
-        pub fn configure(_json_file_path: &String) -> Result<GranularSynth, Vec<String>> {
-            let json_file_value_result = load_json_file(_json_file_path);
+        // Buduje syntezator na podstawie obiektu Value pliku konfiguracyjnego i argumentów linii komend.
+        pub fn configure(
+            _json_file_value: &Value,
+            _arguments: &Arguments,
+        ) -> Result<GranularSynth, Vec<String>> {
+            let source = ConfigSource::load(_arguments.config_file_path.as_ref());
+            let synth_configuration_result = SynthConfiguration::load(_json_file_value);
 
-            let json_file_value = match json_file_value_result {
-                Ok(_json_file_value) => _json_file_value,
-                Err(_error) => {
-                    return Err(vec![_error]);
-                }
-            };
-
-            let synth_configuration_result = SynthConfiguration::load(&json_file_value);
-
-            let _synth_configuration = match synth_configuration_result {
+            let mut _synth_configuration = match synth_configuration_result {
                 Ok(_synth_configuration) => _synth_configuration,
                 Err(_errors) => {
-                    return Err(_errors);
+                    return Err(config_errors_messages(&_errors, &source, Some(_json_file_value)));
                 }
             };
 
-            let tracks_result = load_tracks_configurations(&json_file_value, &_synth_configuration);
+            let tracks_result = load_tracks_configurations(_json_file_value, &_synth_configuration);
 
-            let _tracks = match tracks_result {
+            let mut _tracks = match tracks_result {
                 Ok(_tracks_configurations) => _tracks_configurations,
                 Err(_errors) => {
-                    return Err(_errors);
+                    return Err(config_errors_messages(&_errors, &source, Some(_json_file_value)));
                 }
             };
 
-            // Wypisanie podsumowania wczytanego pliku konfiguracyjnego.
-            println!(
-                "Konfiguracja syntezatora:\n\t# długość beatu: {} ms\n\t# częstotliwość próbkowania silnika: {} Hz\n\t# katalog wyjściowy: \'{}\'\n\t# wyjściowa częstotliwość próbkowania: {} Hz\n\t# wyjściowa głębia bitowa: {} bit",
-                _synth_configuration.beat_length_ms, _synth_configuration.engine_sampling_rate, _synth_configuration.output_directory, _synth_configuration.output_sampling_rate, _synth_configuration.output_bit_depth
-            );
+            // Ścieżki względne są wyznaczane względem katalogu pliku konfiguracyjnego.
+            if let Some(_json_file_path) = &_arguments.config_file_path {
+                match resolve_configuration_paths(
+                    &mut _synth_configuration,
+                    &mut _tracks,
+                    _json_file_path,
+                ) {
+                    Err(_errors) => {
+                        return Err(config_errors_messages(&_errors, &source, Some(_json_file_value)));
+                    }
+                    _ => {}
+                }
+            }
+
+            // Wybór ścieżek wskazanych w linii komend.
+            if _arguments.selected_tracks.is_empty() == false {
+                let mut errors: Vec<String> = Vec::new();
+
+                for _selected_track in _arguments.selected_tracks.iter() {
+                    if _tracks
+                        .iter()
+                        .any(|_track| _track.track_properties.track_name == *_selected_track)
+                        == false
+                    {
+                        errors.push(format!(
+                            "Błąd danych ->\n\tścieżka: \'{}\' nie występuje w pliku konfiguracyjnym :/",
+                            _selected_track
+                        ));
+                    }
+                }
+
+                if errors.is_empty() == false {
+                    return Err(errors);
+                }
+
+                _tracks.retain(|_track| {
+                    _arguments
+                        .selected_tracks
+                        .contains(&_track.track_properties.track_name)
+                });
+            }
 
-            println!("\nŚcieżki:");
+            let _seed: u64 = match _synth_configuration.seed {
+                Some(_seed) => _seed,
+                None => thread_rng().gen(),
+            };
 
-            for (_track_number, _track) in _tracks.iter().enumerate() {
+            // Wypisanie podsumowania wczytanego pliku konfiguracyjnego.
+            if _arguments.verbosity >= Verbosity::Normal {
                 println!(
-                    "\t# ścieżka [{}] -> nazwa: \'{}\', sampel: \'{}\'",
-                    _track_number + 1, _track.track_properties.track_name, _track.grains_properties.sample_file_path
+                    "Konfiguracja syntezatora:\n\t# długość beatu: {} ms (tempo: {:.2} BPM, metrum: {}/{}, punkty mapy tempa: {})\n\t# częstotliwość próbkowania silnika: {} Hz\n\t# katalog wyjściowy: \'{}\'\n\t# wyjściowa częstotliwość próbkowania: {} Hz\n\t# wyjściowa głębia bitowa: {} bit\n\t# ziarno: {}",
+                    _synth_configuration.beat_length_ms, 60_000.0 / _synth_configuration.beat_length_ms, _synth_configuration.time_signature.0, _synth_configuration.time_signature.1, _synth_configuration.tempo_map.len(), _synth_configuration.engine_sampling_rate, _synth_configuration.output_directory, _synth_configuration.output_sampling_rate, _synth_configuration.output_bit_depth, _seed
                 );
+
+                println!("\nŚcieżki:");
+
+                for (_track_number, _track) in _tracks.iter().enumerate() {
+                    println!(
+                        "\t# ścieżka [{}] -> nazwa: \'{}\', sampel: \'{}\'",
+                        _track_number + 1, _track.track_properties.track_name, _track.grains_properties.sample_sources().iter().map(|_source| _source.file_path.as_str()).collect::<Vec<&str>>().join("\', \'")
+                    );
+                }
             }
 
             return Ok(GranularSynth {
                 synth_configuration: _synth_configuration,
                 tracks: _tracks,
+                seed: _seed,
+                verbosity: _arguments.verbosity,
+                manifest: Vec::new(),
+                config_file_path: _arguments.config_file_path.clone(),
             });
         }
 
+        // Ziarno wariantu syntezy (numerowanego od 1), pierwszy wariant korzysta z ziarna globalnego.
+        fn take_seed(&self, _take: usize) -> u64 {
+            return self.seed.wrapping_add((_take - 1) as u64);
+        }
+
+        // Ustawia ziarna generatorów ścieżki (numerowanej od 1) i generuje jej sekwencję zdarzeń.
+        fn sequence_track(&self, _track: &mut Track, _track_number: usize, _take: usize) -> Result<(), String> {
+            let seed = track_seed(self.take_seed(_take), &_track.track_properties.track_name);
+
+            _track.sampler.seed(seed);
+            _track.sequencer.seed(seed.rotate_left(32));
+
+            // Sekwencja beatów ścieżki z łańcuchem Markowa jest generowana od nowa dla każdego wariantu.
+            if let Some(_markov) = &_track.markov {
+                let (beat_sequence, markov_path) =
+                    walk_markov_chain(_markov, &self.synth_configuration, seed.rotate_left(16));
+
+                _track.beat_sequence = beat_sequence;
+                _track.markov_path = markov_path;
+
+                if self.verbosity >= Verbosity::Normal {
+                    println!(
+                        "\tścieżka: \'{}\' - łańcuch Markowa ({} beatów): {}",
+                        _track.track_properties.track_name,
+                        _track.markov_path.len(),
+                        _track.markov_path.join(" -> ")
+                    );
+                }
+            }
+
+            // Ścieżka z wykazem zdarzeń odtwarza zapisaną sekwencję wraz z granulkami i oknem czasowym wykazu.
+            if let Some(_sequence_file) = &_track.sequence_file {
+                let mut event_list = match load_event_list(_sequence_file) {
+                    Ok(_event_list) => _event_list,
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                };
+
+                if let Some(_window_function) = event_list.window_function.take() {
+                    _track.grains_properties.window_function = _window_function;
+                }
+
+                _track
+                    .sequencer
+                    .replay_sequence(&event_list, &self.synth_configuration);
+
+                return Ok(());
+            }
+
+            // Ścieżka z sekwencją MIDI zastępuje generowanie sekwencji zdarzeń nutami pliku MIDI.
+            if let Some(_midi) = &_track.midi {
+                let midi_notes = match load_midi_notes(_midi) {
+                    Ok(_midi_notes) => _midi_notes,
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                };
+
+                _track
+                    .sequencer
+                    .generate_midi_sequence(&midi_notes, _midi, &self.synth_configuration);
+
+                return Ok(());
+            }
+
+            // Ścieżka z mozaiką umieszcza zdarzenia w miejscach ramek dźwięku docelowego, analizowanego jednokrotnie.
+            if let Some(_mosaic) = &_track.mosaic {
+                if _track.mosaic_target.frames.is_empty() == true {
+                    _track.mosaic_target = match MosaicTarget::load(_mosaic) {
+                        Ok(_mosaic_target) => _mosaic_target,
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                    };
+                }
+
+                _track.sequencer.generate_mosaic_sequence(
+                    &_track.mosaic_target,
+                    _mosaic,
+                    &self.synth_configuration,
+                );
+
+                return Ok(());
+            }
+
+            // Ścieżka z syntezą synchroniczną umieszcza zdarzenia w odstępach okresów sampla, wyznaczanych jednokrotnie.
+            if let Some(_pitch_synchronous) = &_track.pitch_synchronous {
+                if _track.pitch_marks.marks.is_empty() == true {
+                    _track.pitch_marks = match PitchMarks::load(
+                        &_track.grains_properties,
+                        _pitch_synchronous,
+                        _track_number,
+                    ) {
+                        Ok(_pitch_marks) => _pitch_marks,
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                    };
+
+                    if self.verbosity >= Verbosity::Verbose {
+                        let voiced: Vec<f64> = _track
+                            .pitch_marks
+                            .marks
+                            .iter()
+                            .filter(|_mark| _mark.voiced == true)
+                            .map(|_mark| 1.0 / _mark.period_s)
+                            .collect();
+
+                        println!(
+                            "\tścieżka: \'{}\' - znaczniki okresów: {} (dźwięczne: {}, wysokość: {:.1} ... {:.1} Hz)",
+                            _track.track_properties.track_name,
+                            _track.pitch_marks.marks.len(),
+                            voiced.len(),
+                            voiced.iter().cloned().fold(f64::INFINITY, f64::min),
+                            voiced.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+                        );
+                    }
+                }
+
+                _track.sequencer.generate_pitch_synchronous_sequence(
+                    &_track.pitch_marks,
+                    _pitch_synchronous,
+                    &self.synth_configuration,
+                );
+
+                return Ok(());
+            }
+
+            // Obwiednia pliku prowadzącego modulująca gęstość i głośność zdarzeń.
+            let guide = match &_track.guide_file {
+                Some(_guide_file) => {
+                    match Envelope::load(
+                        _guide_file,
+                        &_track.guide_envelope.clone().unwrap_or_default(),
+                    ) {
+                        Ok(_guide) => Some(_guide),
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                    }
+                }
+                None => None,
+            };
+
+            _track.sequencer.generate_sequence(
+                &_track.beat_sequence,
+                &self.synth_configuration,
+                guide.as_ref(),
+            );
+
+            return Ok(());
+        }
+
+        // Generuje sekwencje zdarzeń wszystkich ścieżek i wypisuje je bez syntezy dźwięku.
+        pub fn print_events(&mut self) -> Result<(), String> {
+            let mut tracks = std::mem::take(&mut self.tracks);
+            let engine_sampling_rate = self.synth_configuration.engine_sampling_rate as f64;
+
+            for (_track_number, track) in tracks.iter_mut().enumerate() {
+                match self.sequence_track(track, _track_number + 1, 1) {
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                    _ => {}
+                }
+
+                println!(
+                    "\nŚcieżka: \'{}\' ({} zdarzeń):\n\t{:>10} {:>12} {:>10} {:>10} {:>10} {:>10}",
+                    track.track_properties.track_name,
+                    track.sequencer.sequence.len(),
+                    "próbka",
+                    "czas [s]",
+                    "głośność",
+                    "panorama",
+                    "wysokość",
+                    "długość"
+                );
+
+                for event in track.sequencer.sequence.iter() {
+                    println!(
+                        "\t{:>10} {:>12.6} {:>10.4} {:>10.4} {:>10.4} {:>10.4}",
+                        event.start_index,
+                        event.start_index as f64 / engine_sampling_rate,
+                        event.volume,
+                        event.panorama,
+                        event.pitch,
+                        event.length
+                    );
+                }
+            }
+
+            self.tracks = tracks;
+
+            return Ok(());
+        }
+
+        // Syntetyzuje i zapisuje wszystkie warianty ścieżek, przy wielu wariantach zapisuje także manifest.
+        pub fn render(&mut self) -> Result<(), String> {
+            let variations = self.synth_configuration.variations;
+
+            for _take in 1..=variations {
+                if variations > 1 && self.verbosity >= Verbosity::Normal {
+                    println!(
+                        "\nWariant [{}/{}] -> ziarno: {}",
+                        _take,
+                        variations,
+                        self.take_seed(_take)
+                    );
+                }
+
+                match self.run(_take) {
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                    _ => {}
+                }
+
+                match self.save_tracks(_take) {
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                    _ => {}
+                }
+            }
+
+            if variations > 1 {
+                return self.save_manifest();
+            }
+
+            return Ok(());
+        }
+
         // Wczytuje sample, dzieli je na granulki i syntetyzuje ścieżki dźwiękowe.
-        pub fn run(&mut self) -> Result<(), String> {
-            for track in self.tracks.iter_mut() {
-                match track
-                    .sampler
-                    .prepare(&self.synth_configuration, &track.grains_properties)
-                {
+        pub fn run(&mut self, _take: usize) -> Result<(), String> {
+            let mut tracks = std::mem::take(&mut self.tracks);
+
+            for (_track_number, track) in tracks.iter_mut().enumerate() {
+                match self.sequence_track(track, _track_number + 1, _take) {
                     Err(_error) => {
                         return Err(_error);
                     }
                     _ => {}
                 }
 
-                track
-                    .sequencer
-                    .generate_sequence(&track.beat_sequence, &self.synth_configuration);
+                // Ścieżka z syntezą synchroniczną wycina granulki wokół znaczników okresów, a ścieżka odtwarzająca wykaz
+                // zdarzeń wycina granulki wskazane przez wykaz, bez losowania.
+                if let Some(_pitch_synchronous) = &track.pitch_synchronous {
+                    match track.sampler.prepare_pitch_synchronous(
+                        &track.pitch_marks,
+                        _pitch_synchronous.periods,
+                        &self.synth_configuration,
+                        &track.grains_properties,
+                        _track_number + 1,
+                    ) {
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                        _ => {}
+                    }
+                } else if track.sequence_file.is_none() == true {
+                    match track.sampler.prepare(
+                        &self.synth_configuration,
+                        &track.grains_properties,
+                        _track_number + 1,
+                    ) {
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                        _ => {}
+                    }
+                }
 
-                let beat_size = ((self.synth_configuration.beat_length_ms as f64 / 1000.0)
-                    * self.synth_configuration.engine_sampling_rate as f64)
-                    .round() as usize;
-                let canva_size = beat_size * (track.beat_sequence.len() + 2);
+                // Granulki ścieżki z mozaiką są indeksowane wektorami cech porównywanymi z ramkami dźwięku docelowego.
+                if let Some(_mosaic) = &track.mosaic {
+                    track.sampler.index_grains(
+                        &|_grain_descriptors| mosaic_features(_grain_descriptors, &_mosaic.features),
+                        &mosaic_weights(&_mosaic.features),
+                        &self.synth_configuration,
+                    );
+                }
 
-                track.canva.blank(canva_size);
+                track.canva.blank(track.sequencer.length);
+
+                // Położenie zdarzenia w ścieżce (0 ... 1) wyznacza wartości docelowe deskryptorów granulek.
+                let track_length = track.sequencer.length.max(1) as f64;
 
                 let left_volume_track: f64;
                 let rigth_volume_track: f64;
@@ -109,8 +484,122 @@ pub mod synth {
                     rigth_volume_track = 1.0;
                 }
 
-                for event in track.sequencer.sequence.iter() {
-                    let grain = track.sampler.sample();
+                for (_event_index, event) in track.sequencer.sequence.iter_mut().enumerate() {
+                    let replayed_grain: AudioBuffer;
+                    let scaled_grain: AudioBuffer;
+                    let transposed_grain: AudioBuffer;
+
+                    let grain = match (&track.sequence_file, &event.grain) {
+                        (Some(_), Some(_event_grain)) => {
+                            replayed_grain = match track.sampler.grain_at(
+                                _event_grain,
+                                _event_index + 1,
+                                &track.grains_properties.window_function,
+                                &self.synth_configuration,
+                                &track.grains_properties,
+                            ) {
+                                Ok(_replayed_grain) => _replayed_grain,
+                                Err(_error) => {
+                                    return Err(_error);
+                                }
+                            };
+
+                            &replayed_grain
+                        }
+                        _ => {
+                            let sampled_grain = match (&track.mosaic, event.frame, event.pitch_mark) {
+                                (_, _, Some(_pitch_mark)) => track.sampler.pitch_mark_grain(_pitch_mark),
+                                (Some(_mosaic), Some(_frame), _) => {
+                                    let frame = &track.mosaic_target.frames[_frame];
+                                    let sampled_grain = track.sampler.sample_nearest(
+                                        &mosaic_features(frame, &_mosaic.features),
+                                        _mosaic.neighbours,
+                                    );
+
+                                    // Dopasowanie głośności granulki do głośności ramki dźwięku docelowego.
+                                    let grain_rms = sampled_grain.buffer.rms();
+
+                                    if _mosaic.match_loudness == true && grain_rms > 0.0 {
+                                        event.volume *= (frame.rms / grain_rms).min(MOSAIC_GAIN_MAX);
+                                    }
+
+                                    sampled_grain
+                                }
+                                _ => match &track.grains_properties.descriptor_selection {
+                                    Some(_descriptor_selection) => track.sampler.sample_by_descriptors(
+                                        _descriptor_selection,
+                                        event.start_index as f64 / track_length,
+                                    ),
+                                    // Sampel źródłowy granulki jest losowany zgodnie z wagami sampli w położeniu zdarzenia.
+                                    None if track.grains_properties.sources.len() > 1 => {
+                                        let position = event.start_index as f64 / track_length;
+                                        let weights: Vec<f64> = track
+                                            .grains_properties
+                                            .sources
+                                            .iter()
+                                            .map(|_source| _source.weight.value_at(position).max(0.0))
+                                            .collect();
+
+                                        track.sampler.sample_weighted(&weights)
+                                    }
+                                    None => track.sampler.sample(),
+                                },
+                            };
+                            let (source_offset_s, grain_pitch, source) =
+                                (sampled_grain.source_offset_s, sampled_grain.pitch, sampled_grain.source);
+                            let scaled_length = (sampled_grain.buffer.len() as f64 * event.length).round() as usize;
+
+                            // Granulka o zmienionej długości jest wycinana ponownie z tego samego miejsca sampla.
+                            let sampled_buffer = if event.length != 1.0 {
+                                scaled_grain = match track.sampler.grain_at(
+                                    &EventGrain {
+                                        source_offset_s,
+                                        length: scaled_length,
+                                        pitch: grain_pitch,
+                                        source,
+                                        transposed_length: 0,
+                                    },
+                                    _event_index + 1,
+                                    &track.grains_properties.window_function,
+                                    &self.synth_configuration,
+                                    &track.grains_properties,
+                                ) {
+                                    Ok(_scaled_grain) => _scaled_grain,
+                                    Err(_error) => {
+                                        return Err(_error);
+                                    }
+                                };
+
+                                &scaled_grain
+                            } else {
+                                &sampled_grain.buffer
+                            };
+
+                            event.grain = Some(EventGrain {
+                                source_offset_s,
+                                length: sampled_buffer.len(),
+                                pitch: grain_pitch,
+                                source,
+                                transposed_length: 0,
+                            });
+
+                            sampled_buffer
+                        }
+                    };
+
+                    // Granulka jest transponowana o wysokość zdarzenia po nałożeniu okna czasowego, tak samo przy syntezie
+                    // i przy odtwarzaniu wykazu zdarzeń.
+                    let grain = if event.pitch != 1.0 {
+                        transposed_grain =
+                            Sampler::transpose_grain(grain, event.pitch, &self.synth_configuration);
+                        &transposed_grain
+                    } else {
+                        grain
+                    };
+
+                    if let Some(_event_grain) = event.grain.as_mut() {
+                        _event_grain.transposed_length = grain.len();
+                    }
 
                     let left_volume: f64;
                     let rigth_volume: f64;
@@ -168,21 +657,179 @@ pub mod synth {
                 }
 
                 track.canva.normalize(track.track_properties.track_normalization_level);
+
+                if self.verbosity >= Verbosity::Verbose && track.sampler.descriptors.is_empty() == false {
+                    let descriptors = &track.sampler.descriptors;
+                    let range = |_value: &dyn Fn(&GrainDescriptors) -> f64| -> (f64, f64) {
+                        return descriptors.iter().map(_value).fold(
+                            (f64::INFINITY, f64::NEG_INFINITY),
+                            |(_min, _max), _value| (_min.min(_value), _max.max(_value)),
+                        );
+                    };
+
+                    let rms = range(&|_descriptors| _descriptors.rms);
+                    let centroid = range(&|_descriptors| _descriptors.centroid_hz);
+                    let zero_crossing_rate = range(&|_descriptors| _descriptors.zero_crossing_rate);
+                    let flatness = range(&|_descriptors| _descriptors.flatness);
+                    let pitch = range(&|_descriptors| _descriptors.pitch_hz);
+
+                    println!(
+                        "\tdeskryptory granulek ścieżki: \'{}\' ->\n\t\t# rms: {:.4} ... {:.4}\n\t\t# centroid: {:.1} ... {:.1} Hz\n\t\t# zero_crossing_rate: {:.4} ... {:.4}\n\t\t# flatness: {:.4} ... {:.4}\n\t\t# pitch: {:.1} ... {:.1} Hz",
+                        track.track_properties.track_name, rms.0, rms.1, centroid.0, centroid.1, zero_crossing_rate.0, zero_crossing_rate.1, flatness.0, flatness.1, pitch.0, pitch.1
+                    );
+                }
+
+                if self.verbosity >= Verbosity::Verbose {
+                    println!(
+                        "\tzsyntetyzowano ścieżkę: \'{}\' ({} zdarzeń, {} granulek)",
+                        track.track_properties.track_name,
+                        track.sequencer.sequence.len(),
+                        track.sampler.grains_buffer.len()
+                    );
+                }
             }
 
+            self.tracks = tracks;
+
             return Ok(());
         }
 
+        // Wyznacza ścieżkę pliku wyjściowego ścieżki zgodnie z szablonem nazwy i zasadą nadpisywania plików,
+        // zasada obejmuje także pliki zapisywane obok pliku dźwiękowego. Żaden z plików nie może być plikiem
+        // konfiguracyjnym syntezy.
+        fn output_file_path(&self, _track: &Track, _take: usize) -> Result<String, String> {
+            let file_name = self.synth_configuration.output_file_name(
+                &_track.track_properties.track_name,
+                self.take_seed(_take),
+                _take,
+            );
+            let output_file_path = format!(
+                "{}/{}",
+                self.synth_configuration.output_directory, file_name
+            );
+
+            let existing_file = self
+                .output_files(&output_file_path)
+                .into_iter()
+                .find(|_file_path| Path::new(_file_path).exists() == true);
+
+            let output_file_path = match (existing_file, self.synth_configuration.overwrite) {
+                (None, _) | (Some(_), OverwritePolicy::Always) => output_file_path,
+                (Some(_existing_file), OverwritePolicy::Never) => {
+                    return Err(format!(
+                        "Błąd ->\n\tplik: \'{}\' już istnieje (\'overwrite\': \'never\') :/",
+                        _existing_file
+                    ));
+                }
+                (Some(_), OverwritePolicy::Increment) => {
+                    let stem = &output_file_path[..output_file_path.len() - ".wav".len()];
+                    let extension = &output_file_path[output_file_path.len() - ".wav".len()..];
+                    let mut copy_number: usize = 2;
+
+                    loop {
+                        let incremented_file_path =
+                            format!("{} ({}){}", stem, copy_number, extension);
+
+                        if self
+                            .output_files(&incremented_file_path)
+                            .iter()
+                            .all(|_file_path| Path::new(_file_path).exists() == false)
+                        {
+                            break incremented_file_path;
+                        }
+
+                        copy_number += 1;
+                    }
+                }
+            };
+
+            if let Some(_config_file_path) = &self.config_file_path {
+                for file_path in self.output_files(&output_file_path).iter() {
+                    if same_file(file_path, _config_file_path) == true {
+                        return Err(format!(
+                            "Błąd ->\n\tplik wyjściowy: \'{}\' nadpisałby plik konfiguracyjny syntezy (należy zmienić \'output_file_template\' lub \'output_directory\') :/",
+                            file_path
+                        ));
+                    }
+                }
+            }
+
+            return Ok(output_file_path);
+        }
+
+        // Plik dźwiękowy ścieżki i pliki zapisywane obok niego: pełna konfiguracja ścieżki i wykazy zdarzeń.
+        fn output_files(&self, _output_file_path: &String) -> Vec<String> {
+            let mut output_files = vec![
+                _output_file_path.clone(),
+                self.sidecar_file_path(_output_file_path, RESOLVED_CONFIGURATION_SUFFIX),
+            ];
+
+            for _format in self.synth_configuration.export_events.iter() {
+                output_files.push(self.sidecar_file_path(_output_file_path, _format.file_suffix()));
+            }
+
+            return output_files;
+        }
+
+        // Ścieżka pliku zapisywanego obok pliku dźwiękowego (nazwa pliku dźwiękowego z podanym przyrostkiem).
+        fn sidecar_file_path(&self, _output_file_path: &String, _suffix: &str) -> String {
+            return format!(
+                "{}{}",
+                &_output_file_path[.._output_file_path.len() - ".wav".len()],
+                _suffix
+            );
+        }
+
+        // Zapisuje obok pliku dźwiękowego pełną konfigurację ścieżki (z wartościami domyślnymi i wyznaczonymi ścieżkami),
+        // ziarno wariantu jest zapisywane jako ziarno globalne, więc plik pozwala odtworzyć syntezę ścieżki.
+        fn save_resolved_configuration(
+            &self,
+            _track: &Track,
+            _take: usize,
+            _output_file_path: &String,
+        ) -> Result<String, String> {
+            let configuration_file_path =
+                self.sidecar_file_path(_output_file_path, RESOLVED_CONFIGURATION_SUFFIX);
+
+            let mut synth_configuration_value = to_value(&self.synth_configuration).unwrap();
+
+            synth_configuration_value["seed"] = json!(self.take_seed(_take));
+            synth_configuration_value["variations"] = json!(1);
+
+            let configuration_value = json!({
+                "SynthConfiguration": synth_configuration_value,
+                "Tracks": [to_value(_track).unwrap()]
+            });
+
+            match save_config_file(&configuration_value, &configuration_file_path) {
+                Err(_error) => {
+                    return Err(_error);
+                }
+                _ => {}
+            }
+
+            return Ok(configuration_file_path);
+        }
+
         // Zapisuję zsyntetyzowane ścieżki do plików dźwiękowych.
-        pub fn save_tracks(&self) -> Result<(), String> {
-            println!("\nWyjście:");
+        pub fn save_tracks(&mut self, _take: usize) -> Result<(), String> {
+            if self.verbosity >= Verbosity::Normal {
+                println!("\nWyjście:");
+            }
 
             for track in self.tracks.iter() {
+                let output_file_path = match self.output_file_path(track, _take) {
+                    Ok(_output_file_path) => _output_file_path,
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                };
+
                 if self.synth_configuration.engine_sampling_rate
                     == self.synth_configuration.output_sampling_rate
                 {
                     match track.canva.save_audio(
-                        &format!("{}/{}.wav", self.synth_configuration.output_directory, track.track_properties.track_name),
+                        &output_file_path,
                         self.synth_configuration.output_sampling_rate,
                         self.synth_configuration.output_bit_depth,
                     ) {
@@ -216,7 +863,7 @@ pub mod synth {
                         AudioBuffer::Stereo([resampled_audio_left, resampled_audio_rigth]);
 
                     match audio_buffer_output.save_audio(
-                        &format!("{}/{}.wav", self.synth_configuration.output_directory, track.track_properties.track_name),
+                        &output_file_path,
                         self.synth_configuration.output_sampling_rate,
                         self.synth_configuration.output_bit_depth,
                     ) {
@@ -227,13 +874,206 @@ pub mod synth {
                     }
                 }
 
-                println!(
-                    "\tzapisano ścieżkę: \'{0}\' jako: \'{0}.wav\' w katalogu \'{1}\'",
-                    track.track_properties.track_name, self.synth_configuration.output_directory
-                );
+                let configuration_file_path =
+                    match self.save_resolved_configuration(track, _take, &output_file_path) {
+                        Ok(_configuration_file_path) => _configuration_file_path,
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                    };
+
+                // Wykazy zdarzeń ścieżki zapisywane obok pliku dźwiękowego (zasadę nadpisywania plików sprawdza
+                // 'output_file_path').
+                let mut events_files: Vec<String> = Vec::new();
+
+                for _format in self.synth_configuration.export_events.iter() {
+                    let events_file_path = self.sidecar_file_path(&output_file_path, _format.file_suffix());
+
+                    match save_events(
+                        track,
+                        &self.synth_configuration,
+                        self.take_seed(_take),
+                        *_format,
+                        &events_file_path,
+                    ) {
+                        Err(_error) => {
+                            return Err(_error);
+                        }
+                        _ => {}
+                    }
+
+                    events_files.push(events_file_path);
+                }
+
+                if self.verbosity >= Verbosity::Normal {
+                    println!(
+                        "\tzapisano ścieżkę: \'{}\' jako: \'{}\'",
+                        track.track_properties.track_name, output_file_path
+                    );
+                }
+
+                if self.verbosity >= Verbosity::Verbose {
+                    for events_file_path in events_files.iter() {
+                        println!("\t\tzapisano wykaz zdarzeń: \'{}\'", events_file_path);
+                    }
+                }
+
+                let mut manifest_entry = json!({
+                    "file": output_file_path,
+                    "configuration": configuration_file_path,
+                    "track": track.track_properties.track_name,
+                    "take": _take,
+                    "seed": self.take_seed(_take),
+                    "parameters": {
+                        "sample_file_path": track.grains_properties.sample_file_path,
+                        "grains_count": track.grains_properties.grains_count,
+                        "beats": track.beat_sequence.len(),
+                        "beat_length_ms": self.synth_configuration.beat_length_ms,
+                        "engine_sampling_rate": self.synth_configuration.engine_sampling_rate,
+                        "output_sampling_rate": self.synth_configuration.output_sampling_rate,
+                        "output_bit_depth": self.synth_configuration.output_bit_depth
+                    }
+                });
+
+                if track.grains_properties.sources.is_empty() == false {
+                    manifest_entry["parameters"]["sources"] = to_value(&track.grains_properties.sources).unwrap();
+                }
+
+                if track.markov_path.is_empty() == false {
+                    manifest_entry["markov_path"] = json!(track.markov_path);
+                }
+
+                if events_files.is_empty() == false {
+                    manifest_entry["events"] = json!(events_files);
+                }
+
+                self.manifest.push(manifest_entry);
             }
 
             return Ok(());
         }
+
+        // Zapisuje manifest wariantów: plik wyjściowy, ziarno (do odtworzenia przez '--seed') i parametry syntezy.
+        fn save_manifest(&self) -> Result<(), String> {
+            let manifest_file_path = format!(
+                "{}/manifest.json",
+                self.synth_configuration.output_directory
+            );
+
+            match write(
+                Path::new(&manifest_file_path),
+                to_string_pretty(&self.manifest).unwrap(),
+            ) {
+                Err(_system_error) => {
+                    return Err(format!(
+                        "Błąd ->\n\tnie można utworzyć pliku \'{}\'.\n\tSystem error: {} :/",
+                        manifest_file_path, _system_error
+                    ));
+                }
+                _ => {}
+            }
+
+            if self.verbosity >= Verbosity::Normal {
+                println!("\nzapisano manifest wariantów: \'{}\'", manifest_file_path);
+            }
+
+            return Ok(());
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::fs::{create_dir_all, remove_dir_all};
+
+        use serde_json::json;
+
+        use super::*;
+
+        // Synteza pojedynczej ścieżki (wariant 1) bez zapisu plików.
+        fn synthesize(_json_file_value: &Value) -> GranularSynth {
+            let mut arguments = Arguments::default();
+            arguments.verbosity = Verbosity::Quiet;
+
+            let mut granular_synth = GranularSynth::configure(_json_file_value, &arguments).unwrap();
+            granular_synth.run(1).unwrap();
+
+            return granular_synth;
+        }
+
+        fn canva(_granular_synth: &mut GranularSynth) -> (Vec<f64>, Vec<f64>) {
+            let canva = &mut _granular_synth.tracks[0].canva;
+
+            return (canva.left().clone(), canva.rigth().clone());
+        }
+
+        #[test]
+        fn replayed_events_reproduce_transposed_grains() {
+            let directory = std::env::temp_dir().join(format!("granular_synth_replay_{}", std::process::id()));
+            let directory_path = directory.to_string_lossy().to_string();
+            let sample_file_path = directory.join("sample.wav").to_string_lossy().to_string();
+
+            create_dir_all(&directory).unwrap();
+
+            // Sampel z dźwiękiem harmonicznym o zmiennej amplitudzie (1 s, 48 kHz).
+            let sample: Vec<f64> = (0..48_000)
+                .map(|_index| {
+                    let time = _index as f64 / 48_000.0;
+
+                    (0.5 + 0.4 * (time * 3.0).sin())
+                        * ((time * 220.0 * std::f64::consts::TAU).sin()
+                            + 0.3 * (time * 660.0 * std::f64::consts::TAU).sin())
+                        * 0.6
+                })
+                .collect();
+            AudioBuffer::Mono([sample])
+                .save_audio(&sample_file_path, 48_000, 16)
+                .unwrap();
+
+            // Mnożniki wysokości i długości granulek beatów - wysokość zdarzeń różna od 1.0.
+            let mut original = synthesize(&json!({
+                "SynthConfiguration": {
+                    "seed": 5,
+                    "output_directory": directory_path,
+                    "export_events": ["json", "csv"]
+                },
+                "Tracks": [{
+                    "grains_properties": { "sample_file_path": sample_file_path, "grains_count": 8 },
+                    "beat_sequence": [
+                        { "subdivisions": 6, "grains_pitch_multiplier": 1.5, "grains_length_multiplier": 0.5 },
+                        { "subdivisions": 4, "grains_pitch_multiplier": 0.75 }
+                    ]
+                }]
+            }));
+            original.save_tracks(1).unwrap();
+
+            assert!(original.tracks[0]
+                .sequencer
+                .sequence
+                .iter()
+                .any(|_event| _event.pitch != 1.0));
+
+            let replayed = |_format: &str| -> (Vec<f64>, Vec<f64>) {
+                return canva(&mut synthesize(&json!({
+                    "SynthConfiguration": { "seed": 9, "output_directory": directory_path },
+                    "Tracks": [{
+                        "grains_properties": { "sample_file_path": sample_file_path },
+                        "sequence_file": format!("{}/track_1.events.{}", directory_path, _format)
+                    }]
+                })));
+            };
+
+            let (left, rigth) = canva(&mut original);
+            let (json_left, json_rigth) = replayed("json");
+            let (csv_left, csv_rigth) = replayed("csv");
+
+            remove_dir_all(&directory).unwrap();
+
+            assert!(json_left == left && json_rigth == rigth);
+
+            // Wykaz '.csv' nie zawiera długości syntezy - odtworzenie kończy się wraz z ostatnią granulką.
+            assert!(csv_left.len() <= left.len());
+            assert!(csv_left[..] == left[..csv_left.len()] && csv_rigth[..] == rigth[..csv_rigth.len()]);
+            assert!(left[csv_left.len()..].iter().all(|_sample| *_sample == 0.0));
+        }
     }
 }
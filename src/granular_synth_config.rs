@@ -1,66 +1,544 @@
 pub mod tools {
-    use std::collections::HashMap;
-    use std::fs::read_to_string;
-    use std::path::Path;
-
-    use serde::Deserialize;
-    use serde_json::{from_str, from_value, Value};
+    use std::collections::{BTreeMap, HashMap};
+    use std::env;
+    use std::fmt;
+    use std::fs::{read_to_string, write};
+    use std::path::{Component, Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use serde_json::{from_str, json, to_string_pretty, to_value, Value};
+    use serde_path_to_error::Segment;
 
     use crate::audio::tools::AudioBuffer;
+    use crate::descriptors::core::{MosaicTarget, PitchMarks};
     use crate::sampler::core::Sampler;
     use crate::sequencer::core::Sequencer;
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
+    // Błąd danych konfiguracyjnych wraz ze wskaźnikiem JSON (RFC 6901) na błędną wartość.
+    #[derive(Debug)]
+    pub struct ConfigError {
+        pub pointer: String,
+        pub message: String,
+    }
+
+    impl ConfigError {
+        pub fn new(_pointer: String, _message: String) -> Self {
+            return ConfigError {
+                pointer: _pointer,
+                message: _message,
+            };
+        }
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, _formatter: &mut fmt::Formatter) -> fmt::Result {
+            return write!(_formatter, "{}", self.message);
+        }
+    }
+
+    // Deserializuje fragment obiektu Value, w razie błędu wskazuje dokładne miejsce jego wystąpienia.
+    pub fn deserialize_value<T: DeserializeOwned>(
+        _value: &Value,
+        _pointer: &str,
+    ) -> Result<T, ConfigError> {
+        match serde_path_to_error::deserialize(_value.clone()) {
+            Ok(_deserialized) => {
+                return Ok(_deserialized);
+            }
+            Err(_serde_error) => {
+                let mut pointer: String = _pointer.to_string();
+
+                for _segment in _serde_error.path().iter() {
+                    match _segment {
+                        Segment::Seq { index } => pointer.push_str(&format!("/{}", index)),
+                        Segment::Map { key } => pointer
+                            .push_str(&format!("/{}", key.replace("~", "~0").replace("/", "~1"))),
+                        _ => {}
+                    }
+                }
+
+                return Err(ConfigError::new(
+                    pointer,
+                    format!("Serializer Error ->\n\t{} :/", _serde_error.inner()),
+                ));
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Zakresy wartości sprawdzane przez funkcje 'validate' i eksportowane w schemacie JSON pliku konfiguracyjnego.
+    pub const BEAT_LENGTH_MS_RANGE: (f64, f64) = (100.0, 30_000.0);
+    pub const SAMPLING_RATE_RANGE: (u32, u32) = (48_000, 384_000);
+    pub const OUTPUT_BIT_DEPTHS: [u16; 4] = [8, 16, 24, 32];
+    pub const VARIATIONS_RANGE: (usize, usize) = (1, 999);
+    pub const TRACK_NORMALIZATION_LEVEL_RANGE: (f64, f64) = (0.0, 1.0);
+    pub const TRACK_PANORAMA_RANGE: (f64, f64) = (-1.0, 1.0);
+    pub const GRAINS_COUNT_RANGE: (usize, usize) = (4, 1_000_000);
+    pub const GRAINS_LENGTH_MS_MIN: f64 = 10.0;
+    pub const WINDOW_SLOPE_MIN: f64 = 0.1;
+    pub const GRAINS_PITCH_RANGE: (f64, f64) = (0.25, 5.0);
+    pub const GRAINS_LENGTH_MULTIPLIER_RANGE: (f64, f64) = (0.1, 10.0);
+    pub const PERCENTAGE_RANGE: (f64, f64) = (0.0, 100.0);
+    pub const HUMANIZATION_PERCENTS_RANGE: (f64, f64) = (0.0, 50.0);
+    pub const TEMPO_BPM_RANGE: (f64, f64) = (2.0, 600.0);
+    pub const SWING_PERCENT_RANGE: (f64, f64) = (0.0, 99.0);
+    pub const GROOVE_TIMING_PERCENT_RANGE: (f64, f64) = (-99.0, 99.0);
+    pub const GROOVE_VELOCITY_PERCENT_RANGE: (f64, f64) = (-100.0, 100.0);
+    pub const TIME_SIGNATURE_NUMERATOR_RANGE: (u32, u32) = (1, 32);
+    pub const TIME_SIGNATURE_DENOMINATORS: [u32; 6] = [1, 2, 4, 8, 16, 32];
+    pub const MARKOV_BEATS_RANGE: (usize, usize) = (1, 10_000);
+    pub const MIDI_CHANNEL_RANGE: (u8, u8) = (1, 16);
+    pub const MIDI_NOTE_RANGE: (u8, u8) = (0, 127);
+    pub const GUIDE_WINDOW_MS_RANGE: (f64, f64) = (1.0, 1000.0);
+    pub const DESCRIPTOR_NEIGHBOURS_RANGE: (usize, usize) = (1, 1000);
+    pub const MOSAIC_FRAME_MS_RANGE: (f64, f64) = (10.0, 1000.0);
+    pub const MOSAIC_SILENCE_DB_RANGE: (f64, f64) = (-120.0, 0.0);
+    pub const MOSAIC_GAIN_MAX: f64 = 16.0;
+    pub const SOURCE_GAIN_RANGE: (f64, f64) = (0.0, 16.0);
+    pub const ONSET_JITTER_MS_RANGE: (f64, f64) = (0.0, 100.0);
+    pub const ONSET_THRESHOLD_RANGE: (f64, f64) = (1.0, 10.0);
+    pub const ONSET_MIN_INTERVAL_MS_RANGE: (f64, f64) = (1.0, 1000.0);
+    pub const PSOLA_PERIODS_RANGE: (usize, usize) = (1, 8);
+    pub const PSOLA_TIME_STRETCH_RANGE: (f64, f64) = (0.1, 10.0);
+    pub const YIN_THRESHOLD_RANGE: (f64, f64) = (0.01, 1.0);
+
+    // Znaczniki dozwolone w szablonie nazwy pliku wyjściowego.
+    const OUTPUT_FILE_TEMPLATE_PLACEHOLDERS: [&str; 5] = ["track", "seed", "date", "rate", "take"];
+
+    // Bieżąca data (UTC) w formacie RRRR-MM-DD.
+    fn current_date() -> String {
+        let days: i64 = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(_duration) => (_duration.as_secs() / 86_400) as i64,
+            Err(_) => 0,
+        };
+
+        // Zamiana liczby dni od 1970-01-01 na datę w kalendarzu gregoriańskim.
+        let shifted_days = days + 719_468;
+        let era = shifted_days.div_euclid(146_097);
+        let day_of_era = shifted_days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        return format!("{:04}-{:02}-{:02}", year, month, day);
+    }
+
+    // Zachowanie w przypadku istnienia pliku wyjściowego o tej samej nazwie.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum OverwritePolicy {
+        Always,
+        Never,
+        Increment,
+    }
+
+    impl Default for OverwritePolicy {
+        fn default() -> Self {
+            return OverwritePolicy::Always;
+        }
+    }
+
+    // Format wykazu zdarzeń ścieżki zapisywanego obok pliku dźwiękowego.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum EventsFormat {
+        Csv,
+        Json,
+        Midi,
+    }
+
+    impl EventsFormat {
+        // Przyrostek nazwy pliku wykazu zdarzeń (zastępuje rozszerzenie '.wav' pliku dźwiękowego).
+        pub fn file_suffix(&self) -> &'static str {
+            match self {
+                EventsFormat::Csv => {
+                    return ".events.csv";
+                }
+                EventsFormat::Json => {
+                    return ".events.json";
+                }
+                EventsFormat::Midi => {
+                    return ".mid";
+                }
+            }
+        }
+    }
+
+    pub fn default_output_file_template() -> String {
+        return "{track}.wav".to_string();
+    }
+
+    pub fn default_variations() -> usize {
+        return 1;
+    }
+
+    // Wartości domyślne zmiennych konfiguracji pomijanych w pliku konfiguracyjnym.
+    pub fn default_beat_length_ms() -> f64 {
+        return 1000.0;
+    }
+
+    pub fn default_engine_sampling_rate() -> u32 {
+        return 96_000;
+    }
+
+    pub fn default_output_directory() -> String {
+        return ".".to_string();
+    }
+
+    pub fn default_output_bit_depth() -> u16 {
+        return 24;
+    }
+
+    pub fn default_track_normalization_level() -> f64 {
+        return 0.99;
+    }
+
+    pub fn default_grains_count() -> usize {
+        return 256;
+    }
+
+    pub fn default_grains_length_ms() -> GrainsLength {
+        return GrainsLength::Fixed { equal: 100.0 };
+    }
+
+    pub fn default_window_function() -> GWFunction {
+        return GWFunction::SmoothstepRegular { slope: 5.0 };
+    }
+
+    pub fn default_grains_laudness_normalization() -> bool {
+        return true;
+    }
+
+    pub fn default_grains_pitch() -> GrainsPitch {
+        return GrainsPitch::Fixed;
+    }
+
+    pub fn default_grains_alignment() -> GrainsAlignment {
+        return GrainsAlignment::Random;
+    }
+
+    pub fn default_onset_jitter_ms() -> f64 {
+        return 2.0;
+    }
+
+    pub fn default_onset_threshold() -> f64 {
+        return 1.5;
+    }
+
+    pub fn default_onset_min_interval_ms() -> f64 {
+        return 50.0;
+    }
+
+    pub fn default_source_weight() -> DescriptorCurve {
+        return DescriptorCurve::Constant(1.0);
+    }
+
+    pub fn default_source_pitch() -> f64 {
+        return 1.0;
+    }
+
+    pub fn default_source_gain() -> f64 {
+        return 1.0;
+    }
+
+    pub fn default_subdivisions() -> usize {
+        return 16;
+    }
+
+    pub fn default_coverage_percentage() -> f64 {
+        return 100.0;
+    }
+
+    pub fn default_grains_pitch_multiplier() -> f64 {
+        return 1.0;
+    }
+
+    pub fn default_grains_length_multiplier() -> f64 {
+        return 1.0;
+    }
+
+    pub fn default_time_signature() -> (u32, u32) {
+        return (4, 4);
+    }
+
+    pub fn default_midi_root_note() -> u8 {
+        return 60;
+    }
+
+    pub fn default_midi_follow_pitch() -> bool {
+        return true;
+    }
+
+    pub fn default_descriptor_neighbours() -> usize {
+        return 1;
+    }
+
+    pub fn default_mosaic_frame_ms() -> f64 {
+        return 100.0;
+    }
+
+    pub fn default_mosaic_features() -> Vec<MosaicFeature> {
+        return vec![MosaicFeature::Mfcc, MosaicFeature::Centroid, MosaicFeature::Loudness];
+    }
+
+    pub fn default_mosaic_match_loudness() -> bool {
+        return true;
+    }
+
+    pub fn default_mosaic_silence_db() -> f64 {
+        return -60.0;
+    }
+
+    pub fn default_psola_periods() -> usize {
+        return 2;
+    }
+
+    pub fn default_psola_pitch_shift() -> f64 {
+        return 1.0;
+    }
+
+    pub fn default_psola_time_stretch() -> f64 {
+        return 1.0;
+    }
+
+    pub fn default_yin_threshold() -> f64 {
+        return 0.15;
+    }
+
+    pub fn default_guide_window_ms() -> f64 {
+        return 20.0;
+    }
+
+    pub fn default_guide_depth_percents() -> f64 {
+        return 100.0;
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Czas trwania lub położenie w jednostkach muzycznych (beaty, takty) albo w milisekundach.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum MusicalDuration {
+        Beats(f64),
+        Bars(f64),
+        Ms(f64),
+    }
+
+    impl MusicalDuration {
+        // Wartość w beatach, None dla wartości podanej w milisekundach.
+        pub fn in_beats(&self, _beats_per_bar: f64) -> Option<f64> {
+            match self {
+                MusicalDuration::Beats(_beats) => {
+                    return Some(*_beats);
+                }
+                MusicalDuration::Bars(_bars) => {
+                    return Some(*_bars * _beats_per_bar);
+                }
+                MusicalDuration::Ms(_) => {
+                    return None;
+                }
+            }
+        }
+
+        // Przybliżona wartość w milisekundach przy stałym tempie.
+        pub fn approximate_ms(&self, _synth_configuration: &SynthConfiguration) -> f64 {
+            match self {
+                MusicalDuration::Ms(_ms) => {
+                    return *_ms;
+                }
+                _ => {
+                    return self
+                        .in_beats(_synth_configuration.time_signature.0 as f64)
+                        .unwrap_or(1.0)
+                        * _synth_configuration.beat_length_ms;
+                }
+            }
+        }
+
+        fn value(&self) -> f64 {
+            match self {
+                MusicalDuration::Beats(_value)
+                | MusicalDuration::Bars(_value)
+                | MusicalDuration::Ms(_value) => {
+                    return *_value;
+                }
+            }
+        }
+    }
+
+    // Punkt mapy tempa: od pozycji 'at' (liczonej od pierwszego beatu sekwencji) obowiązuje tempo 'tempo_bpm',
+    // przy 'ramp' tempo zmienia się liniowo od poprzedniego punktu (accelerando / ritardando).
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct TempoPoint {
+        pub at: MusicalDuration,
+        pub tempo_bpm: f64,
+
+        #[serde(default)]
+        pub ramp: bool,
+    }
+
     // Konfiguracja syntezatora.
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     pub struct SynthConfiguration {
+        #[serde(default = "default_beat_length_ms")]
         pub beat_length_ms: f64,
+
+        // Tempo w beatach na minutę (beatem jest wartość rytmiczna z mianownika metrum), wyznacza 'beat_length_ms'.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub tempo_bpm: Option<f64>,
+
+        // Metrum (liczba beatów w takcie, wartość rytmiczna beatu).
+        #[serde(default = "default_time_signature")]
+        pub time_signature: (u32, u32),
+
+        #[serde(default)]
+        pub tempo_map: Vec<TempoPoint>,
+
+        #[serde(default = "default_engine_sampling_rate")]
         pub engine_sampling_rate: u32,
+
+        #[serde(default = "default_output_directory")]
         pub output_directory: String,
+
+        // Domyślnie równa częstotliwości próbkowania silnika ('engine_sampling_rate').
+        #[serde(default)]
         pub output_sampling_rate: u32,
+
+        #[serde(default = "default_output_bit_depth")]
         pub output_bit_depth: u16,
+
+        #[serde(default)]
+        pub seed: Option<u64>,
+
+        #[serde(default)]
+        pub overwrite: OverwritePolicy,
+
+        #[serde(default = "default_output_file_template")]
+        pub output_file_template: String,
+
+        #[serde(default)]
+        pub sample_search_paths: Vec<String>,
+
+        #[serde(default = "default_variations")]
+        pub variations: usize,
+
+        // Formaty wykazów zdarzeń zapisywanych obok każdego pliku dźwiękowego.
+        #[serde(default)]
+        pub export_events: Vec<EventsFormat>,
     }
 
     impl SynthConfiguration {
         // Buduje konfigurację na podstawie obiektu Value i sprawdza poprawność wczytanych danych.
-        pub fn load(_json_file_value: &Value) -> Result<Self, Vec<String>> {
+        pub fn load(_json_file_value: &Value) -> Result<Self, Vec<ConfigError>> {
+            // Sekcja 'SynthConfiguration' może zostać pominięta, wszystkie jej zmienne mają wartości domyślne.
             let synth_config_result: Result<SynthConfiguration, _> =
-                from_value(_json_file_value["SynthConfiguration"].clone());
+                match &_json_file_value["SynthConfiguration"] {
+                    Value::Null => deserialize_value(&json!({}), "/SynthConfiguration"),
+                    _synth_config_value => {
+                        deserialize_value(_synth_config_value, "/SynthConfiguration")
+                    }
+                };
 
-            let mut errors: Vec<String> = Vec::new();
+            let mut errors: Vec<ConfigError> = Vec::new();
 
-            let synth_config: SynthConfiguration = match synth_config_result {
+            let mut synth_config: SynthConfiguration = match synth_config_result {
                 Ok(_synth_config) => _synth_config,
-                Err(_serde_error) => {
-                    return Err(vec![format!("Serializer Error ->\n\t{} :/", _serde_error)])
+                Err(_error) => {
+                    return Err(vec![_error]);
                 }
             };
 
-            if synth_config.beat_length_ms < 100.0 || synth_config.beat_length_ms > 30_000.0 {
-                errors.push(
+            if _json_file_value["SynthConfiguration"]["output_sampling_rate"].is_null() == true {
+                synth_config.output_sampling_rate = synth_config.engine_sampling_rate;
+            }
+
+            // Tempo wyznacza długość beatu, podanie sprzecznych wartości obu zmiennych jest błędem.
+            if let Some(_tempo_bpm) = synth_config.tempo_bpm {
+                if _tempo_bpm < TEMPO_BPM_RANGE.0 || _tempo_bpm > TEMPO_BPM_RANGE.1 {
+                    errors.push(ConfigError::new(
+                        "/SynthConfiguration/tempo_bpm".to_string(),
+                        "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'tempo_bpm\' (2.0 ... 600.0) :/"
+                            .to_string(),
+                    ));
+                } else if _json_file_value["SynthConfiguration"]["beat_length_ms"].is_null() == false
+                    && (synth_config.beat_length_ms - 60_000.0 / _tempo_bpm).abs() > 1e-6
+                {
+                    errors.push(ConfigError::new(
+                        "/SynthConfiguration/tempo_bpm".to_string(),
+                        "Błąd danych - \'SynthConfiguration\' ->\n\tnależy podać \'tempo_bpm\' albo \'beat_length_ms\', nie obie zmienne jednocześnie :/"
+                            .to_string(),
+                    ));
+                } else {
+                    synth_config.beat_length_ms = 60_000.0 / _tempo_bpm;
+                }
+            }
+
+            match &mut synth_config.validate_time() {
+                Err(_errors) => {
+                    errors.append(_errors);
+                }
+                _ => {}
+            }
+
+            if synth_config.beat_length_ms < BEAT_LENGTH_MS_RANGE.0
+                || synth_config.beat_length_ms > BEAT_LENGTH_MS_RANGE.1 {
+                errors.push(ConfigError::new(
+                    "/SynthConfiguration/beat_length_ms".to_string(),
                     "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'beat_length_ms\' (100.0 ... 30 000.0) :/"
-                    .to_string()
-                );
+                        .to_string(),
+                ));
             }
-            if synth_config.engine_sampling_rate < 48_000
-                || synth_config.engine_sampling_rate > 384_000
+            if synth_config.engine_sampling_rate < SAMPLING_RATE_RANGE.0
+                || synth_config.engine_sampling_rate > SAMPLING_RATE_RANGE.1
             {
-                errors.push(
+                errors.push(ConfigError::new(
+                    "/SynthConfiguration/engine_sampling_rate".to_string(),
                     "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'engine_sampling_rate\' (48 000 ... 384 000) :/"
-                    .to_string());
+                        .to_string(),
+                ));
             }
-            if synth_config.output_sampling_rate < 48_000
-                || synth_config.output_sampling_rate > 384_000
+            if synth_config.output_sampling_rate < SAMPLING_RATE_RANGE.0
+                || synth_config.output_sampling_rate > SAMPLING_RATE_RANGE.1
             {
-                errors.push(
+                errors.push(ConfigError::new(
+                    "/SynthConfiguration/output_sampling_rate".to_string(),
                     "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'output_sampling_rate\' (48 000 ... 384 000) :/"
-                    .to_string());
+                        .to_string(),
+                ));
             }
-            if matches!(synth_config.output_bit_depth, 8 | 16 | 24 | 32) == false {
-                errors.push(
+            if OUTPUT_BIT_DEPTHS.contains(&synth_config.output_bit_depth) == false {
+                errors.push(ConfigError::new(
+                    "/SynthConfiguration/output_bit_depth".to_string(),
                     "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'output_bit_depth\' (8, 16, 24, 32) :/"
-                    .to_string());
+                        .to_string(),
+                ));
+            }
+            if synth_config.variations < VARIATIONS_RANGE.0
+                || synth_config.variations > VARIATIONS_RANGE.1 {
+                errors.push(ConfigError::new(
+                    "/SynthConfiguration/variations".to_string(),
+                    "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'variations\' (1 ... 999) :/"
+                        .to_string(),
+                ));
+            }
+            match synth_config.validate_output_file_template() {
+                Err(_error) => {
+                    errors.push(_error);
+                }
+                _ => {}
             }
 
             if errors.is_empty() == true {
@@ -69,46 +547,195 @@ pub mod tools {
                 return Err(errors);
             }
         }
+
+        // Sprawdzenie poprawności metrum i mapy tempa.
+        fn validate_time(&self) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let (numerator, denominator) = self.time_signature;
+
+            if numerator < TIME_SIGNATURE_NUMERATOR_RANGE.0
+                || numerator > TIME_SIGNATURE_NUMERATOR_RANGE.1
+                || TIME_SIGNATURE_DENOMINATORS.contains(&denominator) == false
+            {
+                errors.push(ConfigError::new(
+                    "/SynthConfiguration/time_signature".to_string(),
+                    "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'time_signature\' (1 ... 32 / 1, 2, 4, 8, 16, 32) :/"
+                        .to_string(),
+                ));
+            }
+
+            let mut previous_position: f64 = -1.0;
+
+            for (_point_index, _point) in self.tempo_map.iter().enumerate() {
+                if _point.tempo_bpm < TEMPO_BPM_RANGE.0 || _point.tempo_bpm > TEMPO_BPM_RANGE.1 {
+                    errors.push(ConfigError::new(
+                        format!("/SynthConfiguration/tempo_map/{}/tempo_bpm", _point_index),
+                        format!(
+                            "Błąd danych - \'SynthConfiguration\' - tempo: [{}] ->\n\tnieprawidłowa wartość zmiennej: \'tempo_bpm\' (2.0 ... 600.0) :/",
+                            _point_index + 1
+                        ),
+                    ));
+                }
+
+                match _point.at.in_beats(numerator as f64) {
+                    Some(_position) if _position >= 0.0 && _position > previous_position => {
+                        previous_position = _position;
+                    }
+                    _ => {
+                        errors.push(ConfigError::new(
+                            format!("/SynthConfiguration/tempo_map/{}/at", _point_index),
+                            format!(
+                                "Błąd danych - \'SynthConfiguration\' - tempo: [{}] ->\n\tnieprawidłowa wartość zmiennej: \'at\' (położenie w beatach lub taktach, rosnące w kolejnych punktach mapy tempa) :/",
+                                _point_index + 1
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+
+        // Sprawdzenie poprawności szablonu nazwy pliku wyjściowego.
+        fn validate_output_file_template(&self) -> Result<(), ConfigError> {
+            let pointer = "/SynthConfiguration/output_file_template".to_string();
+            let mut remainder: &str = self.output_file_template.as_str();
+
+            if remainder.trim().is_empty() == true {
+                return Err(ConfigError::new(
+                    pointer,
+                    "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'output_file_template\' (\'output_file_template\' nie może być pusty) :/"
+                        .to_string(),
+                ));
+            }
+
+            while let Some(_opening) = remainder.find('{') {
+                let closing = match remainder[_opening..].find('}') {
+                    Some(_closing) => _opening + _closing,
+                    None => {
+                        return Err(ConfigError::new(
+                            pointer,
+                            "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'output_file_template\' (niezamknięty znacznik \'{\') :/"
+                                .to_string(),
+                        ));
+                    }
+                };
+
+                let placeholder = &remainder[_opening + 1..closing];
+
+                if OUTPUT_FILE_TEMPLATE_PLACEHOLDERS.contains(&placeholder) == false {
+                    return Err(ConfigError::new(
+                        pointer,
+                        format!(
+                            "Błąd danych - \'SynthConfiguration\' ->\n\tnieprawidłowa wartość zmiennej: \'output_file_template\' (nieznany znacznik: \'{{{}}}\', dozwolone: {{{}}}) :/",
+                            placeholder,
+                            OUTPUT_FILE_TEMPLATE_PLACEHOLDERS.join("}, {")
+                        ),
+                    ));
+                }
+
+                remainder = &remainder[closing + 1..];
+            }
+
+            return Ok(());
+        }
+
+        // Buduje nazwę pliku wyjściowego ścieżki na podstawie szablonu 'output_file_template'.
+        // Przy wielu wariantach szablon bez znacznika '{take}' otrzymuje przyrostek '_v{take}'.
+        pub fn output_file_name(&self, _track_name: &String, _seed: u64, _take: usize) -> String {
+            let mut template = self.output_file_template.clone();
+
+            if template.to_lowercase().ends_with(".wav") == false {
+                template.push_str(".wav");
+            }
+            if self.variations > 1 && template.contains("{take}") == false {
+                template.insert_str(template.len() - ".wav".len(), "_v{take}");
+            }
+
+            let file_name = template
+                .replace("{track}", _track_name)
+                .replace("{seed}", &_seed.to_string())
+                .replace("{date}", &current_date())
+                .replace("{rate}", &self.output_sampling_rate.to_string())
+                .replace("{take}", &format!("{:02}", _take));
+
+            return file_name;
+        }
     }
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     // Właściwoci ścieżki.
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     pub struct TrackProperties {
+        // Domyślnie 'track_{numer ścieżki}'.
+        #[serde(default)]
         pub track_name: String,
+
+        #[serde(default = "default_track_normalization_level")]
         pub track_normalization_level: f64,
+
+        #[serde(default)]
         pub track_panorama: f64,
     }
 
+    impl Default for TrackProperties {
+        fn default() -> Self {
+            return TrackProperties {
+                track_name: String::new(),
+                track_normalization_level: default_track_normalization_level(),
+                track_panorama: 0.0,
+            };
+        }
+    }
+
     impl TrackProperties {
         // Sprawdza poprawność wczytanych danych konfiguracyjnych ścieżki.
-        fn validate(&self, _track_number: usize) -> Result<(), Vec<String>> {
-            let mut errors: Vec<String> = Vec::new();
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
 
             if self.track_name.is_empty() == true {
-                errors.push(
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/track_properties/track_name", _track_number - 1),
                     format!(
                         "Błąd danych - track: [{}] - \'track_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'track_name\' (\'track_name\' nie może być pusty) :/",
                         _track_number
-                    )
-                )
+                    ),
+                ))
+            }
+            // Nazwa ścieżki trafia do nazwy pliku wyjściowego, więc nie może wskazywać innego katalogu.
+            if self.track_name.contains(['/', '\\']) == true || self.track_name == "." || self.track_name == ".." {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/track_properties/track_name", _track_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - \'track_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'track_name\' (\'track_name\' nie może zawierać znaków \'/\', \'\\\' ani być nazwą \'.\' lub \'..\') :/",
+                        _track_number
+                    ),
+                ))
             }
-            if self.track_normalization_level < 0.0 || self.track_normalization_level > 1.0 {
-                errors.push(
+            if self.track_normalization_level < TRACK_NORMALIZATION_LEVEL_RANGE.0
+                || self.track_normalization_level > TRACK_NORMALIZATION_LEVEL_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/track_properties/track_normalization_level", _track_number - 1),
                     format!(
                         "Błąd danych - track: [{}] - \'track_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'track_normalization_level\' (0.0 < \'track_normalization_level\' < 1.0) :/",
                         _track_number
-                    )
-                )
+                    ),
+                ))
             }
-            if self.track_panorama < -1.0 || self.track_panorama > 1.0 {
-                errors.push(
+            if self.track_panorama < TRACK_PANORAMA_RANGE.0
+                || self.track_panorama > TRACK_PANORAMA_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/track_properties/track_panorama", _track_number - 1),
                     format!(
                         "Błąd danych - track: [{}] - \'track_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'track_panorama\' (-1.0 < \'track_panorama\' < 1.0) :/",
                         _track_number
-                    )
-                )
+                    ),
+                ))
             }
 
             if errors.is_empty() == true {
@@ -122,7 +749,7 @@ pub mod tools {
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     // Typ enumeracyjny długości granulki.
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(tag = "type")]
     pub enum GrainsLength {
         Fixed { equal: f64 },
@@ -135,33 +762,36 @@ pub mod tools {
             &self,
             _synth_configuration: &SynthConfiguration,
             _track_number: usize,
-        ) -> Result<(), String> {
+        ) -> Result<(), ConfigError> {
             match self {
                 GrainsLength::Fixed { equal } => {
-                    if *equal < 10.0 || *equal > _synth_configuration.beat_length_ms - 0.1 {
-                        return Err(
+                    if *equal < GRAINS_LENGTH_MS_MIN || *equal > _synth_configuration.beat_length_ms - 0.1 {
+                        return Err(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_length_ms", _track_number - 1),
                             format!(
                                 "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_length\' (10.0 < \'equal\' < \'beat_length_ms\') :/",
                                 _track_number
-                            )
-                        );
+                            ),
+                        ));
                     }
                 }
                 GrainsLength::Range { from, to } => {
                     if *to <= *from {
-                        return Err(
+                        return Err(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_length_ms", _track_number - 1),
                             format!(
                                 "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_length\' (\'from\' < \'to\') :/",
                                 _track_number
-                            )
-                        );
-                    } else if *from < 10.0 || *to > _synth_configuration.beat_length_ms - 0.1 {
-                        return Err(
+                            ),
+                        ));
+                    } else if *from < GRAINS_LENGTH_MS_MIN || *to > _synth_configuration.beat_length_ms - 0.1 {
+                        return Err(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_length_ms", _track_number - 1),
                             format!(
                                 "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_length\' (\'from\' > 10.0, \'to\' < \'beat_length_ms\') :/",
                                 _track_number
-                            )
-                        );
+                            ),
+                        ));
                     }
                 }
             }
@@ -172,8 +802,9 @@ pub mod tools {
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
-    // Typ enumeracyjny typu okna czasowego granulki.
-    #[derive(Debug, Deserialize)]
+    // Typ enumeracyjny typu okna czasowego granulki. Okno 'Symmetric' narasta przez pierwszą i opada przez
+    // drugą połowę granulki, dzięki czemu granulki nakładające się o połowę długości sumują się do stałej amplitudy.
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(tag = "type")]
     pub enum GWFunction {
         SmoothstepRegular {
@@ -183,6 +814,7 @@ pub mod tools {
             slope_attack: f64,
             slope_release: f64,
         },
+        Symmetric,
     }
 
     impl GWFunction {
@@ -191,36 +823,39 @@ pub mod tools {
             &self,
             _grains_length: &GrainsLength,
             _track_number: usize,
-        ) -> Result<(), String> {
+        ) -> Result<(), ConfigError> {
             match self {
                 GWFunction::SmoothstepRegular { slope } => {
-                    if *slope < 0.1 {
-                        return Err(
+                    if *slope < WINDOW_SLOPE_MIN {
+                        return Err(ConfigError::new(
+                        format!("/Tracks/{}/grains_properties/window_function", _track_number - 1),
                         format!(
                             "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'window_function\' (\'slope\' > 0.1) :/",
                             _track_number
-                        )
-                    );
+                        ),
+                    ));
                     } else {
                         match _grains_length {
                             GrainsLength::Fixed { equal } => {
                                 if 2.0 * *slope + 0.1 > *equal {
-                                    return Err(
+                                    return Err(ConfigError::new(
+                                format!("/Tracks/{}/grains_properties/window_function", _track_number - 1),
                                 format!(
                                     "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'window_function\' (2 x \'slope\' < \'grains_length_ms\': \'equal\') :/",
                                     _track_number
-                                )
-                            );
+                                ),
+                            ));
                                 }
                             }
                             GrainsLength::Range { from, to: _ } => {
                                 if 2.0 * *slope + 0.1 > *from {
-                                    return Err(
+                                    return Err(ConfigError::new(
+                                format!("/Tracks/{}/grains_properties/window_function", _track_number - 1),
                                 format!(
                                     "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'window_function\' (2 x \'slope\' < \'grains_length_ms\': \'from\') :/",
                                     _track_number
-                                )
-                            );
+                                ),
+                            ));
                                 }
                             }
                         };
@@ -230,38 +865,42 @@ pub mod tools {
                     slope_attack,
                     slope_release,
                 } => {
-                    if (*slope_attack < 0.1) || (*slope_release < 0.1) {
-                        return Err(
+                    if (*slope_attack < WINDOW_SLOPE_MIN) || (*slope_release < WINDOW_SLOPE_MIN) {
+                        return Err(ConfigError::new(
+                        format!("/Tracks/{}/grains_properties/window_function", _track_number - 1),
                         format!(
                             "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'window_function\' (\'slope_attack\' > 0.1, \'slope_release\' > 0.1) :/",
                             _track_number
-                        )
-                    );
+                        ),
+                    ));
                     } else {
                         match _grains_length {
                             GrainsLength::Fixed { equal } => {
                                 if *slope_attack + *slope_release + 0.1 > *equal {
-                                    return Err(
+                                    return Err(ConfigError::new(
+                                format!("/Tracks/{}/grains_properties/window_function", _track_number - 1),
                                 format!(
                                     "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'window_function\' (\'slope_attack\' + \'slope_release\' < \'grains_length_ms\': \'equal\') :/",
                                     _track_number
-                                )
-                            );
+                                ),
+                            ));
                                 }
                             }
                             GrainsLength::Range { from, to: _ } => {
                                 if *slope_attack + *slope_release + 0.1 > *from {
-                                    return Err(
+                                    return Err(ConfigError::new(
+                                format!("/Tracks/{}/grains_properties/window_function", _track_number - 1),
                                 format!(
                                     "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'window_function\' (\'slope_attack\' + \'slope_release\' < \'grains_length_ms\': \'from\') :/",
                                     _track_number
-                                )
-                            );
+                                ),
+                            ));
                                 }
                             }
                         };
                     }
                 }
+                GWFunction::Symmetric => {}
             }
 
             return Ok(());
@@ -271,7 +910,7 @@ pub mod tools {
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     // Typ enumeracyjny wysokości dźwięku granulki.
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(tag = "type")]
     pub enum GrainsPitch {
         Fixed,
@@ -280,8 +919,8 @@ pub mod tools {
 
     impl GrainsPitch {
         // Sprawdzenie poprawności wczytanych danych typu enumeracyjnego wysokości dźwieku granulki.
-        fn validate(&self, _track_number: usize) -> Result<(), Vec<String>> {
-            let mut errors: Vec<String> = Vec::new();
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
 
             match self {
                 GrainsPitch::Fixed => {}
@@ -290,21 +929,23 @@ pub mod tools {
                     let mut fraction_correctness: bool = true;
 
                     for (_step_index, _step) in steps.iter().enumerate() {
-                        if _step.0 < 0.25 || _step.0 > 5.0 {
-                            errors.push(
+                        if _step.0 < GRAINS_PITCH_RANGE.0 || _step.0 > GRAINS_PITCH_RANGE.1 {
+                            errors.push(ConfigError::new(
+                                format!("/Tracks/{}/grains_properties/grains_pitch/steps/{}/0", _track_number - 1, _step_index),
                                 format!(
                                     "Błąd danych - track: [{}] - step: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_pitch\' (0.25 < \'pitch\' < 5.0) :/",
                                     _track_number, _step_index + 1
-                                )
-                            );
+                                ),
+                            ));
                         }
-                        if _step.1 < 0.0 || _step.1 > 100.0 {
-                            errors.push(
+                        if _step.1 < PERCENTAGE_RANGE.0 || _step.1 > PERCENTAGE_RANGE.1 {
+                            errors.push(ConfigError::new(
+                                format!("/Tracks/{}/grains_properties/grains_pitch/steps/{}/1", _track_number - 1, _step_index),
                                 format!(
                                     "Błąd danych - track: [{}] - step: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_pitch\' (0% < \'fraction\' < 100%) :/",
                                     _track_number, _step_index + 1
-                                )
-                            );
+                                ),
+                            ));
 
                             fraction_correctness = false;
                         }
@@ -315,12 +956,13 @@ pub mod tools {
                     if (fraction_correctness == true)
                         && (fraction_accumulated < 99.9999 || fraction_accumulated > 100.0001)
                     {
-                        errors.push(
+                        errors.push(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_pitch/steps", _track_number - 1),
                             format!(
                                 "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_pitch\' (\'fraction_total\' = 100%) :/",
                                 _track_number
-                            )
-                        );
+                            ),
+                        ));
                     }
                 }
             }
@@ -335,15 +977,386 @@ pub mod tools {
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
-    // Właściwości granulek.
-    #[derive(Debug, Deserialize)]
-    pub struct GrainsProperties {
-        pub sample_file_path: String,
-        pub grains_count: usize,
+    // Typ enumeracyjny wyrównania początku granulki w samplu: losowy początek, początek w wykrytym onsecie
+    // (z losowym przesunięciem 'jitter_ms') lub losowy początek przesunięty do najbliższego przejścia przez zero.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(tag = "type")]
+    pub enum GrainsAlignment {
+        Random,
+        Onsets {
+            #[serde(default = "default_onset_jitter_ms")]
+            jitter_ms: f64,
+            #[serde(default = "default_onset_threshold")]
+            threshold: f64,
+            #[serde(default = "default_onset_min_interval_ms")]
+            min_interval_ms: f64,
+        },
+        ZeroCrossing,
+    }
+
+    impl GrainsAlignment {
+        // Sprawdzenie poprawności wczytanych danych typu enumeracyjnego wyrównania granulek.
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+
+            match self {
+                GrainsAlignment::Onsets {
+                    jitter_ms,
+                    threshold,
+                    min_interval_ms,
+                } => {
+                    if *jitter_ms < ONSET_JITTER_MS_RANGE.0 || *jitter_ms > ONSET_JITTER_MS_RANGE.1 {
+                        errors.push(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_alignment/jitter_ms", _track_number - 1),
+                            format!(
+                                "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'jitter_ms\' (0 ms < \'jitter_ms\' < 100 ms) :/",
+                                _track_number
+                            ),
+                        ));
+                    }
+                    if *threshold < ONSET_THRESHOLD_RANGE.0 || *threshold > ONSET_THRESHOLD_RANGE.1 {
+                        errors.push(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_alignment/threshold", _track_number - 1),
+                            format!(
+                                "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'threshold\' (1.0 < \'threshold\' < 10.0) :/",
+                                _track_number
+                            ),
+                        ));
+                    }
+                    if *min_interval_ms < ONSET_MIN_INTERVAL_MS_RANGE.0
+                        || *min_interval_ms > ONSET_MIN_INTERVAL_MS_RANGE.1
+                    {
+                        errors.push(ConfigError::new(
+                            format!("/Tracks/{}/grains_properties/grains_alignment/min_interval_ms", _track_number - 1),
+                            format!(
+                                "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'min_interval_ms\' (1 ms < \'min_interval_ms\' < 1000 ms) :/",
+                                _track_number
+                            ),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Deskryptor dźwięku granulki: RMS, środek ciężkości widma (Hz), częstość przejść przez zero (na próbkę),
+    // płaskość widma (0 ... 1) lub wysokość dźwięku (Hz, 0 dla granulek bez wyraźnej wysokości).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Descriptor {
+        Rms,
+        Centroid,
+        ZeroCrossingRate,
+        Flatness,
+        Pitch,
+    }
+
+    // Docelowa wartość deskryptora w funkcji położenia w ścieżce (0 - początek, 1 - koniec ścieżki):
+    // stała, liniowa zmiana od 'from' do 'to' lub łamana wyznaczona przez punkty [położenie, wartość].
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum DescriptorCurve {
+        Constant(f64),
+        Ramp { from: f64, to: f64 },
+        Points(Vec<(f64, f64)>),
+    }
+
+    impl DescriptorCurve {
+        // Wartość docelowa w podanym położeniu ścieżki.
+        pub fn value_at(&self, _position: f64) -> f64 {
+            match self {
+                DescriptorCurve::Constant(_value) => {
+                    return *_value;
+                }
+                DescriptorCurve::Ramp { from, to } => {
+                    return from + (to - from) * _position.clamp(0.0, 1.0);
+                }
+                DescriptorCurve::Points(_points) => {
+                    let next = _points.iter().position(|(_point_position, _)| *_point_position > _position);
+
+                    match next {
+                        Some(0) => {
+                            return _points[0].1;
+                        }
+                        Some(_next) => {
+                            let (start_position, start_value) = _points[_next - 1];
+                            let (end_position, end_value) = _points[_next];
+
+                            return start_value
+                                + (end_value - start_value) * (_position - start_position)
+                                    / (end_position - start_position);
+                        }
+                        None => {
+                            return _points.last().map_or(0.0, |(_, _value)| *_value);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Wartości wyznaczające krzywą (wartość stała, wartości końców lub wartości punktów).
+        pub fn values(&self) -> Vec<f64> {
+            match self {
+                DescriptorCurve::Constant(_value) => {
+                    return vec![*_value];
+                }
+                DescriptorCurve::Ramp { from, to } => {
+                    return vec![*from, *to];
+                }
+                DescriptorCurve::Points(_points) => {
+                    return _points.iter().map(|(_, _value)| *_value).collect();
+                }
+            }
+        }
+
+        // Średnia wartość krzywej w całej ścieżce (wyznaczana w 101 równo rozłożonych położeniach).
+        pub fn mean(&self) -> f64 {
+            return (0..=100)
+                .map(|_step| self.value_at(_step as f64 / 100.0))
+                .sum::<f64>()
+                / 101.0;
+        }
+    }
+
+    // Fragment sampla, z którego wycinane są granulki: zakres czasu (w milisekundach) albo znacznik pliku WAV
+    // o podanej etykiecie (punkt 'cue' z długością 'ltxt' lub do kolejnego punktu, pętla 'smpl').
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum SampleRegion {
+        Range { start_ms: f64, end_ms: f64 },
+        Marker { marker: String },
+    }
+
+    impl SampleRegion {
+        // Sprawdzenie poprawności zakresu fragmentu sampla.
+        fn validate(&self, _pointer: String, _track_number: usize) -> Result<(), ConfigError> {
+            let correctness = match self {
+                SampleRegion::Range { start_ms, end_ms } => *start_ms >= 0.0 && *end_ms > *start_ms,
+                SampleRegion::Marker { marker } => marker.is_empty() == false,
+            };
+
+            if correctness == false {
+                return Err(ConfigError::new(
+                    _pointer,
+                    format!(
+                        "Błąd danych - track: [{}] - \'region\' ->\n\tnieprawidłowy fragment sampla (0.0 <= \'start_ms\' < \'end_ms\', \'marker\' nie może być pusty) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            return Ok(());
+        }
+    }
+
+    // Sampel źródłowy ścieżki: 'weight' wyznacza udział granulek sampla (stały lub zmienny w czasie, jak krzywa
+    // deskryptora), 'pitch' - wysokość dźwięku granulek (mnożona przez 'grains_pitch'), 'gain' - wzmocnienie granulek,
+    // 'region' i 'regions' - fragmenty sampla, z których wycinane są granulki (domyślnie cały sampel).
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct SampleSource {
+        pub file_path: String,
+
+        #[serde(default = "default_source_weight")]
+        pub weight: DescriptorCurve,
+
+        #[serde(default = "default_source_pitch")]
+        pub pitch: f64,
+
+        #[serde(default = "default_source_gain")]
+        pub gain: f64,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub region: Option<SampleRegion>,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub regions: Vec<SampleRegion>,
+    }
+
+    impl SampleSource {
+        // Fragmenty sampla: 'region', a po nim kolejne fragmenty 'regions'.
+        pub fn sample_regions(&self) -> Vec<SampleRegion> {
+            return self.region.iter().chain(self.regions.iter()).cloned().collect();
+        }
+
+        // Sprawdzenie poprawności ustawień sampla źródłowego.
+        fn validate(&self, _track_number: usize, _source_index: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!(
+                "/Tracks/{}/grains_properties/sources/{}",
+                _track_number - 1,
+                _source_index
+            );
+
+            if self.file_path.is_empty() == true {
+                errors.push(ConfigError::new(
+                    format!("{}/file_path", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'sources\' [{}] ->\n\tnieprawidłowa wartość zmiennej: \'file_path\' (\'file_path\' nie może być pusty) :/",
+                        _track_number,
+                        _source_index + 1
+                    ),
+                ));
+            }
+
+            let ordered = match &self.weight {
+                DescriptorCurve::Points(_points) => {
+                    _points.is_empty() == false && _points.windows(2).all(|_pair| _pair[0].0 < _pair[1].0)
+                }
+                _ => true,
+            };
+
+            if ordered == false || self.weight.values().iter().any(|_value| *_value < 0.0) == true {
+                errors.push(ConfigError::new(
+                    format!("{}/weight", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'sources\' [{}] ->\n\twaga sampla nie może być ujemna, punkty krzywej wagi muszą mieć rosnące położenia :/",
+                        _track_number,
+                        _source_index + 1
+                    ),
+                ));
+            }
+
+            if self.pitch < GRAINS_PITCH_RANGE.0 || self.pitch > GRAINS_PITCH_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/pitch", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'sources\' [{}] ->\n\tnieprawidłowa wartość zmiennej: \'pitch\' (0.25 ... 5.0) :/",
+                        _track_number,
+                        _source_index + 1
+                    ),
+                ));
+            }
+
+            if self.gain <= SOURCE_GAIN_RANGE.0 || self.gain > SOURCE_GAIN_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/gain", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'sources\' [{}] ->\n\tnieprawidłowa wartość zmiennej: \'gain\' (0.0 < \'gain\' <= 16.0) :/",
+                        _track_number,
+                        _source_index + 1
+                    ),
+                ));
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Wybór granulek na podstawie deskryptorów: dla każdego zdarzenia wybierana jest losowo jedna z 'neighbours'
+    // granulek najbliższych wartościom docelowym deskryptorów ('neighbours': 1 - zawsze granulka najbliższa).
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct DescriptorSelection {
+        pub targets: BTreeMap<Descriptor, DescriptorCurve>,
+
+        #[serde(default = "default_descriptor_neighbours")]
+        pub neighbours: usize,
+    }
+
+    impl DescriptorSelection {
+        // Sprawdzenie poprawności wartości docelowych i liczby sąsiadów.
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!("/Tracks/{}/grains_properties/descriptor_selection", _track_number - 1);
+
+            if self.targets.is_empty() == true {
+                errors.push(ConfigError::new(
+                    format!("{}/targets", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'descriptor_selection\' ->\n\tnie podano wartości docelowej żadnego deskryptora :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            for (_descriptor, _curve) in self.targets.iter() {
+                if let DescriptorCurve::Points(_points) = _curve {
+                    let ordered = _points.windows(2).all(|_pair| _pair[0].0 < _pair[1].0);
+
+                    if _points.is_empty() == true || ordered == false {
+                        errors.push(ConfigError::new(
+                            format!(
+                                "{}/targets/{}",
+                                pointer,
+                                to_value(_descriptor).unwrap().as_str().unwrap_or_default()
+                            ),
+                            format!(
+                                "Błąd danych - track: [{}] - \'descriptor_selection\' ->\n\tpunkty krzywej deskryptora muszą mieć rosnące położenia (wymagany co najmniej jeden punkt) :/",
+                                _track_number
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            if self.neighbours < DESCRIPTOR_NEIGHBOURS_RANGE.0
+                || self.neighbours > DESCRIPTOR_NEIGHBOURS_RANGE.1
+            {
+                errors.push(ConfigError::new(
+                    format!("{}/neighbours", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'descriptor_selection\' ->\n\tnieprawidłowa wartość zmiennej: \'neighbours\' (1 ... 1000) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Właściwości granulek. Granulki są wycinane z sampla 'sample_file_path' (z jego fragmentów 'region' i 'regions')
+    // albo z listy sampli 'sources'.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct GrainsProperties {
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        pub sample_file_path: String,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub sources: Vec<SampleSource>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub region: Option<SampleRegion>,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub regions: Vec<SampleRegion>,
+
+        #[serde(default = "default_grains_count")]
+        pub grains_count: usize,
+
+        #[serde(default = "default_grains_length_ms")]
         pub grains_length_ms: GrainsLength,
+
+        #[serde(default = "default_window_function")]
         pub window_function: GWFunction,
+
+        #[serde(default = "default_grains_laudness_normalization")]
         pub grains_laudness_normalization: bool,
+
+        #[serde(default = "default_grains_pitch")]
         pub grains_pitch: GrainsPitch,
+
+        #[serde(default = "default_grains_alignment")]
+        pub grains_alignment: GrainsAlignment,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub descriptor_selection: Option<DescriptorSelection>,
     }
 
     impl GrainsProperties {
@@ -352,156 +1365,1595 @@ pub mod tools {
             &self,
             _synth_configuration: &SynthConfiguration,
             _track_number: usize,
-        ) -> Result<(), Vec<String>> {
-            let mut errors: Vec<String> = Vec::new();
+        ) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+
+            if self.sources.is_empty() == true {
+                if self.sample_file_path.is_empty() == true {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/grains_properties/sample_file_path", _track_number - 1),
+                        format!(
+                            "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'sample_file_path\' (\'sample_file_path\' nie może być pusty) :/",
+                            _track_number
+                        ),
+                    ))
+                }
+            } else {
+                if self.sample_file_path.is_empty() == false
+                    || self.region.is_some() == true
+                    || self.regions.is_empty() == false
+                {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/grains_properties/sources", _track_number - 1),
+                        format!(
+                            "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnależy podać \'sample_file_path\' (wraz z \'region\' i \'regions\') albo \'sources\', nie obie zmienne jednocześnie :/",
+                            _track_number
+                        ),
+                    ))
+                }
+
+                for (_source_index, _source) in self.sources.iter().enumerate() {
+                    match &mut _source.validate(_track_number, _source_index) {
+                        Err(_errors) => {
+                            errors.append(_errors);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            for (_source_index, _source) in self.sample_sources().iter().enumerate() {
+                for (_region_index, _region) in _source.sample_regions().iter().enumerate() {
+                    match _region.validate(
+                        self.region_pointer(_track_number, _source_index, _region_index),
+                        _track_number,
+                    ) {
+                        Err(_error) => {
+                            errors.push(_error);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if self.grains_count < GRAINS_COUNT_RANGE.0 || self.grains_count > GRAINS_COUNT_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/grains_properties/grains_count", _track_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_count\' (4 < \'grains_count\' < 1 000 000) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            // Każdy sampel źródłowy otrzymuje co najmniej jedną granulkę dla każdej wysokości dźwięku.
+            let pitch_steps: usize = match &self.grains_pitch {
+                GrainsPitch::Fixed => 1,
+                GrainsPitch::Steps { steps } => steps.len(),
+            };
+            let grains_minimum: usize = self.sample_sources().len() * pitch_steps;
+
+            if self.grains_count < grains_minimum {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/grains_properties/grains_count", _track_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_count\' (\'grains_count\' >= liczba sampli źródłowych x liczba wysokości dźwięku = {}) :/",
+                        _track_number, grains_minimum
+                    ),
+                ));
+            }
+
+            match self
+                .grains_length_ms
+                .validate(_synth_configuration, _track_number)
+            {
+                Err(_error) => {
+                    errors.push(_error);
+                }
+                _ => {
+                    match self
+                        .window_function
+                        .validate(&self.grains_length_ms, _track_number)
+                    {
+                        Err(_error) => {
+                            errors.push(_error);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            match &mut self.grains_pitch.validate(_track_number) {
+                Err(_errors) => {
+                    errors.append(_errors);
+                }
+                _ => {}
+            }
+
+            match &mut self.grains_alignment.validate(_track_number) {
+                Err(_errors) => {
+                    errors.append(_errors);
+                }
+                _ => {}
+            }
+
+            if let Some(_descriptor_selection) = &self.descriptor_selection {
+                match &mut _descriptor_selection.validate(_track_number) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+
+        // Sample źródłowe ścieżki - pojedynczy 'sample_file_path' jest samplem o stałej wadze, bez zmiany
+        // wysokości dźwięku i wzmocnienia.
+        pub fn sample_sources(&self) -> Vec<SampleSource> {
+            if self.sources.is_empty() == false {
+                return self.sources.clone();
+            }
+
+            return vec![SampleSource {
+                file_path: self.sample_file_path.clone(),
+                weight: default_source_weight(),
+                pitch: default_source_pitch(),
+                gain: default_source_gain(),
+                region: self.region.clone(),
+                regions: self.regions.clone(),
+            }];
+        }
+
+        // Wskaźnik JSON fragmentu sampla źródłowego o podanym indeksie (indeks w liście z 'sample_regions').
+        pub fn region_pointer(&self, _track_number: usize, _source_index: usize, _region_index: usize) -> String {
+            let (pointer, has_region) = if self.sources.is_empty() == true {
+                (
+                    format!("/Tracks/{}/grains_properties", _track_number - 1),
+                    self.region.is_some(),
+                )
+            } else {
+                (
+                    format!("/Tracks/{}/grains_properties/sources/{}", _track_number - 1, _source_index),
+                    self.sources[_source_index].region.is_some(),
+                )
+            };
+
+            match (has_region, _region_index) {
+                (true, 0) => {
+                    return format!("{}/region", pointer);
+                }
+                (true, _) => {
+                    return format!("{}/regions/{}", pointer, _region_index - 1);
+                }
+                (false, _) => {
+                    return format!("{}/regions/{}", pointer, _region_index);
+                }
+            }
+        }
+
+        // Wskaźnik JSON ścieżki do pliku sampla źródłowego o podanym indeksie.
+        pub fn source_pointer(&self, _track_number: usize, _source_index: usize) -> String {
+            if self.sources.is_empty() == true {
+                return format!("/Tracks/{}/grains_properties/sample_file_path", _track_number - 1);
+            }
+
+            return format!(
+                "/Tracks/{}/grains_properties/sources/{}/file_path",
+                _track_number - 1,
+                _source_index
+            );
+        }
+
+        // Sprawdzenie, czy wczytany sampel (lub jego fragment '_region_index') jest wystarczająco długi
+        // do wycinania z niego granulek.
+        pub fn validate_sample_length(
+            &self,
+            _sample_length_ms: f64,
+            _track_number: usize,
+            _source_index: usize,
+            _region_index: Option<usize>,
+        ) -> Result<(), ConfigError> {
+            let (pointer, sample_file_path) = match _region_index {
+                Some(_region_index) => (
+                    self.region_pointer(_track_number, _source_index, _region_index),
+                    format!(
+                        "{}\' (fragment: [{}])",
+                        self.sample_sources()[_source_index].file_path,
+                        _region_index + 1
+                    ),
+                ),
+                None => (
+                    self.source_pointer(_track_number, _source_index),
+                    format!("{}\'", self.sample_sources()[_source_index].file_path),
+                ),
+            };
+
+            match self.grains_length_ms {
+                GrainsLength::Fixed { equal } => {
+                    if equal * 1.5 > _sample_length_ms {
+                        return Err(ConfigError::new(
+                            pointer.clone(),
+                            format!(
+                                "Błąd danych ->\n\tsampel: \'{} jest za krótki (sampel musi mieć długość co najmniej: 1.5 x \'grains_length_ms\': \'equal\') :/",
+                                sample_file_path
+                            ),
+                        ));
+                    }
+                }
+                GrainsLength::Range { from: _, to } => {
+                    if to * 1.5 > _sample_length_ms {
+                        return Err(ConfigError::new(
+                            pointer.clone(),
+                            format!(
+                                "Błąd danych ->\n\tsampel: \'{} jest za krótki (sampel musi mieć długość co najmniej: 1.5 x \'grains_length_ms\': \'to\') :/",
+                                sample_file_path
+                            ),
+                        ));
+                    }
+                }
+            };
+
+            return Ok(());
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Przejście parametrów beatu do wartości kolejnego beatu: skokowe, liniowe lub wykładnicze.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Transition {
+        Step,
+        Linear,
+        Exponential,
+    }
+
+    impl Default for Transition {
+        fn default() -> Self {
+            return Transition::Step;
+        }
+    }
+
+    impl Transition {
+        // Wartość parametru w położeniu '_position' (0.0 - początek, 1.0 - koniec beatu) między '_from' i '_to'.
+        // Przejście wykładnicze jest interpolacją geometryczną, dla wartości niedodatnich - liniową.
+        pub fn interpolate(&self, _from: f64, _to: f64, _position: f64) -> f64 {
+            match self {
+                Transition::Step => {
+                    return _from;
+                }
+                Transition::Exponential if _from > 0.0 && _to > 0.0 => {
+                    return _from * (_to / _from).powf(_position);
+                }
+                _ => {
+                    return _from + (_to - _from) * _position;
+                }
+            }
+        }
+
+        // Udział wartości kolejnego beatu (0.0 ... 1.0) w położeniu '_position',
+        // dla przejścia wykładniczego narastający od 1% do 100%.
+        pub fn weight(&self, _position: f64) -> f64 {
+            match self {
+                Transition::Step => {
+                    return 0.0;
+                }
+                Transition::Linear => {
+                    return _position;
+                }
+                Transition::Exponential => {
+                    return (100f64.powf(_position) - 1.0) / 99.0;
+                }
+            }
+        }
+    }
+
+    // Rozkład prawdopodobieństwa odchylenia (w procentach) losowanego dla każdego sub-beatu.
+    // Wartości odchyleń mieszczą się w zakresie +/- wartość maksymalna zmiennej, rozkład 'Gaussian' jest do niego przycinany.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(tag = "type")]
+    pub enum DeviationDistribution {
+        Uniform {
+            percents: f64,
+        },
+        Gaussian {
+            sigma_percents: f64,
+        },
+        Triangular {
+            percents: f64,
+            #[serde(default)]
+            mode_percents: f64,
+        },
+        Beta {
+            percents: f64,
+            alpha: f64,
+            beta: f64,
+        },
+        Discrete {
+            values: Vec<(f64, f64)>,
+        },
+    }
+
+    // Odchylenie parametru sub-beatu: liczba (rozkład jednostajny w zakresie +/- wartość) lub rozkład prawdopodobieństwa.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum Deviation {
+        Percents(f64),
+        Distribution(DeviationDistribution),
+    }
+
+    impl Default for Deviation {
+        fn default() -> Self {
+            return Deviation::Percents(0.0);
+        }
+    }
+
+    impl Deviation {
+        // Brak odchylenia (nie jest losowana żadna wartość).
+        pub fn is_zero(&self) -> bool {
+            return matches!(self, Deviation::Percents(_percents) if *_percents == 0.0);
+        }
+
+        // Sprawdzenie poprawności parametrów rozkładu, '_limit' to maksymalne odchylenie zmiennej w procentach.
+        fn validate(
+            &self,
+            _limit: f64,
+            _pointer: &String,
+            _location: &String,
+            _name: &str,
+        ) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let in_range = |_percents: f64| _percents >= 0.0 && _percents <= _limit;
+
+            let mut error = |_pointer: String, _condition: String| {
+                errors.push(ConfigError::new(
+                    _pointer,
+                    format!(
+                        "Błąd danych - {} - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'{}\' ({}) :/",
+                        _location, _name, _condition
+                    ),
+                ));
+            };
+
+            match self {
+                Deviation::Percents(_percents) => {
+                    if in_range(*_percents) == false {
+                        error(
+                            _pointer.clone(),
+                            format!("0% < \'{}\' < {}%", _name, _limit),
+                        );
+                    }
+                }
+                Deviation::Distribution(DeviationDistribution::Uniform { percents }) => {
+                    if in_range(*percents) == false {
+                        error(
+                            format!("{}/percents", _pointer),
+                            format!("\'Uniform\': 0% < \'percents\' < {}%", _limit),
+                        );
+                    }
+                }
+                Deviation::Distribution(DeviationDistribution::Gaussian { sigma_percents }) => {
+                    if *sigma_percents <= 0.0 || *sigma_percents > _limit {
+                        error(
+                            format!("{}/sigma_percents", _pointer),
+                            format!("\'Gaussian\': 0% < \'sigma_percents\' < {}%", _limit),
+                        );
+                    }
+                }
+                Deviation::Distribution(DeviationDistribution::Triangular {
+                    percents,
+                    mode_percents,
+                }) => {
+                    if in_range(*percents) == false {
+                        error(
+                            format!("{}/percents", _pointer),
+                            format!("\'Triangular\': 0% < \'percents\' < {}%", _limit),
+                        );
+                    } else if mode_percents.abs() > *percents {
+                        error(
+                            format!("{}/mode_percents", _pointer),
+                            "\'Triangular\': -\'percents\' < \'mode_percents\' < \'percents\'".to_string(),
+                        );
+                    }
+                }
+                Deviation::Distribution(DeviationDistribution::Beta {
+                    percents,
+                    alpha,
+                    beta,
+                }) => {
+                    if in_range(*percents) == false {
+                        error(
+                            format!("{}/percents", _pointer),
+                            format!("\'Beta\': 0% < \'percents\' < {}%", _limit),
+                        );
+                    }
+                    if *alpha <= 0.0 || *beta <= 0.0 {
+                        error(
+                            _pointer.clone(),
+                            "\'Beta\': \'alpha\' > 0.0, \'beta\' > 0.0".to_string(),
+                        );
+                    }
+                }
+                Deviation::Distribution(DeviationDistribution::Discrete { values }) => {
+                    if values.iter().map(|(_, _weight)| _weight).sum::<f64>() <= 0.0 {
+                        error(
+                            format!("{}/values", _pointer),
+                            "\'Discrete\': wymagana niepusta lista par [wartość, waga] o dodatniej sumie wag".to_string(),
+                        );
+                    }
+
+                    for (_value_index, (_value, _weight)) in values.iter().enumerate() {
+                        if _value.abs() > _limit || *_weight < 0.0 {
+                            error(
+                                format!("{}/values/{}", _pointer, _value_index),
+                                format!(
+                                    "\'Discrete\' - wartość: [{}]: -{}% < wartość < {}%, waga >= 0.0",
+                                    _value_index + 1, _limit, _limit
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Krok szablonu groove: przesunięcie sub-beatu (w procentach długości sub-beatu, dodatnie opóźnia)
+    // i zmiana głośności (w procentach).
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct GrooveStep {
+        #[serde(default)]
+        pub timing_percent: f64,
+
+        #[serde(default)]
+        pub velocity_percent: f64,
+    }
+
+    // Szablon groove, kroki są przypisywane cyklicznie kolejnym sub-beatom beatu.
+    // W pliku konfiguracyjnym szablon może być też nazwą szablonu z sekcji 'Presets/grooves' lub odwołaniem do pliku ('file').
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct GrooveTemplate {
+        pub steps: Vec<GrooveStep>,
+    }
+
+    impl GrooveTemplate {
+        // Sprawdzenie poprawności kroków szablonu groove.
+        fn validate(&self, _pointer: &String, _location: &String) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+
+            if self.steps.is_empty() == true {
+                errors.push(ConfigError::new(
+                    format!("{}/steps", _pointer),
+                    format!(
+                        "Błąd danych - {} - \'groove\' ->\n\tszablon groove musi zawierać co najmniej jeden krok :/",
+                        _location
+                    ),
+                ));
+            }
+
+            for (_step_index, _step) in self.steps.iter().enumerate() {
+                if _step.timing_percent < GROOVE_TIMING_PERCENT_RANGE.0
+                    || _step.timing_percent > GROOVE_TIMING_PERCENT_RANGE.1
+                {
+                    errors.push(ConfigError::new(
+                        format!("{}/steps/{}/timing_percent", _pointer, _step_index),
+                        format!(
+                            "Błąd danych - {} - \'groove\' - krok: [{}] ->\n\tnieprawidłowa wartość zmiennej: \'timing_percent\' (-99% ... 99%) :/",
+                            _location, _step_index + 1
+                        ),
+                    ));
+                }
+                if _step.velocity_percent < GROOVE_VELOCITY_PERCENT_RANGE.0
+                    || _step.velocity_percent > GROOVE_VELOCITY_PERCENT_RANGE.1
+                {
+                    errors.push(ConfigError::new(
+                        format!("{}/steps/{}/velocity_percent", _pointer, _step_index),
+                        format!(
+                            "Błąd danych - {} - \'groove\' - krok: [{}] ->\n\tnieprawidłowa wartość zmiennej: \'velocity_percent\' (-100% ... 100%) :/",
+                            _location, _step_index + 1
+                        ),
+                    ));
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Konfiguracja beatu.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct BeatConfiguration {
+        #[serde(default = "default_subdivisions")]
+        pub subdivisions: usize,
+
+        #[serde(default = "default_coverage_percentage")]
+        pub coverage_percentage: f64,
+
+        #[serde(default)]
+        pub humanization_percents: Deviation,
+
+        #[serde(default)]
+        pub volume_deviation_percents: Deviation,
+
+        #[serde(default)]
+        pub panorama_deviation_percents: Deviation,
+
+        // Czas trwania beatu w beatach, taktach lub milisekundach, domyślnie jeden beat.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub duration: Option<MusicalDuration>,
+
+        // Opóźnienie parzystych sub-beatów (2., 4., ...) w procentach długości sub-beatu.
+        #[serde(default)]
+        pub swing_percent: f64,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub groove: Option<GrooveTemplate>,
+
+        // Mnożniki wysokości dźwięku i długości granulek zdarzeń beatu.
+        #[serde(default = "default_grains_pitch_multiplier")]
+        pub grains_pitch_multiplier: f64,
+
+        #[serde(default = "default_grains_length_multiplier")]
+        pub grains_length_multiplier: f64,
+
+        // Przejście liczby sub-beatów, pokrycia, odchyleń i mnożników granulek do wartości kolejnego beatu sekwencji.
+        #[serde(default)]
+        pub transition: Transition,
+    }
+
+    impl BeatConfiguration {
+        // Sprawdzenie poprawności wczytanych danych konfiguracji beatu.
+        fn validate(
+            &self,
+            _synth_configuration: &SynthConfiguration,
+            _track_number: usize,
+            _beat_number: usize,
+        ) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+
+            if let Some(_duration) = &self.duration {
+                if _duration.value() <= 0.0 || _duration.value().is_finite() == false {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/beat_sequence/{}/duration", _track_number - 1, _beat_number - 1),
+                        format!(
+                            "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'duration\' (\'duration\' > 0) :/",
+                            _track_number, _beat_number
+                        ),
+                    ));
+                }
+            }
+
+            if self.swing_percent < SWING_PERCENT_RANGE.0 || self.swing_percent > SWING_PERCENT_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/beat_sequence/{}/swing_percent", _track_number - 1, _beat_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'swing_percent\' (0% ... 99%) :/",
+                        _track_number, _beat_number
+                    ),
+                ))
+            }
+            if let Some(_groove) = &self.groove {
+                match &mut _groove.validate(
+                    &format!("/Tracks/{}/beat_sequence/{}/groove", _track_number - 1, _beat_number - 1),
+                    &format!("track: [{}] - beat: [{}]", _track_number, _beat_number),
+                ) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            let beat_length_ms = match &self.duration {
+                Some(_duration) => _duration.approximate_ms(_synth_configuration),
+                None => _synth_configuration.beat_length_ms,
+            };
+
+            if (beat_length_ms / self.subdivisions as f64) < GRAINS_LENGTH_MS_MIN {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/beat_sequence/{}/subdivisions", _track_number - 1, _beat_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'subdivisions\' (\'SynthConfiguration\': \'beat_length_ms\' / \'subdivisions\' > 10) :/",
+                        _track_number, _beat_number
+                    ),
+                ))
+            }
+            if self.coverage_percentage < PERCENTAGE_RANGE.0
+                || self.coverage_percentage > PERCENTAGE_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/beat_sequence/{}/coverage_percentage", _track_number - 1, _beat_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'coverage_percentage\' (0% < \'coverage_percentage\' < 100%) :/",
+                        _track_number, _beat_number
+                    ),
+                ))
+            }
+            if self.grains_pitch_multiplier < GRAINS_PITCH_RANGE.0
+                || self.grains_pitch_multiplier > GRAINS_PITCH_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/beat_sequence/{}/grains_pitch_multiplier", _track_number - 1, _beat_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_pitch_multiplier\' (0.25 ... 5.0) :/",
+                        _track_number, _beat_number
+                    ),
+                ))
+            }
+            if self.grains_length_multiplier < GRAINS_LENGTH_MULTIPLIER_RANGE.0
+                || self.grains_length_multiplier > GRAINS_LENGTH_MULTIPLIER_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/beat_sequence/{}/grains_length_multiplier", _track_number - 1, _beat_number - 1),
+                    format!(
+                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_length_multiplier\' (0.1 ... 10.0) :/",
+                        _track_number, _beat_number
+                    ),
+                ))
+            }
+            let deviations = [
+                ("humanization_percents", &self.humanization_percents, HUMANIZATION_PERCENTS_RANGE.1),
+                ("volume_deviation_percents", &self.volume_deviation_percents, PERCENTAGE_RANGE.1),
+                ("panorama_deviation_percents", &self.panorama_deviation_percents, PERCENTAGE_RANGE.1),
+            ];
+
+            for (_name, _deviation, _limit) in deviations.iter() {
+                match &mut _deviation.validate(
+                    *_limit,
+                    &format!("/Tracks/{}/beat_sequence/{}/{}", _track_number - 1, _beat_number - 1, _name),
+                    &format!("track: [{}] - beat: [{}]", _track_number, _beat_number),
+                    _name,
+                ) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Łańcuch Markowa generujący sekwencję beatów: nazwane stany (konfiguracje beatów) i prawdopodobieństwa przejść
+    // między nimi. Sekwencja ma 'beats' beatów albo trwa co najmniej 'duration', stan początkowy bez 'initial' jest losowany.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct MarkovChain {
+        pub states: BTreeMap<String, BeatConfiguration>,
+        pub transitions: BTreeMap<String, BTreeMap<String, f64>>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub initial: Option<String>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub beats: Option<usize>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub duration: Option<MusicalDuration>,
+    }
+
+    impl MarkovChain {
+        // Sprawdzenie poprawności stanów, macierzy przejść i długości generowanej sekwencji.
+        fn validate(
+            &self,
+            _synth_configuration: &SynthConfiguration,
+            _track_number: usize,
+        ) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!("/Tracks/{}/markov", _track_number - 1);
+
+            let mut error = |_pointer: String, _description: String| {
+                errors.push(ConfigError::new(
+                    _pointer,
+                    format!(
+                        "Błąd danych - track: [{}] - \'markov\' ->\n\t{} :/",
+                        _track_number, _description
+                    ),
+                ));
+            };
+
+            if self.states.is_empty() == true {
+                error(
+                    format!("{}/states", pointer),
+                    "łańcuch Markowa musi zawierać co najmniej jeden stan".to_string(),
+                );
+            }
+
+            if let Some(_initial) = &self.initial {
+                if self.states.contains_key(_initial) == false {
+                    error(
+                        format!("{}/initial", pointer),
+                        format!("stan początkowy: \'{}\' nie występuje w \'states\'", _initial),
+                    );
+                }
+            }
+
+            for _state in self.states.keys() {
+                if self.transitions.contains_key(_state) == false {
+                    error(
+                        format!("{}/transitions", pointer),
+                        format!("brak przejść ze stanu: \'{}\'", _state),
+                    );
+                }
+            }
+
+            for (_state, _row) in self.transitions.iter() {
+                let row_pointer = format!("{}/transitions/{}", pointer, escape_pointer(_state));
+
+                if self.states.contains_key(_state) == false {
+                    error(
+                        row_pointer.clone(),
+                        format!("stan: \'{}\' nie występuje w \'states\'", _state),
+                    );
+                    continue;
+                }
+
+                for (_next_state, _probability) in _row.iter() {
+                    if self.states.contains_key(_next_state) == false {
+                        error(
+                            format!("{}/{}", row_pointer, escape_pointer(_next_state)),
+                            format!("stan: \'{}\' nie występuje w \'states\'", _next_state),
+                        );
+                    } else if *_probability < 0.0 || _probability.is_finite() == false {
+                        error(
+                            format!("{}/{}", row_pointer, escape_pointer(_next_state)),
+                            format!(
+                                "nieprawidłowe prawdopodobieństwo przejścia: \'{}\' -> \'{}\' (>= 0.0)",
+                                _state, _next_state
+                            ),
+                        );
+                    }
+                }
+
+                if _row.values().sum::<f64>() <= 0.0 {
+                    error(
+                        row_pointer,
+                        format!(
+                            "suma prawdopodobieństw przejść ze stanu: \'{}\' musi być dodatnia",
+                            _state
+                        ),
+                    );
+                }
+            }
+
+            match (&self.beats, &self.duration) {
+                (Some(_beats), None) => {
+                    if *_beats < MARKOV_BEATS_RANGE.0 || *_beats > MARKOV_BEATS_RANGE.1 {
+                        error(
+                            format!("{}/beats", pointer),
+                            format!(
+                                "nieprawidłowa wartość zmiennej: \'beats\' ({} ... {})",
+                                MARKOV_BEATS_RANGE.0, MARKOV_BEATS_RANGE.1
+                            ),
+                        );
+                    }
+                }
+                (None, Some(_duration)) => {
+                    if _duration.value() <= 0.0 || _duration.value().is_finite() == false {
+                        error(
+                            format!("{}/duration", pointer),
+                            "nieprawidłowa wartość zmiennej: \'duration\' (\'duration\' > 0)".to_string(),
+                        );
+                    }
+                }
+                _ => {
+                    error(
+                        pointer.clone(),
+                        "należy podać \'beats\' albo \'duration\' generowanej sekwencji".to_string(),
+                    );
+                }
+            }
+
+            // Stany są sprawdzane jak beaty sekwencji, wskaźniki błędów wskazują stan łańcucha.
+            for (_state_number, (_state, _beat)) in self.states.iter().enumerate() {
+                let beat_pointer =
+                    format!("/Tracks/{}/beat_sequence/{}", _track_number - 1, _state_number);
+                let state_pointer = format!("{}/states/{}", pointer, escape_pointer(_state));
+
+                match _beat.validate(_synth_configuration, _track_number, _state_number + 1) {
+                    Err(_errors) => {
+                        errors.extend(_errors.into_iter().map(|_error| {
+                            ConfigError::new(
+                                _error.pointer.replacen(&beat_pointer, &state_pointer, 1),
+                                _error.message,
+                            )
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Sekwencja zdarzeń ścieżki wczytywana ze standardowego pliku MIDI (zamiast sekwencji beatów): każda nuta wyznacza
+    // zdarzenie, prędkość - głośność, kontroler panoramy (CC 10) - panoramę, numer nuty względem 'root_note' - wysokość granulki.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct MidiSequence {
+        pub file: String,
+
+        // Numer ścieżki pliku MIDI (od 1), domyślnie wszystkie ścieżki.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub track: Option<usize>,
+
+        // Kanał MIDI (1 - 16), domyślnie wszystkie kanały.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub channel: Option<u8>,
+
+        #[serde(default = "default_midi_root_note")]
+        pub root_note: u8,
+
+        #[serde(default = "default_midi_follow_pitch")]
+        pub follow_pitch: bool,
+    }
+
+    impl MidiSequence {
+        // Sprawdzenie poprawności ustawień odczytu pliku MIDI.
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!("/Tracks/{}/midi", _track_number - 1);
+
+            if self.file.is_empty() == true {
+                errors.push(ConfigError::new(
+                    format!("{}/file", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'midi\' ->\n\tnie podano ścieżki do pliku MIDI :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if let Some(0) = self.track {
+                errors.push(ConfigError::new(
+                    format!("{}/track", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'midi\' ->\n\tnieprawidłowa wartość zmiennej: \'track\' (ścieżki pliku MIDI są numerowane od 1) :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if let Some(_channel) = self.channel {
+                if _channel < MIDI_CHANNEL_RANGE.0 || _channel > MIDI_CHANNEL_RANGE.1 {
+                    errors.push(ConfigError::new(
+                        format!("{}/channel", pointer),
+                        format!(
+                            "Błąd danych - track: [{}] - \'midi\' ->\n\tnieprawidłowa wartość zmiennej: \'channel\' (1 ... 16) :/",
+                            _track_number
+                        ),
+                    ));
+                }
+            }
+            if self.root_note > MIDI_NOTE_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/root_note", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'midi\' ->\n\tnieprawidłowa wartość zmiennej: \'root_note\' (0 ... 127) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Ustawienia analizy obwiedni pliku 'guide_file': długość okna analizy oraz głębokość modulacji gęstości
+    // (pokrycia) i głośności zdarzeń przez obwiednię (0% - brak modulacji, 100% - pełna modulacja).
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct GuideEnvelope {
+        #[serde(default = "default_guide_window_ms")]
+        pub window_ms: f64,
+
+        #[serde(default = "default_guide_depth_percents")]
+        pub density_percents: f64,
+
+        #[serde(default = "default_guide_depth_percents")]
+        pub volume_percents: f64,
+    }
+
+    impl Default for GuideEnvelope {
+        fn default() -> Self {
+            return GuideEnvelope {
+                window_ms: default_guide_window_ms(),
+                density_percents: default_guide_depth_percents(),
+                volume_percents: default_guide_depth_percents(),
+            };
+        }
+    }
+
+    impl GuideEnvelope {
+        // Sprawdzenie poprawności ustawień analizy obwiedni.
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!("/Tracks/{}/guide_envelope", _track_number - 1);
+
+            if self.window_ms < GUIDE_WINDOW_MS_RANGE.0 || self.window_ms > GUIDE_WINDOW_MS_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/window_ms", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'guide_envelope\' ->\n\tnieprawidłowa wartość zmiennej: \'window_ms\' (1.0 ... 1000.0) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            for (_name, _value) in [
+                ("density_percents", self.density_percents),
+                ("volume_percents", self.volume_percents),
+            ] {
+                if _value < PERCENTAGE_RANGE.0 || _value > PERCENTAGE_RANGE.1 {
+                    errors.push(ConfigError::new(
+                        format!("{}/{}", pointer, _name),
+                        format!(
+                            "Błąd danych - track: [{}] - \'guide_envelope\' ->\n\tnieprawidłowa wartość zmiennej: \'{}\' (0% ... 100%) :/",
+                            _track_number, _name
+                        ),
+                    ));
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Cecha porównywana przy dopasowaniu granulek do ramek dźwięku docelowego.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum MosaicFeature {
+        Mfcc,
+        Centroid,
+        Loudness,
+    }
+
+    // Resynteza dźwięku docelowego (mozaika): plik 'target_file' jest dzielony na ramki o długości 'frame_ms'
+    // (co 'hop_ms', domyślnie połowa ramki), a w miejscu każdej ramki umieszczana jest granulka o najbliższych cechach
+    // ('features'), wybrana losowo spośród 'neighbours' najbliższych granulek. Ramki cichsze od najgłośniejszej ramki
+    // o więcej niż 'silence_db' są pomijane, 'match_loudness' dopasowuje głośność granulki do głośności ramki.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct Mosaic {
+        pub target_file: String,
+
+        #[serde(default = "default_mosaic_frame_ms")]
+        pub frame_ms: f64,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub hop_ms: Option<f64>,
+
+        #[serde(default = "default_mosaic_features")]
+        pub features: Vec<MosaicFeature>,
+
+        #[serde(default = "default_descriptor_neighbours")]
+        pub neighbours: usize,
+
+        #[serde(default = "default_mosaic_match_loudness")]
+        pub match_loudness: bool,
+
+        #[serde(default = "default_mosaic_silence_db")]
+        pub silence_db: f64,
+    }
+
+    impl Mosaic {
+        // Odstęp kolejnych ramek (w milisekundach).
+        pub fn hop_ms(&self) -> f64 {
+            return self.hop_ms.unwrap_or(self.frame_ms / 2.0);
+        }
+
+        // Sprawdzenie poprawności ustawień mozaiki.
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!("/Tracks/{}/mosaic", _track_number - 1);
+
+            if self.target_file.is_empty() == true {
+                errors.push(ConfigError::new(
+                    format!("{}/target_file", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'mosaic\' ->\n\tnie podano ścieżki do pliku dźwięku docelowego :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if self.frame_ms < MOSAIC_FRAME_MS_RANGE.0 || self.frame_ms > MOSAIC_FRAME_MS_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/frame_ms", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'mosaic\' ->\n\tnieprawidłowa wartość zmiennej: \'frame_ms\' (10.0 ... 1000.0) :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if let Some(_hop_ms) = self.hop_ms {
+                if _hop_ms < 1.0 || _hop_ms > MOSAIC_FRAME_MS_RANGE.1 {
+                    errors.push(ConfigError::new(
+                        format!("{}/hop_ms", pointer),
+                        format!(
+                            "Błąd danych - track: [{}] - \'mosaic\' ->\n\tnieprawidłowa wartość zmiennej: \'hop_ms\' (1.0 ... 1000.0) :/",
+                            _track_number
+                        ),
+                    ));
+                }
+            }
+            if self.features.is_empty() == true {
+                errors.push(ConfigError::new(
+                    format!("{}/features", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'mosaic\' ->\n\tnie podano żadnej cechy dopasowania (\'mfcc\', \'centroid\', \'loudness\') :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if self.neighbours < DESCRIPTOR_NEIGHBOURS_RANGE.0
+                || self.neighbours > DESCRIPTOR_NEIGHBOURS_RANGE.1
+            {
+                errors.push(ConfigError::new(
+                    format!("{}/neighbours", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'mosaic\' ->\n\tnieprawidłowa wartość zmiennej: \'neighbours\' (1 ... 1000) :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if self.silence_db < MOSAIC_SILENCE_DB_RANGE.0 || self.silence_db > MOSAIC_SILENCE_DB_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/silence_db", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'mosaic\' ->\n\tnieprawidłowa wartość zmiennej: \'silence_db\' (-120.0 ... 0.0) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Synteza synchroniczna z okresem dźwięku (PSOLA): w samplu wyznaczane są znaczniki kolejnych okresów (wysokość
+    // dźwięku szacowana metodą YIN z progiem 'yin_threshold'), a granulki o długości 'periods' okresów są wycinane
+    // wokół znaczników. Granulki są rozmieszczane co okres podzielony przez 'pitch_shift' (bez zmiany formantów),
+    // a sampel jest odczytywany 'time_stretch' razy wolniej. Fragmenty bezdźwięczne zachowują odstęp znaczników.
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    pub struct PitchSynchronous {
+        #[serde(default = "default_psola_periods")]
+        pub periods: usize,
+
+        #[serde(default = "default_psola_pitch_shift")]
+        pub pitch_shift: f64,
+
+        #[serde(default = "default_psola_time_stretch")]
+        pub time_stretch: f64,
+
+        #[serde(default = "default_yin_threshold")]
+        pub yin_threshold: f64,
+    }
+
+    impl PitchSynchronous {
+        // Sprawdzenie poprawności ustawień syntezy synchronicznej z okresem.
+        fn validate(&self, _track_number: usize) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+            let pointer = format!("/Tracks/{}/pitch_synchronous", _track_number - 1);
+
+            if self.periods < PSOLA_PERIODS_RANGE.0 || self.periods > PSOLA_PERIODS_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/periods", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'pitch_synchronous\' ->\n\tnieprawidłowa wartość zmiennej: \'periods\' (1 ... 8) :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if self.pitch_shift < GRAINS_PITCH_RANGE.0 || self.pitch_shift > GRAINS_PITCH_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/pitch_shift", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'pitch_synchronous\' ->\n\tnieprawidłowa wartość zmiennej: \'pitch_shift\' (0.25 ... 5.0) :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if self.time_stretch < PSOLA_TIME_STRETCH_RANGE.0 || self.time_stretch > PSOLA_TIME_STRETCH_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/time_stretch", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'pitch_synchronous\' ->\n\tnieprawidłowa wartość zmiennej: \'time_stretch\' (0.1 ... 10.0) :/",
+                        _track_number
+                    ),
+                ));
+            }
+            if self.yin_threshold < YIN_THRESHOLD_RANGE.0 || self.yin_threshold > YIN_THRESHOLD_RANGE.1 {
+                errors.push(ConfigError::new(
+                    format!("{}/yin_threshold", pointer),
+                    format!(
+                        "Błąd danych - track: [{}] - \'pitch_synchronous\' ->\n\tnieprawidłowa wartość zmiennej: \'yin_threshold\' (0.01 ... 1.0) :/",
+                        _track_number
+                    ),
+                ));
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Zamienia nazwę na fragment wskaźnika JSON (RFC 6901).
+    fn escape_pointer(_name: &String) -> String {
+        return _name.replace('~', "~0").replace('/', "~1");
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Struktura reprezentująca całą ścieżkę.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct Track {
+        #[serde(default)]
+        pub track_properties: TrackProperties,
+        pub grains_properties: GrainsProperties,
+
+        // Sekwencja beatów, dla ścieżki z łańcuchem Markowa generowana przed każdą syntezą.
+        #[serde(default)]
+        pub beat_sequence: Vec<BeatConfiguration>,
+
+        #[serde(default, skip_serializing)]
+        pub markov: Option<MarkovChain>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub midi: Option<MidiSequence>,
+
+        // Wykaz zdarzeń ('.csv' lub '.json') zapisany podczas wcześniejszej syntezy, odtwarzany bez losowości.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub sequence_file: Option<String>,
+
+        // Plik dźwiękowy, którego obwiednia amplitudy moduluje gęstość i głośność zdarzeń sekwencji beatów.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub guide_file: Option<String>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub guide_envelope: Option<GuideEnvelope>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub mosaic: Option<Mosaic>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub pitch_synchronous: Option<PitchSynchronous>,
+
+        // Stany łańcucha Markowa odwiedzone podczas generowania sekwencji beatów.
+        #[serde(skip)]
+        pub markov_path: Vec<String>,
+
+        // Ramki dźwięku docelowego mozaiki wraz z ich deskryptorami.
+        #[serde(skip)]
+        pub mosaic_target: MosaicTarget,
+
+        // Znaczniki okresów sampla ścieżki z syntezą synchroniczną z okresem.
+        #[serde(skip)]
+        pub pitch_marks: PitchMarks,
+
+        #[serde(default = "AudioBuffer::default", skip_serializing)]
+        pub canva: AudioBuffer,
+
+        #[serde(default = "Sampler::default", skip_serializing)]
+        pub sampler: Sampler,
+
+        #[serde(default = "Sequencer::default", skip_serializing)]
+        pub sequencer: Sequencer,
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Rozwija '~' do katalogu domowego oraz zmienne środowiskowe w postaci '$NAZWA' i '${NAZWA}'.
+    pub fn expand_path(_path: &str) -> Result<String, String> {
+        let mut expanded = String::with_capacity(_path.len());
+
+        let path: String = if _path == "~" || _path.starts_with("~/") {
+            match env::var("HOME") {
+                Ok(_home_directory) => format!("{}{}", _home_directory, &_path[1..]),
+                Err(_) => {
+                    return Err(format!(
+                        "Błąd danych ->\n\tnie można rozwinąć ścieżki: \'{}\' (zmienna środowiskowa \'HOME\' nie jest zdefiniowana) :/",
+                        _path
+                    ));
+                }
+            }
+        } else {
+            _path.to_string()
+        };
+
+        let mut characters = path.chars().peekable();
+
+        while let Some(_character) = characters.next() {
+            if _character != '$' {
+                expanded.push(_character);
+                continue;
+            }
+
+            let braced = characters.peek() == Some(&'{');
+
+            if braced == true {
+                characters.next();
+            }
+
+            let mut variable_name = String::new();
+
+            while let Some(&_name_character) = characters.peek() {
+                if _name_character.is_alphanumeric() == false && _name_character != '_' {
+                    break;
+                }
 
-            if self.sample_file_path.is_empty() == true {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'sample_file_path\' (\'sample_file_path\' nie może być pusty) :/",
-                        _track_number
-                    )
-                )
+                variable_name.push(_name_character);
+                characters.next();
             }
 
-            if self.grains_count < 4 || self.grains_count > 1_000_000 {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - \'grains_properties\' ->\n\tnieprawidłowa wartość zmiennej: \'grains_count\' (4 < \'grains_count\' < 1 000 000) :/",
-                        _track_number
-                    )
-                );
+            if braced == true && characters.next() != Some('}') {
+                return Err(format!(
+                    "Błąd danych ->\n\tnie można rozwinąć ścieżki: \'{}\' (niezamknięty znacznik \'${{\') :/",
+                    _path
+                ));
             }
 
-            match self
-                .grains_length_ms
-                .validate(_synth_configuration, _track_number)
-            {
-                Err(_error) => {
-                    errors.push(_error);
-                }
-                _ => {
-                    match self
-                        .window_function
-                        .validate(&self.grains_length_ms, _track_number)
-                    {
-                        Err(_error) => {
-                            errors.push(_error);
-                        }
-                        _ => {}
-                    }
-                }
+            if variable_name.is_empty() == true {
+                expanded.push('$');
+                continue;
             }
 
-            match &mut self.grains_pitch.validate(_track_number) {
-                Err(_errors) => {
-                    errors.append(_errors);
+            match env::var(&variable_name) {
+                Ok(_value) => expanded.push_str(&_value),
+                Err(_) => {
+                    return Err(format!(
+                        "Błąd danych ->\n\tnie można rozwinąć ścieżki: \'{}\' (zmienna środowiskowa \'{}\' nie jest zdefiniowana) :/",
+                        _path, variable_name
+                    ));
                 }
-                _ => {}
             }
+        }
 
-            if errors.is_empty() == true {
-                return Ok(());
-            } else {
-                return Err(errors);
+        return Ok(expanded);
+    }
+
+    // Rozwija ścieżkę i, jeżeli jest względna, wyznacza ją względem zadanego katalogu.
+    pub fn resolve_path(_path: &str, _base_directory: &Path) -> Result<String, String> {
+        let expanded_path = match expand_path(_path) {
+            Ok(_expanded_path) => PathBuf::from(_expanded_path),
+            Err(_error) => {
+                return Err(_error);
             }
+        };
+
+        if expanded_path.is_absolute() == true {
+            return Ok(expanded_path.to_string_lossy().to_string());
         }
+
+        return Ok(_base_directory
+            .join(expanded_path)
+            .components()
+            .filter(|_component| _component != &Component::CurDir)
+            .collect::<PathBuf>()
+            .to_string_lossy()
+            .to_string());
     }
 
-    // ------------------------------------------------------------------------------------------------------------------------------------------
+    // Rozwiązuje ścieżkę sampla: ścieżka bezwzględna pozostaje bez zmian, względna wskazuje pierwszą istniejącą
+    // lokalizację w katalogach wyszukiwania, w przeciwnym razie katalog pliku konfiguracyjnego
+    // (brak pliku zostanie zgłoszony przy jego wczytywaniu).
+    fn resolve_sample_path(
+        _path: &str,
+        _base_directory: &Path,
+        _search_directories: &Vec<PathBuf>,
+    ) -> Result<String, String> {
+        let sample_file_path = match expand_path(_path) {
+            Ok(_expanded_path) => PathBuf::from(_expanded_path),
+            Err(_error) => {
+                return Err(_error);
+            }
+        };
 
-    // Konfiguracja beatu.
-    #[derive(Debug, Deserialize)]
-    pub struct BeatConfiguration {
-        pub subdivisions: usize,
-        pub coverage_percentage: f64,
-        pub humanization_percents: f64,
-        pub volume_deviation_percents: f64,
-        pub panorama_deviation_percents: f64,
+        if sample_file_path.is_absolute() == true {
+            return Ok(sample_file_path.to_string_lossy().to_string());
+        }
+
+        let resolved_path = _search_directories
+            .iter()
+            .map(|_directory| _directory.join(&sample_file_path))
+            .find(|_candidate| _candidate.is_file())
+            .unwrap_or(_base_directory.join(&sample_file_path));
+
+        return Ok(resolved_path.to_string_lossy().to_string());
     }
 
-    impl BeatConfiguration {
-        // Sprawdzenie poprawności wczytanych danych konfiguracji beatu.
-        fn validate(
-            &self,
-            _synth_configuration: &SynthConfiguration,
-            _track_number: usize,
-            _beat_number: usize,
-        ) -> Result<(), Vec<String>> {
-            let mut errors: Vec<String> = Vec::new();
+    // Zwraca katalog, w którym znajduje się plik konfiguracyjny (jako ścieżkę bezwzględną, jeśli to możliwe).
+    pub fn config_directory(_json_file_path: &String) -> PathBuf {
+        let parent_directory = match Path::new(_json_file_path).parent() {
+            Some(_parent_directory) if _parent_directory.as_os_str().is_empty() == false => {
+                _parent_directory.to_path_buf()
+            }
+            _ => PathBuf::from("."),
+        };
 
-            if (_synth_configuration.beat_length_ms / self.subdivisions as f64) < 10.0 {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'subdivisions\' (\'SynthConfiguration\': \'beat_length_ms\' / \'subdivisions\' > 10) :/",
-                        _track_number, _beat_number
-                    )
-                )
+        match env::current_dir() {
+            Ok(_working_directory) if parent_directory.is_relative() == true => {
+                return _working_directory
+                    .join(parent_directory)
+                    .components()
+                    .filter(|_component| _component != &Component::CurDir)
+                    .collect();
             }
-            if self.coverage_percentage < 0.0 || self.coverage_percentage > 100.0 {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'coverage_percentage\' (0% < \'coverage_percentage\' < 100%) :/",
-                        _track_number, _beat_number
-                    )
-                )
+            _ => {
+                return parent_directory;
             }
-            if self.humanization_percents < 0.0 || self.humanization_percents > 50.0 {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'humanization_percents\' (0% < \'humanization_percents\' < 50%) :/",
-                        _track_number, _beat_number
-                    )
-                )
+        }
+    }
+
+    // Wyznacza ścieżki katalogu wyjściowego i sampli względem katalogu pliku konfiguracyjnego.
+    // Sample o ścieżkach względnych są wyszukiwane kolejno w katalogu pliku konfiguracyjnego
+    // i w katalogach 'sample_search_paths'.
+    pub fn resolve_configuration_paths(
+        _synth_configuration: &mut SynthConfiguration,
+        _tracks: &mut Vec<Track>,
+        _json_file_path: &String,
+    ) -> Result<(), Vec<ConfigError>> {
+        let base_directory = config_directory(_json_file_path);
+        let mut errors: Vec<ConfigError> = Vec::new();
+
+        match resolve_path(&_synth_configuration.output_directory, &base_directory) {
+            Ok(_output_directory) => _synth_configuration.output_directory = _output_directory,
+            Err(_error) => errors.push(ConfigError::new(
+                "/SynthConfiguration/output_directory".to_string(),
+                _error,
+            )),
+        }
+
+        let mut search_directories: Vec<PathBuf> = vec![base_directory.clone()];
+
+        for (_path_index, _search_path) in _synth_configuration
+            .sample_search_paths
+            .iter_mut()
+            .enumerate()
+        {
+            match resolve_path(_search_path, &base_directory) {
+                Ok(_resolved_search_path) => {
+                    *_search_path = _resolved_search_path;
+                    search_directories.push(PathBuf::from(_search_path.as_str()));
+                }
+                Err(_error) => errors.push(ConfigError::new(
+                    format!("/SynthConfiguration/sample_search_paths/{}", _path_index),
+                    _error,
+                )),
             }
-            if self.volume_deviation_percents < 0.0 || self.volume_deviation_percents > 100.0 {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'volume_deviation_percents\' (0% < \'volume_deviation_percents\' < 100%) :/",
-                        _track_number, _beat_number
-                    )
-                )
+        }
+
+        for (_track_number, _track) in _tracks.iter_mut().enumerate() {
+            if let Some(_midi) = &mut _track.midi {
+                match resolve_path(&_midi.file, &base_directory) {
+                    Ok(_midi_file_path) => _midi.file = _midi_file_path,
+                    Err(_error) => errors.push(ConfigError::new(
+                        format!("/Tracks/{}/midi/file", _track_number),
+                        _error,
+                    )),
+                }
             }
-            if self.panorama_deviation_percents < 0.0 || self.panorama_deviation_percents > 100.0 {
-                errors.push(
-                    format!(
-                        "Błąd danych - track: [{}] - beat: [{}] - \'beat_sequence\' ->\n\tnieprawidłowa wartość zmiennej: \'panorama_deviation_percents\' (0% < \'panorama_deviation_percents\' < 100%) :/",
-                        _track_number, _beat_number
-                    )
-                )
+
+            if let Some(_mosaic) = &mut _track.mosaic {
+                match resolve_path(&_mosaic.target_file, &base_directory) {
+                    Ok(_target_file_path) => _mosaic.target_file = _target_file_path,
+                    Err(_error) => errors.push(ConfigError::new(
+                        format!("/Tracks/{}/mosaic/target_file", _track_number),
+                        _error,
+                    )),
+                }
             }
 
-            if errors.is_empty() == true {
-                return Ok(());
-            } else {
-                return Err(errors);
+            if let Some(_guide_file) = &mut _track.guide_file {
+                match resolve_path(_guide_file, &base_directory) {
+                    Ok(_guide_file_path) => *_guide_file = _guide_file_path,
+                    Err(_error) => errors.push(ConfigError::new(
+                        format!("/Tracks/{}/guide_file", _track_number),
+                        _error,
+                    )),
+                }
+            }
+
+            if let Some(_sequence_file) = &mut _track.sequence_file {
+                match resolve_path(_sequence_file, &base_directory) {
+                    Ok(_sequence_file_path) => *_sequence_file = _sequence_file_path,
+                    Err(_error) => errors.push(ConfigError::new(
+                        format!("/Tracks/{}/sequence_file", _track_number),
+                        _error,
+                    )),
+                }
+            }
+
+            if _track.grains_properties.sample_file_path.is_empty() == false {
+                match resolve_sample_path(
+                    &_track.grains_properties.sample_file_path,
+                    &base_directory,
+                    &search_directories,
+                ) {
+                    Ok(_sample_file_path) => _track.grains_properties.sample_file_path = _sample_file_path,
+                    Err(_error) => errors.push(ConfigError::new(
+                        format!(
+                            "/Tracks/{}/grains_properties/sample_file_path",
+                            _track_number
+                        ),
+                        _error,
+                    )),
+                }
+            }
+
+            for (_source_index, _source) in _track.grains_properties.sources.iter_mut().enumerate() {
+                match resolve_sample_path(&_source.file_path, &base_directory, &search_directories) {
+                    Ok(_sample_file_path) => _source.file_path = _sample_file_path,
+                    Err(_error) => errors.push(ConfigError::new(
+                        format!(
+                            "/Tracks/{}/grains_properties/sources/{}/file_path",
+                            _track_number, _source_index
+                        ),
+                        _error,
+                    )),
+                }
             }
         }
+
+        if errors.is_empty() == true {
+            return Ok(());
+        } else {
+            return Err(errors);
+        }
     }
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
-    // Struktura reprezentująca całą ścieżkę.
-    #[derive(Debug, Deserialize)]
-    pub struct Track {
-        pub track_properties: TrackProperties,
-        pub grains_properties: GrainsProperties,
-        pub beat_sequence: Vec<BeatConfiguration>,
+    // Format pliku konfiguracyjnego, wybierany na podstawie rozszerzenia pliku.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum ConfigFormat {
+        Json,
+        Json5,
+        Toml,
+        Yaml,
+    }
 
-        #[serde(default = "AudioBuffer::default")]
-        pub canva: AudioBuffer,
+    impl ConfigFormat {
+        // Wyznacza format pliku na podstawie jego rozszerzenia (.json, .json5, .toml, .yaml, .yml).
+        pub fn from_path(_file_path: &String) -> Result<Self, String> {
+            let extension = Path::new(_file_path)
+                .extension()
+                .map(|_extension| _extension.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+
+            match extension.as_str() {
+                "json" => {
+                    return Ok(ConfigFormat::Json);
+                }
+                "json5" => {
+                    return Ok(ConfigFormat::Json5);
+                }
+                "toml" => {
+                    return Ok(ConfigFormat::Toml);
+                }
+                "yaml" | "yml" => {
+                    return Ok(ConfigFormat::Yaml);
+                }
+                _ => {
+                    return Err(format!(
+                        "Błąd danych ->\n\tnieobsługiwany format pliku konfiguracyjnego: \'{}\' (dozwolone rozszerzenia: .json, .json5, .toml, .yaml, .yml) :/",
+                        _file_path
+                    ));
+                }
+            }
+        }
 
-        #[serde(default = "Sampler::default")]
-        pub sampler: Sampler,
+        // Parsuje treść pliku konfiguracyjnego do obiektu Value.
+        fn parse(&self, _content: &str) -> Result<Value, String> {
+            match self {
+                ConfigFormat::Json => {
+                    return from_str(_content).map_err(|_error| _error.to_string());
+                }
+                ConfigFormat::Json5 => {
+                    return json5::from_str(_content).map_err(|_error| _error.to_string());
+                }
+                ConfigFormat::Toml => {
+                    return toml::from_str(_content).map_err(|_error| _error.to_string());
+                }
+                ConfigFormat::Yaml => {
+                    return serde_yaml::from_str(_content).map_err(|_error| _error.to_string());
+                }
+            }
+        }
 
-        #[serde(default = "Sequencer::default")]
-        pub sequencer: Sequencer,
+        // Zapisuje obiekt Value w danym formacie, plik JSON5 jest zapisywany jako zwykły JSON.
+        fn serialize(&self, _value: &Value) -> Result<String, String> {
+            match self {
+                ConfigFormat::Json | ConfigFormat::Json5 => {
+                    return to_string_pretty(_value).map_err(|_error| _error.to_string());
+                }
+                ConfigFormat::Toml => {
+                    // TOML nie posiada wartości pustej, zmienne o wartości null są pomijane (przyjmują wartość domyślną).
+                    let toml_value = match toml::Value::try_from(without_nulls(_value)) {
+                        Ok(_toml_value) => _toml_value,
+                        Err(_error) => {
+                            return Err(_error.to_string());
+                        }
+                    };
+
+                    return toml::to_string_pretty(&toml_value).map_err(|_error| _error.to_string());
+                }
+                ConfigFormat::Yaml => {
+                    return serde_yaml::to_string(_value).map_err(|_error| _error.to_string());
+                }
+            }
+        }
     }
 
-    // ------------------------------------------------------------------------------------------------------------------------------------------
+    // Kopia obiektu Value bez zmiennych i elementów o wartości null.
+    fn without_nulls(_value: &Value) -> Value {
+        match _value {
+            Value::Object(_object) => {
+                return Value::Object(
+                    _object
+                        .iter()
+                        .filter(|(_, _field)| _field.is_null() == false)
+                        .map(|(_key, _field)| (_key.clone(), without_nulls(_field)))
+                        .collect(),
+                );
+            }
+            Value::Array(_array) => {
+                return Value::Array(
+                    _array
+                        .iter()
+                        .filter(|_item| _item.is_null() == false)
+                        .map(without_nulls)
+                        .collect(),
+                );
+            }
+            _ => {
+                return _value.clone();
+            }
+        }
+    }
+
+    // Wczytuje plik konfiguracyjny (JSON, JSON5, TOML lub YAML) i buduje na jego podstawie obiekt Value.
+    pub fn load_config_file(_json_file_path: &String) -> Result<Value, String> {
+        let config_format = match ConfigFormat::from_path(_json_file_path) {
+            Ok(_config_format) => _config_format,
+            Err(_error) => {
+                return Err(_error);
+            }
+        };
 
-    // Wczytuje plik .json z konfiguracją i buduje na jego podstawie obiekt Value.
-    pub fn load_json_file(_json_file_path: &String) -> Result<Value, String> {
         let json_file_content_result = read_to_string(Path::new(_json_file_path));
 
         let json_file_content = match json_file_content_result {
@@ -514,9 +2966,7 @@ pub mod tools {
             }
         };
 
-        let json_file_value_result: Result<Value, _> = from_str(json_file_content.as_str());
-
-        let json_file_value = match json_file_value_result {
+        let json_file_value = match config_format.parse(json_file_content.as_str()) {
             Ok(_json_file_value) => _json_file_value,
             Err(_serde_error) => {
                 return Err(format!(
@@ -529,24 +2979,75 @@ pub mod tools {
         return Ok(json_file_value);
     }
 
+    // Zapisuje obiekt Value do pliku konfiguracyjnego w formacie wynikającym z rozszerzenia pliku.
+    pub fn save_config_file(_value: &Value, _file_path: &String) -> Result<(), String> {
+        let config_format = match ConfigFormat::from_path(_file_path) {
+            Ok(_config_format) => _config_format,
+            Err(_error) => {
+                return Err(_error);
+            }
+        };
+
+        let content = match config_format.serialize(_value) {
+            Ok(_content) => _content,
+            Err(_serde_error) => {
+                return Err(format!(
+                    "Błąd zapisu ->\n\tnie można zapisać konfiguracji w pliku: \'{}\'.\n\tSerializer error: {} :/",
+                    _file_path, _serde_error
+                ))
+            }
+        };
+
+        match write(Path::new(_file_path), content) {
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd ->\n\tnie można utworzyć pliku \'{}\'.\n\tSystem error: {} :/",
+                    _file_path, _system_error
+                ));
+            }
+            _ => {}
+        }
+
+        return Ok(());
+    }
+
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
     // Wczytuje konfiguracje ścieżek na podstawie obiektu Value.
     pub fn load_tracks_configurations(
         _json_file_value: &Value,
         _synth_configuration: &SynthConfiguration,
-    ) -> Result<Vec<Track>, Vec<String>> {
-        let tracks_result: Result<Vec<Track>, _> = from_value(_json_file_value["Tracks"].clone());
+    ) -> Result<Vec<Track>, Vec<ConfigError>> {
+        let tracks_result: Result<Vec<Track>, _> =
+            deserialize_value(&_json_file_value["Tracks"], "/Tracks");
 
-        let tracks = match tracks_result {
+        let mut tracks = match tracks_result {
             Ok(_tracks) => _tracks,
-            Err(_serde_error) => {
-                return Err(vec![format!("Serializer Error ->\n\t{} :/", _serde_error)]);
+            Err(_error) => {
+                return Err(vec![_error]);
             }
         };
 
-        let mut errors: Vec<String> = Vec::new();
-        let mut tracks_names: HashMap<String, u16> = HashMap::new();
+        // Ścieżki bez nazwy otrzymują nazwę na podstawie numeru ścieżki.
+        for (_track_number, _track) in tracks.iter_mut().enumerate() {
+            if _json_file_value["Tracks"][_track_number]["track_properties"]["track_name"].is_null()
+                == true
+            {
+                _track.track_properties.track_name = format!("track_{}", _track_number + 1);
+            }
+
+            // Ścieżki z syntezą synchroniczną bez podanego okna otrzymują okno 'Symmetric'.
+            if _track.pitch_synchronous.is_some() == true
+                && _json_file_value["Tracks"][_track_number]["grains_properties"]["window_function"]
+                    .is_null()
+                    == true
+            {
+                _track.grains_properties.window_function = GWFunction::Symmetric;
+            }
+        }
+
+        let mut errors: Vec<ConfigError> = Vec::new();
+        let mut tracks_names: HashMap<String, Vec<usize>> = HashMap::new();
 
         for (_track_number, _track) in tracks.iter().enumerate() {
             match &mut _track.track_properties.validate(_track_number + 1) {
@@ -554,9 +3055,10 @@ pub mod tools {
                     errors.append(_errors);
                 }
                 _ => {
-                    *tracks_names
+                    tracks_names
                         .entry(_track.track_properties.track_name.clone())
-                        .or_insert(0) += 1;
+                        .or_default()
+                        .push(_track_number);
                 }
             }
 
@@ -579,13 +3081,200 @@ pub mod tools {
                     _ => {}
                 }
             }
+
+            if _track.markov.is_none() == true
+                && _track.midi.is_none() == true
+                && _track.sequence_file.is_none() == true
+                && _track.mosaic.is_none() == true
+                && _track.pitch_synchronous.is_none() == true
+                && _json_file_value["Tracks"][_track_number].get("beat_sequence").is_none() == true
+            {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}", _track_number),
+                    format!(
+                        "Błąd danych - track: [{}] ->\n\tbrak sekwencji beatów (wymagana zmienna \'beat_sequence\', \'markov\', \'midi\', \'sequence_file\', \'mosaic\' albo \'pitch_synchronous\') :/",
+                        _track_number + 1
+                    ),
+                ));
+            }
+
+            if let Some(_sequence_file) = &_track.sequence_file {
+                if _track.beat_sequence.is_empty() == false
+                    || _track.markov.is_some() == true
+                    || _track.midi.is_some() == true
+                {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/sequence_file", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tścieżka odtwarzająca wykaz zdarzeń \'sequence_file\' nie może zawierać \'beat_sequence\', \'markov\' ani \'midi\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                if _sequence_file.trim().is_empty() == true {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/sequence_file", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tnie podano ścieżki do wykazu zdarzeń \'sequence_file\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+            }
+
+            if let Some(_mosaic) = &_track.mosaic {
+                if _track.beat_sequence.is_empty() == false
+                    || _track.markov.is_some() == true
+                    || _track.midi.is_some() == true
+                    || _track.sequence_file.is_some() == true
+                    || _track.guide_file.is_some() == true
+                    || _track.grains_properties.descriptor_selection.is_some() == true
+                {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/mosaic", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tścieżka z mozaiką \'mosaic\' nie może zawierać \'beat_sequence\', \'markov\', \'midi\', \'sequence_file\', \'guide_file\' ani \'descriptor_selection\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                match &mut _mosaic.validate(_track_number + 1) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(_pitch_synchronous) = &_track.pitch_synchronous {
+                if _track.beat_sequence.is_empty() == false
+                    || _track.markov.is_some() == true
+                    || _track.midi.is_some() == true
+                    || _track.sequence_file.is_some() == true
+                    || _track.guide_file.is_some() == true
+                    || _track.mosaic.is_some() == true
+                    || _track.grains_properties.descriptor_selection.is_some() == true
+                {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/pitch_synchronous", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tścieżka z syntezą synchroniczną \'pitch_synchronous\' nie może zawierać \'beat_sequence\', \'markov\', \'midi\', \'sequence_file\', \'guide_file\', \'mosaic\' ani \'descriptor_selection\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                // Granulki są wycinane z jednego sampla, bez zmiany wysokości dźwięku i bez wyrównania początków.
+                if _track.grains_properties.sources.len() > 1
+                    || matches!(_track.grains_properties.grains_pitch, GrainsPitch::Fixed) == false
+                    || matches!(_track.grains_properties.grains_alignment, GrainsAlignment::Random) == false
+                {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/grains_properties", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tścieżka z syntezą synchroniczną \'pitch_synchronous\' wymaga jednego sampla źródłowego, \'grains_pitch\': \'Fixed\' i \'grains_alignment\': \'Random\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                // Granulki sumują się do stałej amplitudy tylko z oknem 'Symmetric'.
+                if matches!(_track.grains_properties.window_function, GWFunction::Symmetric) == false {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/grains_properties/window_function", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tścieżka z syntezą synchroniczną \'pitch_synchronous\' wymaga okna \'window_function\': \'Symmetric\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                match &mut _pitch_synchronous.validate(_track_number + 1) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(_guide_envelope) = &_track.guide_envelope {
+                if _track.guide_file.is_none() == true {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/guide_envelope", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tustawienia \'guide_envelope\' wymagają pliku \'guide_file\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                match &mut _guide_envelope.validate(_track_number + 1) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if _track.guide_file.is_some() == true
+                && (_track.midi.is_some() == true || _track.sequence_file.is_some() == true)
+            {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/guide_file", _track_number),
+                    format!(
+                        "Błąd danych - track: [{}] ->\n\tobwiednia \'guide_file\' moduluje wyłącznie sekwencję beatów (nie można jej łączyć z \'midi\' ani \'sequence_file\') :/",
+                        _track_number + 1
+                    ),
+                ));
+            }
+
+            if let Some(_midi) = &_track.midi {
+                if _track.beat_sequence.is_empty() == false || _track.markov.is_some() == true {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/midi", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tścieżka z sekwencją \'midi\' nie może zawierać \'beat_sequence\' ani \'markov\' :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                match &mut _midi.validate(_track_number + 1) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(_markov) = &_track.markov {
+                if _track.beat_sequence.is_empty() == false {
+                    errors.push(ConfigError::new(
+                        format!("/Tracks/{}/markov", _track_number),
+                        format!(
+                            "Błąd danych - track: [{}] ->\n\tnależy podać \'beat_sequence\' albo \'markov\', nie obie zmienne jednocześnie :/",
+                            _track_number + 1
+                        ),
+                    ));
+                }
+
+                match &mut _markov.validate(_synth_configuration, _track_number + 1) {
+                    Err(_errors) => {
+                        errors.append(_errors);
+                    }
+                    _ => {}
+                }
+            }
         }
 
-        for (_track_name, _count) in tracks_names.iter() {
-            if *_count > 1 {
-                errors.push(
-                    format!("Błąd danych ->\n\t\'track_name\': \'{}\' występuje \'{}\' razy (nazwy ścieżek muszą być unikatowe) :/", _track_name, _count)
-                )
+        for (_track_name, _tracks_numbers) in tracks_names.iter() {
+            if _tracks_numbers.len() > 1 {
+                errors.push(ConfigError::new(
+                    format!("/Tracks/{}/track_properties/track_name", _tracks_numbers[1]),
+                    format!("Błąd danych ->\n\t\'track_name\': \'{}\' występuje \'{}\' razy (nazwy ścieżek muszą być unikatowe) :/", _track_name, _tracks_numbers.len()),
+                ))
             }
         }
 
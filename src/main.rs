@@ -1,52 +1,223 @@
 mod audio;
+mod cli;
+mod config_expansion;
+mod config_schema;
+mod config_validator;
+mod descriptors;
+mod events_export;
 mod granular_synth;
 mod granular_synth_config;
+mod markers;
+mod midi;
 mod sampler;
 mod sequencer;
+mod sweep;
+mod timeline;
 
-use granular_synth::synth::GranularSynth;
+use serde_json::Value;
 
-fn synthesize_tracks() -> Result<(), Vec<String>> {
-    let json_file_path: String = match std::env::args().nth(1) {
-        Some(_json_file_path) => _json_file_path,
-        None => {
-            return Err(vec![
-                "Błąd danych ->\n\tnie podano ścieżki do pliku konfiguracyjnego (wymagana ścieżka do odpowiedniego pliku \'.json\') :/".to_string()
-            ])
-        }
-    };
+use audio::tools::describe_audio_file;
+use cli::tools::{parse_arguments, usage, Arguments, Command, Verbosity};
+use config_expansion::tools::expand_configuration;
+use config_schema::tools::configuration_schema;
+use config_validator::tools::{config_errors_messages, validate_configuration, ConfigSource};
+use granular_synth::synth::GranularSynth;
+use granular_synth_config::tools::{load_config_file, save_config_file};
+use sweep::tools::Sweep;
 
-    let mut granular_synth = match GranularSynth::configure(&json_file_path) {
+// Syntetyzuje ścieżki jednej konfiguracji (lub wypisuje ich zdarzenia), zwraca wpisy manifestu zapisanych plików.
+fn synthesize_configuration(
+    _arguments: &Arguments,
+    _json_file_value: &Value,
+) -> Result<(String, Vec<Value>), Vec<String>> {
+    let mut granular_synth = match GranularSynth::configure(_json_file_value, _arguments) {
         Ok(_granular_synth) => _granular_synth,
         Err(_errors) => {
             return Err(_errors);
         }
     };
 
-    match granular_synth.run() {
+    if _arguments.command == Command::Events {
+        match granular_synth.print_events() {
+            Err(_error) => {
+                return Err(vec![_error]);
+            }
+            _ => {}
+        }
+    } else {
+        match granular_synth.render() {
+            Err(_error) => {
+                return Err(vec![_error]);
+            }
+            _ => {}
+        }
+    }
+
+    return Ok((
+        granular_synth.synth_configuration.output_directory,
+        granular_synth.manifest,
+    ));
+}
+
+fn synthesize_tracks(_arguments: &Arguments, _json_file_path: &String) -> Result<(), Vec<String>> {
+    let json_file_value = match load_config_file(_json_file_path) {
+        Ok(_json_file_value) => _json_file_value,
         Err(_error) => {
             return Err(vec![_error]);
         }
-        _ => {}
+    };
+
+    let source = ConfigSource::load(Some(_json_file_path));
+
+    let mut json_file_value = match expand_configuration(json_file_value, _json_file_path) {
+        Ok(_json_file_value) => _json_file_value,
+        Err(_errors) => {
+            return Err(config_errors_messages(&_errors, &source, None));
+        }
+    };
+
+    _arguments.overrides.apply(&mut json_file_value);
+
+    let mut sweep = match Sweep::load(&json_file_value) {
+        Ok(Some(_sweep)) => _sweep,
+        Ok(None) => {
+            return synthesize_configuration(_arguments, &json_file_value).map(|_| ());
+        }
+        Err(_errors) => {
+            return Err(config_errors_messages(&_errors, &source, Some(&json_file_value)));
+        }
+    };
+
+    // Sprawdzenie wszystkich punktów przeglądu przed rozpoczęciem syntezy.
+    let mut errors: Vec<String> = Vec::new();
+
+    for point in sweep.points.iter() {
+        match point.validate() {
+            Err(_errors) => {
+                errors.push(format!(
+                    "Punkt przeglądu [{}] ({}):",
+                    point.index,
+                    point.label()
+                ));
+                errors.append(&mut config_errors_messages(
+                    &_errors,
+                    &source,
+                    Some(&point.json_file_value),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    if errors.is_empty() == false {
+        return Err(errors);
     }
 
-    match granular_synth.save_tracks() {
+    let points_count = sweep.points.len();
+    let mut output_directory = String::new();
+
+    for point in sweep.points.iter_mut() {
+        if _arguments.verbosity >= Verbosity::Normal {
+            println!(
+                "\nPunkt przeglądu [{}/{}]: {}\n",
+                point.index,
+                points_count,
+                point.label()
+            );
+        }
+
+        let (_output_directory, _output_files) =
+            match synthesize_configuration(_arguments, &point.json_file_value) {
+                Ok(_result) => _result,
+                Err(_errors) => {
+                    return Err(_errors);
+                }
+            };
+
+        output_directory = _output_directory;
+        point.output_files = _output_files;
+    }
+
+    if _arguments.command == Command::Render {
+        match sweep.save_index(&output_directory, _arguments.verbosity) {
+            Err(_error) => {
+                return Err(vec![_error]);
+            }
+            _ => {}
+        }
+    }
+
+    return Ok(());
+}
+
+fn execute_command() -> Result<(), Vec<String>> {
+    let arguments = match parse_arguments(std::env::args().skip(1).collect()) {
+        Ok(_arguments) => _arguments,
         Err(_error) => {
             return Err(vec![_error]);
         }
-        _ => {}
+    };
+
+    let json_file_path = arguments.config_file_path.clone().unwrap_or_default();
+
+    match &arguments.command {
+        Command::Render | Command::Events => {
+            return synthesize_tracks(&arguments, &json_file_path);
+        }
+        Command::Validate => {
+            return validate_configuration(&json_file_path, &arguments.overrides);
+        }
+        Command::Convert { output_file_path } => {
+            let json_file_value = match load_config_file(&json_file_path) {
+                Ok(_json_file_value) => _json_file_value,
+                Err(_error) => {
+                    return Err(vec![_error]);
+                }
+            };
+
+            match save_config_file(&json_file_value, output_file_path) {
+                Err(_error) => {
+                    return Err(vec![_error]);
+                }
+                _ => {}
+            }
+
+            if arguments.verbosity >= Verbosity::Normal {
+                println!(
+                    "zapisano plik konfiguracyjny: \'{}\' jako: \'{}\'",
+                    json_file_path, output_file_path
+                );
+            }
+        }
+        Command::Info { wav_file_path } => match describe_audio_file(wav_file_path) {
+            Ok(_description) => {
+                println!("{}", _description);
+            }
+            Err(_error) => {
+                return Err(vec![_error]);
+            }
+        },
+        Command::Schema => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&configuration_schema()).unwrap()
+            );
+        }
+        Command::Help => {
+            println!("{}", usage());
+        }
     }
 
     return Ok(());
 }
 
 fn main() {
-    match synthesize_tracks() {
+    match execute_command() {
         Err(_errors) => {
             for error in _errors.iter() {
                 println!("{}", error);
             }
-        },
+        }
         _ => {}
     }
 }
@@ -0,0 +1,452 @@
+pub mod tools {
+    use std::collections::BTreeMap;
+    use std::convert::TryInto;
+    use std::fs::read;
+    use std::path::Path;
+
+    use crate::granular_synth_config::tools::{
+        ConfigError, GrainsProperties, SampleRegion, SampleSource,
+    };
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Znacznik pliku WAV: etykieta i zakres próbek (koniec nie należy do zakresu).
+    #[derive(Debug, Clone)]
+    pub struct SampleMarker {
+        pub label: String,
+        pub start: usize,
+        pub end: usize,
+    }
+
+    // Zakres próbek fragmentu sampla: indeks fragmentu w konfiguracji, początek i koniec (koniec nie należy do zakresu).
+    type RegionRange = (usize, usize, usize);
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Wczytuje znaczniki pliku WAV: punkty 'cue' (z etykietami 'labl' i długościami 'ltxt' listy 'adtl') oraz pętle
+    // 'smpl'. Punkt bez długości obejmuje próbki do kolejnego punktu lub do końca pliku, pętla bez etykiety
+    // otrzymuje etykietę 'loop_N', a punkt bez etykiety - 'cue_N' (N - numer kolejny od 1).
+    pub fn load_markers(_wav_file_path: &String) -> Result<Vec<SampleMarker>, String> {
+        let contents = match read(Path::new(_wav_file_path)) {
+            Ok(_contents) => _contents,
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd odczytu ->\n\tplik: \'{}\' nie został znaleziony.\n\tSystem error: {} :/",
+                    _wav_file_path, _system_error
+                ));
+            }
+        };
+
+        if contents.len() < 12 || &contents[0..4] != b"RIFF" || &contents[8..12] != b"WAVE" {
+            return Err(format!(
+                "Błąd danych ->\n\tplik: \'{}\' nie jest plikiem WAV (RIFF) :/",
+                _wav_file_path
+            ));
+        }
+
+        let mut block_align: usize = 0;
+        let mut data_size: usize = 0;
+        let mut cue_points: Vec<(u32, usize)> = Vec::new();
+        let mut labels: BTreeMap<u32, String> = BTreeMap::new();
+        let mut lengths: BTreeMap<u32, usize> = BTreeMap::new();
+        let mut loops: Vec<(u32, usize, usize)> = Vec::new();
+
+        for (_id, _chunk) in riff_chunks(&contents[12..]) {
+            match _id {
+                b"fmt " if _chunk.len() >= 14 => {
+                    block_align = read_u16(_chunk, 12) as usize;
+                }
+                b"data" => {
+                    data_size = _chunk.len();
+                }
+                b"cue " if _chunk.len() >= 4 => {
+                    let count = read_u32(_chunk, 0) as usize;
+
+                    for point in 0..count {
+                        let offset = 4 + point * 24;
+
+                        if offset + 24 > _chunk.len() {
+                            break;
+                        }
+
+                        cue_points.push((read_u32(_chunk, offset), read_u32(_chunk, offset + 20) as usize));
+                    }
+                }
+                b"LIST" if _chunk.len() >= 4 && &_chunk[0..4] == b"adtl" => {
+                    for (_sub_id, _sub_chunk) in riff_chunks(&_chunk[4..]) {
+                        match _sub_id {
+                            b"labl" if _sub_chunk.len() >= 4 => {
+                                labels.insert(read_u32(_sub_chunk, 0), read_text(&_sub_chunk[4..]));
+                            }
+                            b"ltxt" if _sub_chunk.len() >= 8 => {
+                                lengths.insert(read_u32(_sub_chunk, 0), read_u32(_sub_chunk, 4) as usize);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                b"smpl" if _chunk.len() >= 36 => {
+                    let count = read_u32(_chunk, 28) as usize;
+
+                    for sample_loop in 0..count {
+                        let offset = 36 + sample_loop * 24;
+
+                        if offset + 24 > _chunk.len() {
+                            break;
+                        }
+
+                        loops.push((
+                            read_u32(_chunk, offset),
+                            read_u32(_chunk, offset + 8) as usize,
+                            read_u32(_chunk, offset + 12) as usize,
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let sample_length = data_size.checked_div(block_align).unwrap_or(0);
+        let mut markers: Vec<SampleMarker> = Vec::new();
+
+        cue_points.sort_by_key(|(_, _position)| *_position);
+
+        // Punkty będące początkiem pętli 'smpl' są opisywane wyłącznie przez pętlę.
+        let loop_points: Vec<u32> = loops.iter().map(|(_cue_point_id, _, _)| *_cue_point_id).collect();
+
+        for (_point_index, (_id, _position)) in cue_points.iter().enumerate() {
+            if loop_points.contains(_id) == true && lengths.contains_key(_id) == false {
+                continue;
+            }
+
+            let end = match lengths.get(_id) {
+                Some(_length) if *_length > 0 => _position + _length,
+                _ => cue_points
+                    .iter()
+                    .skip(_point_index + 1)
+                    .map(|(_, _next_position)| *_next_position)
+                    .find(|_next_position| _next_position > _position)
+                    .unwrap_or(sample_length),
+            };
+
+            markers.push(SampleMarker {
+                label: labels
+                    .get(_id)
+                    .cloned()
+                    .unwrap_or(format!("cue_{}", _point_index + 1)),
+                start: *_position,
+                end: end.min(sample_length).max(*_position),
+            });
+        }
+
+        // Koniec pętli 'smpl' jest ostatnią próbką pętli.
+        for (_loop_index, (_cue_point_id, _start, _end)) in loops.iter().enumerate() {
+            markers.push(SampleMarker {
+                label: labels
+                    .get(_cue_point_id)
+                    .cloned()
+                    .unwrap_or(format!("loop_{}", _loop_index + 1)),
+                start: *_start,
+                end: (_end + 1).min(sample_length).max(*_start),
+            });
+        }
+
+        return Ok(markers);
+    }
+
+    // Zakresy próbek fragmentów sampla źródłowego (w częstotliwości próbkowania sampla) wraz z indeksem fragmentu
+    // w konfiguracji, pusta lista oznacza cały sampel. Fragment wskazany etykietą obejmuje wszystkie znaczniki pliku
+    // o tej etykiecie. Błąd zawiera indeks fragmentu, którego nie udało się wyznaczyć.
+    pub fn source_regions(
+        _source: &SampleSource,
+        _sampling_rate: u32,
+        _sample_length: usize,
+    ) -> Result<Vec<RegionRange>, (usize, String)> {
+        let regions = _source.sample_regions();
+        let mut markers: Option<Vec<SampleMarker>> = None;
+        let mut ranges: Vec<RegionRange> = Vec::with_capacity(regions.len());
+
+        for (_region_index, region) in regions.iter().enumerate() {
+            match region {
+                SampleRegion::Range { start_ms, end_ms } => {
+                    let start = ((start_ms / 1000.0) * _sampling_rate as f64).round() as usize;
+                    let end = ((end_ms / 1000.0) * _sampling_rate as f64).round() as usize;
+
+                    ranges.push((_region_index, start.min(_sample_length), end.min(_sample_length)));
+                }
+                SampleRegion::Marker { marker } => {
+                    if markers.is_none() == true {
+                        markers = match load_markers(&_source.file_path) {
+                            Ok(_markers) => Some(_markers),
+                            Err(_error) => {
+                                return Err((_region_index, _error));
+                            }
+                        };
+                    }
+
+                    let file_markers = markers.as_ref().unwrap();
+                    let matching: Vec<RegionRange> = file_markers
+                        .iter()
+                        .filter(|_marker| _marker.label == *marker)
+                        .map(|_marker| {
+                            (
+                                _region_index,
+                                _marker.start.min(_sample_length),
+                                _marker.end.min(_sample_length),
+                            )
+                        })
+                        .collect();
+
+                    if matching.is_empty() == true {
+                        return Err((_region_index, format!(
+                            "Błąd danych ->\n\tsampel: \'{}\' nie zawiera znacznika: \'{}\' (dostępne znaczniki: {}) :/",
+                            _source.file_path,
+                            marker,
+                            describe_labels(file_markers)
+                        )));
+                    }
+
+                    ranges.extend(matching);
+                }
+            }
+        }
+
+        return Ok(ranges);
+    }
+
+    // Wyznacza fragmenty sampla źródłowego i sprawdza, czy sampel (lub każdy z jego fragmentów) jest wystarczająco
+    // długi do wycinania z niego granulek, zwraca zakresy próbek fragmentów.
+    pub fn checked_source_regions(
+        _grains_properties: &GrainsProperties,
+        _track_number: usize,
+        _source_index: usize,
+        _sample_length: usize,
+        _sampling_rate: u32,
+    ) -> Result<Vec<(usize, usize)>, ConfigError> {
+        let source = &_grains_properties.sample_sources()[_source_index];
+        let length_ms = |_length: usize| -> f64 { (_length as f64 / _sampling_rate as f64) * 1000.0 };
+
+        let regions = match source_regions(source, _sampling_rate, _sample_length) {
+            Ok(_regions) => _regions,
+            Err((_region_index, _error)) => {
+                return Err(ConfigError::new(
+                    _grains_properties.region_pointer(_track_number, _source_index, _region_index),
+                    _error,
+                ));
+            }
+        };
+
+        if regions.is_empty() == true {
+            match _grains_properties.validate_sample_length(
+                length_ms(_sample_length),
+                _track_number,
+                _source_index,
+                None,
+            ) {
+                Err(_error) => {
+                    return Err(_error);
+                }
+                _ => {}
+            }
+        }
+
+        for (_region_index, _start, _end) in regions.iter() {
+            match _grains_properties.validate_sample_length(
+                length_ms(_end.saturating_sub(*_start)),
+                _track_number,
+                _source_index,
+                Some(*_region_index),
+            ) {
+                Err(_error) => {
+                    return Err(_error);
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(regions
+            .iter()
+            .map(|(_, _start, _end)| (*_start, *_end))
+            .collect());
+    }
+
+    // Opis znaczników pliku: etykiety i zakresy w sekundach.
+    pub fn describe_markers(_markers: &Vec<SampleMarker>, _sampling_rate: u32) -> String {
+        return _markers
+            .iter()
+            .map(|_marker| {
+                format!(
+                    "\n\t\t- \'{}\': {:.3} ... {:.3} s",
+                    _marker.label,
+                    _marker.start as f64 / _sampling_rate as f64,
+                    _marker.end as f64 / _sampling_rate as f64
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("");
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Lista etykiet znaczników do komunikatu o błędzie.
+    fn describe_labels(_markers: &Vec<SampleMarker>) -> String {
+        if _markers.is_empty() == true {
+            return "brak".to_string();
+        }
+
+        return _markers
+            .iter()
+            .map(|_marker| format!("\'{}\'", _marker.label))
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    // Dzieli zawartość pliku RIFF na bloki (identyfikator i dane), bloki o nieparzystej długości są wyrównywane.
+    fn riff_chunks(_contents: &[u8]) -> Vec<(&[u8; 4], &[u8])> {
+        let mut chunks: Vec<(&[u8; 4], &[u8])> = Vec::new();
+        let mut offset: usize = 0;
+
+        while offset + 8 <= _contents.len() {
+            let id: &[u8; 4] = _contents[offset..offset + 4].try_into().unwrap();
+            let size = read_u32(_contents, offset + 4) as usize;
+            let end = (offset + 8 + size).min(_contents.len());
+
+            chunks.push((id, &_contents[offset + 8..end]));
+            offset = offset + 8 + size + size % 2;
+        }
+
+        return chunks;
+    }
+
+    fn read_u16(_bytes: &[u8], _offset: usize) -> u16 {
+        return u16::from_le_bytes([_bytes[_offset], _bytes[_offset + 1]]);
+    }
+
+    fn read_u32(_bytes: &[u8], _offset: usize) -> u32 {
+        return u32::from_le_bytes([
+            _bytes[_offset],
+            _bytes[_offset + 1],
+            _bytes[_offset + 2],
+            _bytes[_offset + 3],
+        ]);
+    }
+
+    // Tekst zakończony znakiem zerowym.
+    fn read_text(_bytes: &[u8]) -> String {
+        let end = _bytes.iter().position(|_byte| *_byte == 0).unwrap_or(_bytes.len());
+
+        return String::from_utf8_lossy(&_bytes[..end]).trim().to_string();
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::fs::{remove_file, write};
+
+        use super::*;
+
+        fn chunk(_id: &[u8; 4], _data: &[u8]) -> Vec<u8> {
+            let mut bytes = _id.to_vec();
+
+            bytes.extend_from_slice(&(_data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(_data);
+
+            if _data.len() % 2 == 1 {
+                bytes.push(0);
+            }
+
+            return bytes;
+        }
+
+        fn words(_values: &[u32]) -> Vec<u8> {
+            return _values.iter().flat_map(|_value| _value.to_le_bytes().to_vec()).collect();
+        }
+
+        // Plik WAV (mono, 16 bitów, 1000 próbek) z punktami 'cue' zapisanymi poza kolejnością, etykietami 'labl'
+        // (jedna o nieparzystej długości), długością 'ltxt' i pętlą 'smpl'.
+        fn marked_wav() -> Vec<u8> {
+            let mut format: Vec<u8> = Vec::new();
+            format.extend_from_slice(&1u16.to_le_bytes());
+            format.extend_from_slice(&1u16.to_le_bytes());
+            format.extend(words(&[48_000, 96_000]));
+            format.extend_from_slice(&2u16.to_le_bytes());
+            format.extend_from_slice(&16u16.to_le_bytes());
+
+            let mut cue = words(&[4]);
+            for (_id, _position) in [(2, 400), (1, 100), (4, 800), (3, 700)].iter() {
+                cue.extend(words(&[*_id, *_position]));
+                cue.extend_from_slice(b"data");
+                cue.extend(words(&[0, 0, *_position]));
+            }
+
+            let mut adtl = b"adtl".to_vec();
+            adtl.extend(chunk(b"labl", &[words(&[1]), b"attack\0".to_vec()].concat()));
+            adtl.extend(chunk(b"labl", &[words(&[2]), b"body\0".to_vec()].concat()));
+            adtl.extend(chunk(b"labl", &[words(&[4]), b"sustain\0".to_vec()].concat()));
+            adtl.extend(chunk(b"ltxt", &[words(&[1, 50]), b"rgn ".to_vec(), vec![0; 8]].concat()));
+
+            let mut sampler = words(&[0, 0, 20_833, 60, 0, 0, 0, 1, 0]);
+            sampler.extend(words(&[4, 0, 800, 899, 0, 0]));
+
+            let mut wave = b"WAVE".to_vec();
+            wave.extend(chunk(b"fmt ", &format));
+            wave.extend(chunk(b"data", &vec![0; 2000]));
+            wave.extend(chunk(b"cue ", &cue));
+            wave.extend(chunk(b"LIST", &adtl));
+            wave.extend(chunk(b"smpl", &sampler));
+
+            return chunk(b"RIFF", &wave);
+        }
+
+        #[test]
+        fn riff_chunks_skip_padding_bytes() {
+            let contents = [chunk(b"abcd", b"xyz"), chunk(b"efgh", b"12")].concat();
+            let chunks = riff_chunks(&contents);
+
+            assert_eq!(chunks.len(), 2);
+            assert_eq!((chunks[0].0, chunks[0].1), (b"abcd", &b"xyz"[..]));
+            assert_eq!((chunks[1].0, chunks[1].1), (b"efgh", &b"12"[..]));
+        }
+
+        #[test]
+        fn load_markers_reads_cue_labels_lengths_and_loops() {
+            let file_path = std::env::temp_dir()
+                .join(format!("granular_synth_markers_{}.wav", std::process::id()))
+                .to_string_lossy()
+                .to_string();
+
+            write(&file_path, marked_wav()).unwrap();
+            let markers = load_markers(&file_path);
+            remove_file(&file_path).unwrap();
+
+            let markers: Vec<(String, usize, usize)> = markers
+                .unwrap()
+                .into_iter()
+                .map(|_marker| (_marker.label, _marker.start, _marker.end))
+                .collect();
+
+            assert_eq!(
+                markers,
+                vec![
+                    ("attack".to_string(), 100, 150),
+                    ("body".to_string(), 400, 700),
+                    ("cue_3".to_string(), 700, 800),
+                    ("sustain".to_string(), 800, 900),
+                ]
+            );
+        }
+
+        #[test]
+        fn load_markers_rejects_non_riff_files() {
+            let file_path = std::env::temp_dir()
+                .join(format!("granular_synth_not_riff_{}.wav", std::process::id()))
+                .to_string_lossy()
+                .to_string();
+
+            write(&file_path, b"not a wave file").unwrap();
+            let markers = load_markers(&file_path);
+            remove_file(&file_path).unwrap();
+
+            assert!(markers.is_err());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+pub mod core {
+    use std::fs::read;
+
+    use midly::{MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+
+    use crate::granular_synth_config::tools::MidiSequence;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Tempo pliku MIDI przed pierwszym zdarzeniem zmiany tempa (120 BPM).
+    const DEFAULT_TEMPO_US_PER_BEAT: f64 = 500_000.0;
+    const PAN_CONTROLLER: u8 = 10;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Nuta pliku MIDI: czas rozpoczęcia, numer nuty, prędkość i panorama kanału (kontroler 10) w chwili jej rozpoczęcia.
+    #[derive(Debug, Clone)]
+    pub struct MidiNote {
+        pub time_s: f64,
+        pub key: u8,
+        pub velocity: u8,
+        pub panorama: f64,
+    }
+
+    // Nuty wybranych ścieżek i kanałów pliku MIDI oraz czas trwania pliku (w sekundach).
+    #[derive(Debug)]
+    pub struct MidiNotes {
+        pub notes: Vec<MidiNote>,
+        pub length_s: f64,
+    }
+
+    // Wczytuje plik MIDI i przelicza położenia nut na czas zgodnie z mapą tempa pliku.
+    pub fn load_midi_notes(_midi_sequence: &MidiSequence) -> Result<MidiNotes, String> {
+        let midi_file_bytes = match read(&_midi_sequence.file) {
+            Ok(_midi_file_bytes) => _midi_file_bytes,
+            Err(_system_error) => {
+                return Err(format!(
+                    "Błąd odczytu ->\n\tplik: \'{}\' nie został znaleziony.\n\tSystem error: {} :/",
+                    _midi_sequence.file, _system_error
+                ));
+            }
+        };
+
+        let smf = match Smf::parse(&midi_file_bytes) {
+            Ok(_smf) => _smf,
+            Err(_error) => {
+                return Err(format!(
+                    "Błąd danych ->\n\tplik: \'{}\' nie jest prawidłowym plikiem MIDI.\n\tMIDI error: {} :/",
+                    _midi_sequence.file, _error
+                ));
+            }
+        };
+
+        if let Some(_track) = _midi_sequence.track {
+            if _track > smf.tracks.len() {
+                return Err(format!(
+                    "Błąd danych ->\n\tplik: \'{}\' zawiera {} ścieżek, wybrano ścieżkę: [{}] :/",
+                    _midi_sequence.file,
+                    smf.tracks.len(),
+                    _track
+                ));
+            }
+        }
+
+        // Zdarzenia wszystkich ścieżek w kolejności czasu (zmiany tempa mogą występować w dowolnej ścieżce).
+        let mut events: Vec<(u64, usize, TrackEventKind)> = Vec::new();
+
+        for (_track_index, _track) in smf.tracks.iter().enumerate() {
+            let mut tick: u64 = 0;
+
+            for _event in _track.iter() {
+                tick += _event.delta.as_int() as u64;
+                events.push((tick, _track_index, _event.kind));
+            }
+        }
+
+        events.sort_by_key(|(_tick, _, _)| *_tick);
+
+        let (ticks_per_beat, mut seconds_per_tick): (Option<f64>, f64) = match smf.header.timing {
+            Timing::Metrical(_ticks_per_beat) => {
+                let ticks_per_beat = _ticks_per_beat.as_int() as f64;
+
+                (Some(ticks_per_beat), DEFAULT_TEMPO_US_PER_BEAT / 1e6 / ticks_per_beat)
+            }
+            Timing::Timecode(_fps, _subframes) => {
+                (None, 1.0 / (_fps.as_f32() as f64 * _subframes as f64))
+            }
+        };
+
+        let mut notes: Vec<MidiNote> = Vec::new();
+        let mut channels_panorama: [f64; 16] = [0.0; 16];
+        let mut time_s: f64 = 0.0;
+        let mut last_tick: u64 = 0;
+
+        for (_tick, _track_index, _kind) in events.iter() {
+            time_s += (*_tick - last_tick) as f64 * seconds_per_tick;
+            last_tick = *_tick;
+
+            match _kind {
+                TrackEventKind::Meta(MetaMessage::Tempo(_us_per_beat)) => {
+                    if let Some(_ticks_per_beat) = ticks_per_beat {
+                        seconds_per_tick = _us_per_beat.as_int() as f64 / 1e6 / _ticks_per_beat;
+                    }
+                }
+                TrackEventKind::Midi { channel, message } => {
+                    let channel = channel.as_int();
+
+                    if _midi_sequence.track.is_some_and(|_track| _track != _track_index + 1)
+                        || _midi_sequence.channel.is_some_and(|_channel| _channel != channel + 1)
+                    {
+                        continue;
+                    }
+
+                    match message {
+                        MidiMessage::Controller { controller, value }
+                            if controller.as_int() == PAN_CONTROLLER =>
+                        {
+                            channels_panorama[channel as usize] =
+                                ((value.as_int() as f64 - 64.0) / 63.0).clamp(-1.0, 1.0);
+                        }
+                        MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+                            notes.push(MidiNote {
+                                time_s,
+                                key: key.as_int(),
+                                velocity: vel.as_int(),
+                                panorama: channels_panorama[channel as usize],
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(MidiNotes {
+            notes,
+            length_s: time_s,
+        });
+    }
+}
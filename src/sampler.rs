@@ -1,15 +1,18 @@
 pub mod core {
-    use std::collections::VecDeque;
+    use std::collections::{BTreeMap, VecDeque};
 
-    use rand::distributions::{Distribution, Uniform};
-    use rand::prelude::{SliceRandom, ThreadRng};
-    use rand::thread_rng;
+    use rand::distributions::{Distribution, Uniform, WeightedIndex};
+    use rand::prelude::{SeedableRng, SliceRandom, StdRng};
     use serde::Deserialize;
 
     use crate::audio::tools::AudioBuffer;
+    use crate::descriptors::core::{DescriptorAnalyser, DescriptorSpace, GrainDescriptors, PitchMarks};
     use crate::granular_synth_config::tools::{
-        GWFunction, GrainsLength, GrainsPitch, GrainsProperties, SynthConfiguration,
+        Descriptor, DescriptorSelection, GWFunction, GrainsAlignment, GrainsLength, GrainsPitch,
+        GrainsProperties, SynthConfiguration,
     };
+    use crate::markers::tools::checked_source_regions;
+    use crate::sequencer::core::EventGrain;
 
     use rubato::{
         interpolator_avx::AvxInterpolator, InterpolationType, Resampler, SincFixedIn,
@@ -20,17 +23,106 @@ pub mod core {
 
     const SINC_LEN: usize = 256;
     const OVERSAMPLING_FACTOR: usize = 256;
+    // Częstotliwość odcięcia filtru sinc względem częstotliwości Nyquista sygnału wejściowego.
+    const SINC_CUTOFF: f32 = 0.95;
+
+    // Zakres poszukiwania przejścia przez zero wokół wylosowanego początku granulki (w milisekundach).
+    const ZERO_CROSSING_SEARCH_MS: f64 = 10.0;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Dzieli '_grains_count' granulek proporcjonalnie do udziałów procentowych '_fractions' metodą największych reszt,
+    // tak aby liczby granulek sumowały się do '_grains_count'. Każdy niezerowy udział otrzymuje co najmniej jedną
+    // granulkę (kosztem udziału o największej liczbie granulek), o ile granulek wystarcza.
+    fn split_grains_count(_fractions: &Vec<f64>, _grains_count: usize) -> Vec<usize> {
+        let total: f64 = _fractions.iter().map(|_fraction| _fraction.max(0.0)).sum();
+
+        if total <= 0.0 || _fractions.is_empty() == true {
+            return vec![0; _fractions.len()];
+        }
+
+        let quotas: Vec<f64> = _fractions
+            .iter()
+            .map(|_fraction| _grains_count as f64 * _fraction.max(0.0) / total)
+            .collect();
+        let mut counts: Vec<usize> = quotas.iter().map(|_quota| _quota.floor() as usize).collect();
+        let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+
+        by_remainder.sort_by(|a, b| {
+            (quotas[*b] - quotas[*b].floor())
+                .partial_cmp(&(quotas[*a] - quotas[*a].floor()))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let assigned: usize = counts.iter().sum();
+
+        for index in by_remainder.iter().take(_grains_count - assigned) {
+            counts[*index] += 1;
+        }
+
+        for index in 0..counts.len() {
+            if counts[index] == 0 && _fractions[index] > 0.0 {
+                let largest = (0..counts.len()).max_by_key(|_index| counts[*_index]).unwrap();
+
+                if counts[largest] > 1 {
+                    counts[largest] -= 1;
+                    counts[index] = 1;
+                }
+            }
+        }
+
+        return counts;
+    }
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
+    // Granulka wraz z położeniem jej początku w samplu (w sekundach), wysokością dźwięku, z jaką została wycięta,
+    // i indeksem sampla źródłowego.
+    #[derive(Debug)]
+    pub struct Grain {
+        pub buffer: AudioBuffer,
+        pub source_offset_s: f64,
+        pub pitch: f64,
+        pub source: usize,
+    }
+
+    // Sampel źródłowy przepróbkowany z wysokością dźwięku 'pitch' wraz z indeksem sampla, udziałem procentowym
+    // granulek, zakresami próbek fragmentów sampla (pusta lista oznacza cały sampel) i położeniami wykrytych onsetów.
+    #[derive(Debug)]
+    struct ResampledSource {
+        source: usize,
+        buffer: AudioBuffer,
+        pitch: f64,
+        fraction: f64,
+        regions: Vec<(usize, usize)>,
+        onsets: Vec<usize>,
+    }
+
     // Struktura reprezentująca sampler.
     #[derive(Debug, Deserialize)]
     pub struct Sampler {
         #[serde(skip_deserializing)]
-        pub grains_buffer: VecDeque<AudioBuffer>,
+        pub grains_buffer: VecDeque<Grain>,
 
+        // Sample źródłowe po zmianie częstotliwości próbkowania dla każdej wysokości dźwięku, zachowywane pomiędzy
+        // kolejnymi wariantami syntezy.
         #[serde(skip_deserializing)]
-        randomness_source: ThreadRng,
+        resampled_sources: Vec<ResampledSource>,
+
+        // Sample źródłowe w oryginalnej częstotliwości próbkowania, wczytywane przy odtwarzaniu wykazu zdarzeń.
+        #[serde(skip_deserializing)]
+        source_samples: BTreeMap<usize, (AudioBuffer, u32)>,
+
+        // Deskryptory granulek bufora i przestrzeń deskryptorów, tworzone przy wyborze granulek na podstawie
+        // deskryptorów lub dopasowaniu granulek do dźwięku docelowego.
+        #[serde(skip_deserializing)]
+        pub descriptors: Vec<GrainDescriptors>,
+
+        #[serde(skip_deserializing)]
+        descriptor_space: Option<DescriptorSpace>,
+
+        #[serde(skip_deserializing, default = "StdRng::from_entropy")]
+        randomness_source: StdRng,
     }
 
     impl Sampler {
@@ -38,101 +130,335 @@ pub mod core {
         pub fn default() -> Self {
             return Sampler {
                 grains_buffer: VecDeque::new(),
-                randomness_source: thread_rng(),
+                resampled_sources: Vec::new(),
+                source_samples: BTreeMap::new(),
+                descriptors: Vec::new(),
+                descriptor_space: None,
+                randomness_source: StdRng::from_entropy(),
             };
         }
 
-        // Wczytanie sampla i utworzenie granulek.
+        // Ustawia ziarno generatora liczb losowych, pozwalając odtworzyć wynik syntezy.
+        pub fn seed(&mut self, _seed: u64) {
+            self.randomness_source = StdRng::seed_from_u64(_seed);
+        }
+
+        // Wczytanie sampla i utworzenie granulek. Sampel jest wczytywany i przepróbkowywany tylko
+        // przy pierwszym wywołaniu, kolejne wywołania jedynie wycinają nowy zestaw granulek.
         pub fn prepare(
             &mut self,
             _synth_configuration: &SynthConfiguration,
             _grains_properties: &GrainsProperties,
+            _track_number: usize,
         ) -> Result<(), String> {
-            let sample_result = AudioBuffer::load_audio(&_grains_properties.sample_file_path);
-
-            // Wczytanie sampla, obsługa błędów długości.
-            let (sample_audio_buffer, sample_sampling_rate) = match sample_result {
-                Ok((_audio_buffer, _sampling_rate)) => {
-                    let sample_length_ms =
-                        (_audio_buffer.len() as f64 / _sampling_rate as f64) * 1000.0;
-
-                    match _grains_properties.grains_length_ms {
-                        GrainsLength::Fixed { equal } => {
-                            if equal * 1.5 > sample_length_ms {
-                                return Err(
-                                format!(
-                                    "Błąd danych ->\n\tsampel: \'{}\' jest za krótki (sampel musi mieć długość co najmniej: 1.5 x \'grains_length_ms\': \'equal\') :/",
-                                    _grains_properties.sample_file_path
-                                )
-                            );
-                            }
-                        }
-                        GrainsLength::Range { from: _, to } => {
-                            if to * 1.5 > sample_length_ms {
-                                return Err(
-                                format!(
-                                    "Błąd danych ->\n\tsampel: \'{}\' jest za krótki (sampel musi mieć długość co najmniej: 1.5 x \'grains_length_ms\': \'to\') :/",
-                                    _grains_properties.sample_file_path
-                                )
-                            );
-                            }
-                        }
-                    };
+            if self.resampled_sources.is_empty() == true {
+                match self.load_sources(_synth_configuration, _grains_properties, _track_number) {
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                    _ => {}
+                }
+            }
+
+            // Utworzenie granulek dla każdego sampla źródłowego i modelu modyfikacji wysokości dźwięku.
+            let resampled_sources = std::mem::take(&mut self.resampled_sources);
+            let sources = _grains_properties.sample_sources();
 
-                    (_audio_buffer, _sampling_rate)
+            let grains_counts = split_grains_count(
+                &resampled_sources
+                    .iter()
+                    .map(|_resampled_source| _resampled_source.fraction)
+                    .collect(),
+                _grains_properties.grains_count,
+            );
+
+            self.grains_buffer.clear();
+            self.grains_buffer.reserve(_grains_properties.grains_count);
+
+            for (resampled_source, grains_count) in resampled_sources.iter().zip(grains_counts) {
+                for _ in 0..grains_count {
+                    let mut grain = self.get_random_grain(resampled_source, _synth_configuration, _grains_properties);
+
+                    if sources[resampled_source.source].gain != 1.0 {
+                        grain.buffer.amplify(sources[resampled_source.source].gain);
+                    }
+
+                    self.grains_buffer.push_back(grain);
                 }
-                Err(_error) => {
-                    return Err(_error);
+            }
+
+            self.resampled_sources = resampled_sources;
+
+            match &_grains_properties.grains_pitch {
+                GrainsPitch::Fixed => {}
+                GrainsPitch::Steps { steps: _ } => {
+                    self.grains_buffer
+                        .make_contiguous()
+                        .shuffle(&mut self.randomness_source);
                 }
+            }
+
+            self.descriptors.clear();
+            self.descriptor_space = None;
+
+            if let Some(_descriptor_selection) = &_grains_properties.descriptor_selection {
+                let descriptors: Vec<Descriptor> =
+                    _descriptor_selection.targets.keys().cloned().collect();
+
+                self.index_grains(
+                    &|_grain_descriptors| {
+                        descriptors
+                            .iter()
+                            .map(|_descriptor| _grain_descriptors.value(*_descriptor))
+                            .collect()
+                    },
+                    &vec![1.0; descriptors.len()],
+                    _synth_configuration,
+                );
+            }
+
+            return Ok(());
+        }
+
+        // Wyznacza deskryptory granulek bufora i buduje przestrzeń deskryptorów z wektorów cech '_features'
+        // (z wagami wymiarów '_weights'), w której wyszukiwane są granulki najbliższe wartościom docelowym.
+        pub fn index_grains(
+            &mut self,
+            _features: &dyn Fn(&GrainDescriptors) -> Vec<f64>,
+            _weights: &Vec<f64>,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            if self.descriptors.len() != self.grains_buffer.len() {
+                let mut analyser = DescriptorAnalyser::new();
+                let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+
+                self.descriptors = self
+                    .grains_buffer
+                    .iter()
+                    .map(|_grain| analyser.analyse(&_grain.buffer, sampling_rate))
+                    .collect();
+            }
+
+            let vectors: Vec<Vec<f64>> = self.descriptors.iter().map(_features).collect();
+
+            self.descriptor_space = Some(DescriptorSpace::build(&vectors, _weights));
+        }
+
+        // Zwraca granulkę o wektorze cech najbliższym wektorowi docelowemu, wybraną losowo spośród '_neighbours'
+        // najbliższych granulek (wymaga wcześniejszego wywołania 'index_grains').
+        pub fn sample_nearest(&mut self, _target: &[f64], _neighbours: usize) -> &Grain {
+            let nearest = match &self.descriptor_space {
+                Some(_descriptor_space) => _descriptor_space.nearest(_target, _neighbours),
+                None => Vec::new(),
             };
 
-            // Utworzenie granulek dla każdego modelu modyfikacji wysokości dźwięku.
-            self.grains_buffer.reserve(_grains_properties.grains_count);
+            let grain_index = match nearest.len() {
+                0 => 0,
+                1 => nearest[0],
+                _ => *nearest.choose(&mut self.randomness_source).unwrap(),
+            };
 
-            match &_grains_properties.grains_pitch {
-                GrainsPitch::Fixed => {
-                    let resampled_audio_buffer: AudioBuffer = self.resample_audio(
-                        &sample_audio_buffer,
-                        sample_sampling_rate,
-                        1.0,
-                        _synth_configuration,
-                    );
+            return &self.grains_buffer[grain_index];
+        }
 
-                    for _ in 0.._grains_properties.grains_count {
-                        let grain = self.get_random_grain(
-                            &resampled_audio_buffer,
-                            _synth_configuration,
-                            _grains_properties,
-                        );
-                        self.grains_buffer.push_back(grain);
+        // Zwraca granulkę o deskryptorach najbliższych wartościom docelowym w podanym położeniu ścieżki (0 ... 1).
+        // Deskryptory są normalizowane średnią i odchyleniem standardowym, więc deskryptory o różnych jednostkach
+        // mają równy wpływ na wybór.
+        pub fn sample_by_descriptors(
+            &mut self,
+            _descriptor_selection: &DescriptorSelection,
+            _position: f64,
+        ) -> &Grain {
+            let target: Vec<f64> = _descriptor_selection
+                .targets
+                .values()
+                .map(|_curve| _curve.value_at(_position))
+                .collect();
+
+            return self.sample_nearest(&target, _descriptor_selection.neighbours);
+        }
+
+        // Wycięcie granulek syntezy synchronicznej z okresem: po jednej granulce o długości '_periods' okresów wokół
+        // każdego znacznika (w kolejności znaczników, bez losowania), z oknem czasowym ścieżki.
+        pub fn prepare_pitch_synchronous(
+            &mut self,
+            _pitch_marks: &PitchMarks,
+            _periods: usize,
+            _synth_configuration: &SynthConfiguration,
+            _grains_properties: &GrainsProperties,
+            _track_number: usize,
+        ) -> Result<(), String> {
+            if self.resampled_sources.is_empty() == true {
+                match self.load_sources(_synth_configuration, _grains_properties, _track_number) {
+                    Err(_error) => {
+                        return Err(_error);
                     }
+                    _ => {}
                 }
-                GrainsPitch::Steps { steps } => {
-                    for (_pitch, _fraction) in steps.iter() {
-                        let resampled_buffer: AudioBuffer = self.resample_audio(
-                            &sample_audio_buffer,
-                            sample_sampling_rate,
-                            *_pitch,
-                            _synth_configuration,
-                        );
-                        let grains_count: usize = (_grains_properties.grains_count as f64
-                            * (*_fraction / 100.0))
-                            .round() as usize;
-
-                        for _ in 0..grains_count {
-                            let grain = self.get_random_grain(
-                                &resampled_buffer,
-                                _synth_configuration,
-                                _grains_properties,
-                            );
+            }
 
-                            self.grains_buffer.push_back(grain);
+            let resampled_sources = std::mem::take(&mut self.resampled_sources);
+            let resampled_source = &resampled_sources[0];
+            let gain = _grains_properties.sample_sources()[0].gain;
+            let length = resampled_source.buffer.len();
+            let samples_per_second = _synth_configuration.engine_sampling_rate as f64 / resampled_source.pitch;
+
+            self.grains_buffer.clear();
+            self.descriptors.clear();
+            self.descriptor_space = None;
+
+            for mark in _pitch_marks.marks.iter() {
+                let grain_length = ((_periods as f64 * mark.period_s * samples_per_second).round() as usize)
+                    .max(2)
+                    .min(length);
+                let start = ((mark.position_s * samples_per_second).round() as usize)
+                    .saturating_sub(grain_length / 2)
+                    .min(length - grain_length);
+
+                let mut grain_output = match &resampled_source.buffer {
+                    AudioBuffer::Mono(_buffer) => AudioBuffer::Mono([_buffer[0][start..start + grain_length].to_vec()]),
+                    AudioBuffer::Stereo(_buffer) => AudioBuffer::Stereo([
+                        _buffer[0][start..start + grain_length].to_vec(),
+                        _buffer[1][start..start + grain_length].to_vec(),
+                    ]),
+                };
+
+                match &mut grain_output {
+                    AudioBuffer::Mono(_buffer) => {
+                        self.apply_window_function(&mut _buffer[0], &_grains_properties.window_function, _synth_configuration);
+                    }
+                    AudioBuffer::Stereo(_buffer) => {
+                        self.apply_window_function(&mut _buffer[0], &_grains_properties.window_function, _synth_configuration);
+                        self.apply_window_function(&mut _buffer[1], &_grains_properties.window_function, _synth_configuration);
+                    }
+                }
+
+                if _grains_properties.grains_laudness_normalization == true {
+                    grain_output.normalize(1.0);
+                }
+
+                if gain != 1.0 {
+                    grain_output.amplify(gain);
+                }
+
+                self.grains_buffer.push_back(Grain {
+                    buffer: grain_output,
+                    source_offset_s: start as f64 / samples_per_second,
+                    pitch: resampled_source.pitch,
+                    source: resampled_source.source,
+                });
+            }
+
+            self.resampled_sources = resampled_sources;
+
+            return Ok(());
+        }
+
+        // Zwraca granulkę wyciętą wokół znacznika okresu o podanym indeksie (wymaga wcześniejszego wywołania
+        // 'prepare_pitch_synchronous').
+        pub fn pitch_mark_grain(&self, _pitch_mark: usize) -> &Grain {
+            return &self.grains_buffer[_pitch_mark];
+        }
+
+        // Wczytanie sampli źródłowych i zmiana ich częstotliwości próbkowania dla każdej wysokości dźwięku.
+        // Udział granulek sampla jest proporcjonalny do średniej wagi sampla w całej ścieżce.
+        fn load_sources(
+            &mut self,
+            _synth_configuration: &SynthConfiguration,
+            _grains_properties: &GrainsProperties,
+            _track_number: usize,
+        ) -> Result<(), String> {
+            let sources = _grains_properties.sample_sources();
+            let mean_weights: Vec<f64> = sources
+                .iter()
+                .map(|_source| _source.weight.mean().max(0.0))
+                .collect();
+            let total_weight: f64 = mean_weights.iter().sum();
+
+            for (_source_index, _source) in sources.iter().enumerate() {
+                let sample_result = AudioBuffer::load_audio(&_source.file_path);
+
+                // Wczytanie sampla, wyznaczenie fragmentów, obsługa błędów długości.
+                let (sample_audio_buffer, sample_sampling_rate, regions) = match sample_result {
+                    Ok((_audio_buffer, _sampling_rate)) => {
+                        match checked_source_regions(
+                            _grains_properties,
+                            _track_number,
+                            _source_index,
+                            _audio_buffer.len(),
+                            _sampling_rate,
+                        ) {
+                            Ok(_regions) => (_audio_buffer, _sampling_rate, _regions),
+                            Err(_error) => {
+                                return Err(_error.to_string());
+                            }
                         }
                     }
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                };
+
+                let share: f64 = if total_weight > 0.0 {
+                    mean_weights[_source_index] / total_weight
+                } else {
+                    1.0 / sources.len() as f64
+                };
+
+                let steps: Vec<(f64, f64)> = match &_grains_properties.grains_pitch {
+                    GrainsPitch::Fixed => vec![(1.0, 100.0)],
+                    GrainsPitch::Steps { steps } => steps.clone(),
+                };
+
+                // Onsety są wykrywane raz, w oryginalnym samplu.
+                let onsets: Vec<usize> = match &_grains_properties.grains_alignment {
+                    GrainsAlignment::Onsets {
+                        threshold,
+                        min_interval_ms,
+                        ..
+                    } => DescriptorAnalyser::new().detect_onsets(
+                        &sample_audio_buffer,
+                        sample_sampling_rate as f64,
+                        *threshold,
+                        min_interval_ms / 1000.0,
+                    ),
+                    _ => Vec::new(),
+                };
+
+                for (_pitch, _fraction) in steps.iter() {
+                    let pitch = *_pitch * _source.pitch;
+                    let resampled_buffer: AudioBuffer = Sampler::resample_audio(
+                        &sample_audio_buffer,
+                        sample_sampling_rate,
+                        pitch,
+                        SINC_CUTOFF,
+                        _synth_configuration,
+                    );
 
-                    self.grains_buffer
-                        .make_contiguous()
-                        .shuffle(&mut self.randomness_source);
+                    // Zakresy fragmentów i położenia onsetów w próbkach sampla przepróbkowanego.
+                    let scale = _synth_configuration.engine_sampling_rate as f64
+                        / (sample_sampling_rate as f64 * pitch);
+                    let resampled_regions: Vec<(usize, usize)> = regions
+                        .iter()
+                        .map(|(_start, _end)| {
+                            (
+                                (*_start as f64 * scale).round() as usize,
+                                (*_end as f64 * scale).round() as usize,
+                            )
+                        })
+                        .collect();
+
+                    self.resampled_sources.push(ResampledSource {
+                        source: _source_index,
+                        buffer: resampled_buffer,
+                        pitch,
+                        fraction: *_fraction * share,
+                        regions: resampled_regions,
+                        onsets: onsets
+                            .iter()
+                            .map(|_onset| (*_onset as f64 * scale).round() as usize)
+                            .collect(),
+                    });
                 }
             }
 
@@ -140,7 +466,7 @@ pub mod core {
         }
 
         // Zwraca losowo wybraną granulkę z bufora.
-        pub fn sample(&mut self) -> &AudioBuffer {
+        pub fn sample(&mut self) -> &Grain {
             let half_buffer: usize = self.grains_buffer.len() / 2;
             let pick_distr = Uniform::new(0, half_buffer);
 
@@ -153,18 +479,211 @@ pub mod core {
             return self.grains_buffer.back().unwrap();
         }
 
-        // Zmiana częstotliwości samplowania i/lub wysokości dźwięku.
+        // Zwraca losowo wybraną granulkę sampla źródłowego wylosowanego zgodnie z wagami '_weights' (wagi sampli
+        // w bieżącym położeniu ścieżki). Granulka jest wybierana spośród mniej niedawno użytych granulek sampla.
+        pub fn sample_weighted(&mut self, _weights: &Vec<f64>) -> &Grain {
+            let source = match WeightedIndex::new(_weights) {
+                Ok(_distribution) => _distribution.sample(&mut self.randomness_source),
+                Err(_) => {
+                    return self.sample();
+                }
+            };
+
+            let half_buffer: usize = self.grains_buffer.len() / 2;
+            let mut candidates: Vec<usize> = (0..half_buffer)
+                .filter(|_index| self.grains_buffer[*_index].source == source)
+                .collect();
+
+            if candidates.is_empty() == true {
+                candidates = (0..self.grains_buffer.len())
+                    .filter(|_index| self.grains_buffer[*_index].source == source)
+                    .collect();
+            }
+
+            let grain_index = match candidates.choose(&mut self.randomness_source) {
+                Some(_grain_index) => *_grain_index,
+                None => {
+                    return self.sample();
+                }
+            };
+
+            let grain = self.grains_buffer.remove(grain_index).unwrap();
+            self.grains_buffer.push_back(grain);
+
+            return self.grains_buffer.back().unwrap();
+        }
+
+        // Wycięcie granulki o zapisanym położeniu w samplu, długości i wysokości dźwięku (odtworzenie wykazu zdarzeń,
+        // '_event_number' to numer zdarzenia od 1). Każdy sampel źródłowy jest przepróbkowywany tylko raz dla każdej
+        // wysokości dźwięku występującej w wykazie.
+        pub fn grain_at(
+            &mut self,
+            _event_grain: &EventGrain,
+            _event_number: usize,
+            _window_function: &GWFunction,
+            _synth_configuration: &SynthConfiguration,
+            _grains_properties: &GrainsProperties,
+        ) -> Result<AudioBuffer, String> {
+            let sources = _grains_properties.sample_sources();
+
+            if _event_grain.source >= sources.len() {
+                return Err(format!(
+                    "Błąd danych ->\n\tzdarzenie: [{}] wskazuje sampel źródłowy: [{}], ścieżka ma sampli źródłowych: {} :/",
+                    _event_number,
+                    _event_grain.source + 1,
+                    sources.len()
+                ));
+            }
+
+            if self.source_samples.contains_key(&_event_grain.source) == false {
+                match AudioBuffer::load_audio(&sources[_event_grain.source].file_path) {
+                    Ok(_source_sample) => {
+                        self.source_samples.insert(_event_grain.source, _source_sample);
+                    }
+                    Err(_error) => {
+                        return Err(_error);
+                    }
+                }
+            }
+
+            let source_index = match self.resampled_sources.iter().position(|_resampled_source| {
+                _resampled_source.source == _event_grain.source
+                    && _resampled_source.pitch == _event_grain.pitch
+            }) {
+                Some(_source_index) => _source_index,
+                None => {
+                    let (source_audio_buffer, source_sampling_rate) =
+                        &self.source_samples[&_event_grain.source];
+
+                    let resampled_buffer = Sampler::resample_audio(
+                        source_audio_buffer,
+                        *source_sampling_rate,
+                        _event_grain.pitch,
+                        SINC_CUTOFF,
+                        _synth_configuration,
+                    );
+
+                    self.resampled_sources.push(ResampledSource {
+                        source: _event_grain.source,
+                        buffer: resampled_buffer,
+                        pitch: _event_grain.pitch,
+                        fraction: 0.0,
+                        regions: Vec::new(),
+                        onsets: Vec::new(),
+                    });
+                    self.resampled_sources.len() - 1
+                }
+            };
+
+            let resampled_buffer = &self.resampled_sources[source_index].buffer;
+
+            let start = ((_event_grain.source_offset_s * _synth_configuration.engine_sampling_rate as f64
+                / _event_grain.pitch)
+                .round() as usize)
+                .min(resampled_buffer.len());
+            let end = (start + _event_grain.length).min(resampled_buffer.len());
+
+            if start >= resampled_buffer.len() || end - start < 2 {
+                return Err(format!(
+                    "Błąd danych ->\n\tzdarzenie: [{}] wskazuje granulkę poza końcem sampla źródłowego: [{}] (\'source_offset_s\': {} s) :/",
+                    _event_number,
+                    _event_grain.source + 1,
+                    _event_grain.source_offset_s
+                ));
+            }
+
+            let mut grain_output = match resampled_buffer {
+                AudioBuffer::Mono(_buffer) => AudioBuffer::Mono([_buffer[0][start..end].to_vec()]),
+                AudioBuffer::Stereo(_buffer) => AudioBuffer::Stereo([
+                    _buffer[0][start..end].to_vec(),
+                    _buffer[1][start..end].to_vec(),
+                ]),
+            };
+
+            match &mut grain_output {
+                AudioBuffer::Mono(_buffer) => {
+                    self.apply_window_function(&mut _buffer[0], _window_function, _synth_configuration);
+                }
+                AudioBuffer::Stereo(_buffer) => {
+                    self.apply_window_function(&mut _buffer[0], _window_function, _synth_configuration);
+                    self.apply_window_function(&mut _buffer[1], _window_function, _synth_configuration);
+                }
+            }
+
+            if _grains_properties.grains_laudness_normalization == true {
+                grain_output.normalize(1.0);
+            }
+
+            if sources[_event_grain.source].gain != 1.0 {
+                grain_output.amplify(sources[_event_grain.source].gain);
+            }
+
+            return Ok(grain_output);
+        }
+
+        // Transpozycja granulki o wysokość zdarzenia '_pitch' przepróbkowaniem z interpolacją sinc (tak jak sampli
+        // źródłowych). Przy transpozycji w górę częstotliwość odcięcia filtru jest obniżana proporcjonalnie
+        // do wysokości, co zapobiega aliasingowi. Granulka jest uzupełniana ciszą, aby przepróbkowanie objęło także
+        // jej koniec.
+        pub fn transpose_grain(
+            _grain: &AudioBuffer,
+            _pitch: f64,
+            _synth_configuration: &SynthConfiguration,
+        ) -> AudioBuffer {
+            if _grain.len() < 2 {
+                return _grain.clone();
+            }
+
+            let length = ((_grain.len() - 1) as f64 / _pitch).floor() as usize + 1;
+            let padding = SINC_LEN + _pitch.ceil() as usize + 1;
+            let padded = |_channel: &Vec<f64>| -> Vec<f64> {
+                return _channel
+                    .iter()
+                    .cloned()
+                    .chain(std::iter::repeat_n(0.0, padding))
+                    .collect();
+            };
+
+            let padded_grain = match _grain {
+                AudioBuffer::Mono(_buffer) => AudioBuffer::Mono([padded(&_buffer[0])]),
+                AudioBuffer::Stereo(_buffer) => {
+                    AudioBuffer::Stereo([padded(&_buffer[0]), padded(&_buffer[1])])
+                }
+            };
+
+            let mut transposed_grain = Sampler::resample_audio(
+                &padded_grain,
+                _synth_configuration.engine_sampling_rate,
+                _pitch,
+                SINC_CUTOFF / _pitch.max(1.0) as f32,
+                _synth_configuration,
+            );
+
+            match &mut transposed_grain {
+                AudioBuffer::Mono(_buffer) => {
+                    _buffer[0].truncate(length);
+                }
+                AudioBuffer::Stereo(_buffer) => {
+                    _buffer[0].truncate(length);
+                    _buffer[1].truncate(length);
+                }
+            }
+
+            return transposed_grain;
+        }
+
+        // Zmiana częstotliwości samplowania i/lub wysokości dźwięku ('_cutoff' - częstotliwość odcięcia filtru sinc).
         fn resample_audio(
-            &self,
             _audio_buffer: &AudioBuffer,
             _audio_sampling_rate: u32,
             _pitch: f64,
+            _cutoff: f32,
             _synth_configuration: &SynthConfiguration,
         ) -> AudioBuffer {
             let interpolator = AvxInterpolator::<f64>::new(
                 SINC_LEN,
                 OVERSAMPLING_FACTOR,
-                0.95,
+                _cutoff,
                 WindowFunction::BlackmanHarris2,
             )
             .unwrap();
@@ -204,13 +723,153 @@ pub mod core {
             return resampled_audio_buffer;
         }
 
-        // Wycięcie granulki o zadanej długości z losowego miejsca sampla.
+        // Losuje początek granulki o długości '_grain_length' w jednym z fragmentów '_regions' bufora o długości
+        // '_length' (fragment jest wybierany z prawdopodobieństwem proporcjonalnym do liczby możliwych położeń granulki).
+        // Brak fragmentów, w których mieści się granulka, oznacza losowanie z całego bufora.
+        fn random_window(&mut self, _regions: &Vec<(usize, usize)>, _length: usize, _grain_length: usize) -> usize {
+            let windows_number = |_start: usize, _end: usize| -> usize {
+                return (_end.min(_length) + 1).saturating_sub(_start + _grain_length);
+            };
+
+            let regions: Vec<(usize, usize)> = _regions
+                .iter()
+                .filter(|(_start, _end)| windows_number(*_start, *_end) > 0)
+                .cloned()
+                .collect();
+
+            let (start, windows) = match regions.len() {
+                0 => (0, windows_number(0, _length)),
+                1 => (regions[0].0, windows_number(regions[0].0, regions[0].1)),
+                _ => {
+                    let windows: Vec<usize> = regions
+                        .iter()
+                        .map(|(_start, _end)| windows_number(*_start, *_end))
+                        .collect();
+                    let region_index = WeightedIndex::new(&windows)
+                        .unwrap()
+                        .sample(&mut self.randomness_source);
+
+                    (regions[region_index].0, windows[region_index])
+                }
+            };
+
+            let nth_distr = Uniform::new(0, windows);
+
+            return start + nth_distr.sample(&mut self.randomness_source);
+        }
+
+        // Najmniejszy i największy początek granulki o długości '_grain_length' we fragmencie '_regions' zawierającym
+        // położenie '_position' (lub w całym buforze o długości '_length', jeśli granulka nie mieści się w żadnym
+        // fragmencie obejmującym to położenie).
+        fn window_bounds(
+            _regions: &Vec<(usize, usize)>,
+            _length: usize,
+            _grain_length: usize,
+            _position: usize,
+        ) -> (usize, usize) {
+            return _regions
+                .iter()
+                .map(|(_start, _end)| (*_start, (*_end).min(_length).saturating_sub(_grain_length)))
+                .find(|(_first, _last)| _first <= _last && *_first <= _position && _position <= *_last)
+                .unwrap_or((0, _length.saturating_sub(_grain_length)));
+        }
+
+        // Początek granulki o długości '_grain_length' wyrównany zgodnie z '_alignment'. Onset jest wybierany spośród
+        // onsetów, od których granulka mieści się we fragmencie sampla, i przesuwany o losowe 'jitter_ms'; brak takich
+        // onsetów oznacza losowy początek. Przy wyrównaniu do przejścia przez zero losowy początek jest przesuwany do
+        // najbliższej zmiany znaku sygnału (sumy kanałów) w promieniu 'ZERO_CROSSING_SEARCH_MS'.
+        fn aligned_window(
+            &mut self,
+            _resampled_source: &ResampledSource,
+            _alignment: &GrainsAlignment,
+            _grain_length: usize,
+            _synth_configuration: &SynthConfiguration,
+        ) -> usize {
+            let engine_sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+            let regions = &_resampled_source.regions;
+            let length = _resampled_source.buffer.len();
+
+            match _alignment {
+                GrainsAlignment::Random => {
+                    return self.random_window(regions, length, _grain_length);
+                }
+                GrainsAlignment::Onsets { jitter_ms, .. } => {
+                    let fits = |_onset: usize| -> bool {
+                        if regions.is_empty() == true {
+                            return _onset + _grain_length <= length;
+                        }
+
+                        return regions.iter().any(|(_start, _end)| {
+                            *_start <= _onset && _onset + _grain_length <= (*_end).min(length)
+                        });
+                    };
+                    let onsets: Vec<usize> = _resampled_source
+                        .onsets
+                        .iter()
+                        .cloned()
+                        .filter(|_onset| fits(*_onset))
+                        .collect();
+
+                    if onsets.is_empty() == true {
+                        return self.random_window(regions, length, _grain_length);
+                    }
+
+                    let onset = *onsets.choose(&mut self.randomness_source).unwrap();
+                    let (first, last) = Sampler::window_bounds(regions, length, _grain_length, onset);
+                    let jitter = ((jitter_ms / 1000.0) * engine_sampling_rate).round() as i64;
+                    let shift = if jitter > 0 {
+                        Uniform::new_inclusive(-jitter, jitter).sample(&mut self.randomness_source)
+                    } else {
+                        0
+                    };
+
+                    return (onset as i64 + shift).max(first as i64).min(last as i64) as usize;
+                }
+                GrainsAlignment::ZeroCrossing => {
+                    let window_index = self.random_window(regions, length, _grain_length);
+                    let (first, last) = Sampler::window_bounds(regions, length, _grain_length, window_index);
+                    let search = ((ZERO_CROSSING_SEARCH_MS / 1000.0) * engine_sampling_rate).round() as usize;
+                    let signal = |_index: usize| -> f64 {
+                        match &_resampled_source.buffer {
+                            AudioBuffer::Mono(_buffer) => _buffer[0][_index],
+                            AudioBuffer::Stereo(_buffer) => _buffer[0][_index] + _buffer[1][_index],
+                        }
+                    };
+                    let is_crossing = |_index: usize| -> bool {
+                        return _index >= first.max(1)
+                            && _index <= last
+                            && (signal(_index) == 0.0 || (signal(_index - 1) < 0.0) != (signal(_index) < 0.0));
+                    };
+
+                    for distance in 0..=search {
+                        if is_crossing(window_index + distance) == true {
+                            return window_index + distance;
+                        }
+                        if distance <= window_index && is_crossing(window_index - distance) == true {
+                            return window_index - distance;
+                        }
+                    }
+
+                    return window_index;
+                }
+            }
+        }
+
+        // Wycięcie granulki o zadanej długości z losowego miejsca sampla. Położenie granulki w samplu przepróbkowanym
+        // z wysokością '_pitch' jest przeliczane na czas w oryginalnym samplu.
         fn get_random_grain(
             &mut self,
-            _sample: &AudioBuffer,
+            _resampled_source: &ResampledSource,
             _synth_configuration: &SynthConfiguration,
             _grains_properties: &GrainsProperties,
-        ) -> AudioBuffer {
+        ) -> Grain {
+            let engine_sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+            let (_sample, _pitch, _source) = (
+                &_resampled_source.buffer,
+                _resampled_source.pitch,
+                _resampled_source.source,
+            );
+
             match _grains_properties.grains_length_ms {
                 GrainsLength::Fixed { equal } => {
                     let grain_length: usize = ((equal / 1000.0)
@@ -219,13 +878,14 @@ pub mod core {
 
                     match _sample {
                         AudioBuffer::Mono(_buffer) => {
-                            let mut windows = _buffer[0].windows(grain_length);
-
-                            let windows_number = windows.len();
-                            let nth_distr = Uniform::new(0, windows_number);
-                            let window_index = nth_distr.sample(&mut self.randomness_source);
+                            let window_index = self.aligned_window(
+                                _resampled_source,
+                                &_grains_properties.grains_alignment,
+                                grain_length,
+                                _synth_configuration,
+                            );
 
-                            let mut grain = windows.nth(window_index).unwrap().to_vec();
+                            let mut grain = _buffer[0][window_index..window_index + grain_length].to_vec();
 
                             match _grains_properties.window_function {
                                 GWFunction::SmoothstepRegular { slope } => {
@@ -246,6 +906,9 @@ pub mod core {
                                         _synth_configuration,
                                     );
                                 }
+                                GWFunction::Symmetric => {
+                                    self.symmetric_window(&mut grain);
+                                }
                             }
 
                             let mut grain_output = AudioBuffer::Mono([grain]);
@@ -254,18 +917,23 @@ pub mod core {
                                 grain_output.normalize(1.0);
                             }
 
-                            return grain_output;
+                            return Grain {
+                                buffer: grain_output,
+                                source_offset_s: window_index as f64 * _pitch / engine_sampling_rate,
+                                pitch: _pitch,
+                                source: _source,
+                            };
                         }
                         AudioBuffer::Stereo(_buffer) => {
-                            let mut windows_left = _buffer[0].windows(grain_length);
-                            let mut windows_rigth = _buffer[1].windows(grain_length);
-
-                            let windows_number = windows_left.len();
-                            let nth_distr = Uniform::new(0, windows_number);
-                            let window_index = nth_distr.sample(&mut self.randomness_source);
+                            let window_index = self.aligned_window(
+                                _resampled_source,
+                                &_grains_properties.grains_alignment,
+                                grain_length,
+                                _synth_configuration,
+                            );
 
-                            let mut grain_left = windows_left.nth(window_index).unwrap().to_vec();
-                            let mut grain_rigth = windows_rigth.nth(window_index).unwrap().to_vec();
+                            let mut grain_left = _buffer[0][window_index..window_index + grain_length].to_vec();
+                            let mut grain_rigth = _buffer[1][window_index..window_index + grain_length].to_vec();
 
                             match _grains_properties.window_function {
                                 GWFunction::SmoothstepRegular { slope } => {
@@ -297,6 +965,10 @@ pub mod core {
                                         _synth_configuration,
                                     );
                                 }
+                                GWFunction::Symmetric => {
+                                    self.symmetric_window(&mut grain_left);
+                                    self.symmetric_window(&mut grain_rigth);
+                                }
                             };
 
                             let mut grain_output = AudioBuffer::Stereo([grain_left, grain_rigth]);
@@ -305,7 +977,12 @@ pub mod core {
                                 grain_output.normalize(1.0);
                             }
 
-                            return grain_output;
+                            return Grain {
+                                buffer: grain_output,
+                                source_offset_s: window_index as f64 * _pitch / engine_sampling_rate,
+                                pitch: _pitch,
+                                source: _source,
+                            };
                         }
                     }
                 }
@@ -322,13 +999,14 @@ pub mod core {
 
                     match _sample {
                         AudioBuffer::Mono(_buffer) => {
-                            let mut windows = _buffer[0].windows(grain_length);
-
-                            let windows_number = windows.len();
-                            let nth_distr = Uniform::new(0, windows_number);
-                            let window_index = nth_distr.sample(&mut self.randomness_source);
+                            let window_index = self.aligned_window(
+                                _resampled_source,
+                                &_grains_properties.grains_alignment,
+                                grain_length,
+                                _synth_configuration,
+                            );
 
-                            let mut grain = windows.nth(window_index).unwrap().to_vec();
+                            let mut grain = _buffer[0][window_index..window_index + grain_length].to_vec();
 
                             match _grains_properties.window_function {
                                 GWFunction::SmoothstepRegular { slope } => {
@@ -349,6 +1027,9 @@ pub mod core {
                                         _synth_configuration,
                                     );
                                 }
+                                GWFunction::Symmetric => {
+                                    self.symmetric_window(&mut grain);
+                                }
                             }
 
                             let mut grain_output = AudioBuffer::Mono([grain]);
@@ -357,18 +1038,23 @@ pub mod core {
                                 grain_output.normalize(1.0);
                             }
 
-                            return grain_output;
+                            return Grain {
+                                buffer: grain_output,
+                                source_offset_s: window_index as f64 * _pitch / engine_sampling_rate,
+                                pitch: _pitch,
+                                source: _source,
+                            };
                         }
                         AudioBuffer::Stereo(_buffer) => {
-                            let mut windows_left = _buffer[0].windows(grain_length);
-                            let mut windows_rigth = _buffer[1].windows(grain_length);
-
-                            let windows_number = windows_left.len();
-                            let nth_distr = Uniform::new(0, windows_number);
-                            let window_index = nth_distr.sample(&mut self.randomness_source);
+                            let window_index = self.aligned_window(
+                                _resampled_source,
+                                &_grains_properties.grains_alignment,
+                                grain_length,
+                                _synth_configuration,
+                            );
 
-                            let mut grain_left = windows_left.nth(window_index).unwrap().to_vec();
-                            let mut grain_rigth = windows_rigth.nth(window_index).unwrap().to_vec();
+                            let mut grain_left = _buffer[0][window_index..window_index + grain_length].to_vec();
+                            let mut grain_rigth = _buffer[1][window_index..window_index + grain_length].to_vec();
 
                             match _grains_properties.window_function {
                                 GWFunction::SmoothstepRegular { slope } => {
@@ -400,6 +1086,10 @@ pub mod core {
                                         _synth_configuration,
                                     );
                                 }
+                                GWFunction::Symmetric => {
+                                    self.symmetric_window(&mut grain_left);
+                                    self.symmetric_window(&mut grain_rigth);
+                                }
                             };
 
                             let mut grain_output = AudioBuffer::Stereo([grain_left, grain_rigth]);
@@ -408,7 +1098,12 @@ pub mod core {
                                 grain_output.normalize(1.0);
                             }
 
-                            return grain_output;
+                            return Grain {
+                                buffer: grain_output,
+                                source_offset_s: window_index as f64 * _pitch / engine_sampling_rate,
+                                pitch: _pitch,
+                                source: _source,
+                            };
                         }
                     }
                 }
@@ -417,6 +1112,47 @@ pub mod core {
 
         // ------------------------------------------------------------------------------------------------------------------------------------------
 
+        // Nałożenie okna czasowego na kanał granulki.
+        fn apply_window_function(
+            &self,
+            _buffer: &mut Vec<f64>,
+            _window_function: &GWFunction,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            match _window_function {
+                GWFunction::SmoothstepRegular { slope } => {
+                    self.smoothsteep_regular(_buffer, *slope, _synth_configuration);
+                }
+                GWFunction::SmoothstepUnregular {
+                    slope_attack,
+                    slope_release,
+                } => {
+                    self.smoothsteep_unregular(
+                        _buffer,
+                        *slope_attack,
+                        *slope_release,
+                        _synth_configuration,
+                    );
+                }
+                GWFunction::Symmetric => {
+                    self.symmetric_window(_buffer);
+                }
+            }
+        }
+
+        // Funkcja okna czasowego smoothstep w wersji symetrycznej: narastanie przez pierwszą i opadanie przez drugą
+        // połowę granulki.
+        fn symmetric_window(&self, _buffer: &mut Vec<f64>) {
+            let half_length: f64 = _buffer.len() as f64 / 2.0;
+
+            for (_index, _sample_value) in _buffer.iter_mut().enumerate() {
+                let position: f64 = _index as f64 / half_length;
+                let phase: f64 = if position < 1.0 { position } else { 2.0 - position };
+
+                *_sample_value *= 6.0 * phase.powi(5) - 15.0 * phase.powi(4) + 10.0 * phase.powi(3);
+            }
+        }
+
         // Funkcja okna czasowego smoothstep w wersji regularnej.
         fn smoothsteep_regular(
             &self,
@@ -433,10 +1169,9 @@ pub mod core {
                     break;
                 }
 
-                *_sample_value = *_sample_value
-                    * (6.0 * (curvature * _index as f64).powi(5)
-                        - 15.0 * (curvature * _index as f64).powi(4)
-                        + 10.0 * (curvature * _index as f64).powi(3));
+                *_sample_value *= 6.0 * (curvature * _index as f64).powi(5)
+                    - 15.0 * (curvature * _index as f64).powi(4)
+                    + 10.0 * (curvature * _index as f64).powi(3);
             }
 
             for (_index, _sample_value) in _buffer.iter_mut().rev().enumerate() {
@@ -444,10 +1179,9 @@ pub mod core {
                     break;
                 }
 
-                *_sample_value = *_sample_value
-                    * (6.0 * (curvature * _index as f64).powi(5)
-                        - 15.0 * (curvature * _index as f64).powi(4)
-                        + 10.0 * (curvature * _index as f64).powi(3));
+                *_sample_value *= 6.0 * (curvature * _index as f64).powi(5)
+                    - 15.0 * (curvature * _index as f64).powi(4)
+                    + 10.0 * (curvature * _index as f64).powi(3);
             }
         }
 
@@ -472,10 +1206,9 @@ pub mod core {
                     break;
                 }
 
-                *_sample_value = *_sample_value
-                    * (6.0 * (curvature_attack * _index as f64).powi(5)
-                        - 15.0 * (curvature_attack * _index as f64).powi(4)
-                        + 10.0 * (curvature_attack * _index as f64).powi(3));
+                *_sample_value *= 6.0 * (curvature_attack * _index as f64).powi(5)
+                    - 15.0 * (curvature_attack * _index as f64).powi(4)
+                    + 10.0 * (curvature_attack * _index as f64).powi(3);
             }
 
             for (_index, _sample_value) in _buffer.iter_mut().rev().enumerate() {
@@ -483,11 +1216,60 @@ pub mod core {
                     break;
                 }
 
-                *_sample_value = *_sample_value
-                    * (6.0 * (curvature_release * _index as f64).powi(5)
-                        - 15.0 * (curvature_release * _index as f64).powi(4)
-                        + 10.0 * (curvature_release * _index as f64).powi(3));
+                *_sample_value *= 6.0 * (curvature_release * _index as f64).powi(5)
+                    - 15.0 * (curvature_release * _index as f64).powi(4)
+                    + 10.0 * (curvature_release * _index as f64).powi(3);
             }
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use serde_json::json;
+
+        use super::*;
+
+        fn sine(_frequency: f64, _length: usize) -> AudioBuffer {
+            return AudioBuffer::Mono([(0.._length)
+                .map(|_index| (_index as f64 / 48_000.0 * _frequency * std::f64::consts::TAU).sin())
+                .collect()]);
+        }
+
+        fn synth_configuration() -> SynthConfiguration {
+            return serde_json::from_value(json!({ "engine_sampling_rate": 48_000 })).unwrap();
+        }
+
+        #[test]
+        fn transpose_grain_shifts_frequency_and_length() {
+            let transposed = Sampler::transpose_grain(&sine(1000.0, 4801), 2.0, &synth_configuration());
+
+            let samples = match &transposed {
+                AudioBuffer::Mono(_buffer) => _buffer[0].clone(),
+                AudioBuffer::Stereo(_) => panic!(),
+            };
+            let zero_crossings = samples[200..2200]
+                .windows(2)
+                .filter(|_pair| (_pair[0] < 0.0) != (_pair[1] < 0.0))
+                .count();
+
+            assert_eq!(samples.len(), 2401);
+            // 2000 próbek sinusa 2 kHz to 83.3 okresu, czyli około 167 przejść przez zero.
+            assert!((166..=168).contains(&zero_crossings));
+            assert!((transposed.peak() - 1.0).abs() < 0.02);
+        }
+
+        #[test]
+        fn transpose_grain_suppresses_aliasing() {
+            // Sinus 15 kHz transponowany o oktawę w górę przekracza częstotliwość Nyquista (24 kHz).
+            let transposed = Sampler::transpose_grain(&sine(15_000.0, 4800), 2.0, &synth_configuration());
+
+            let samples = match &transposed {
+                AudioBuffer::Mono(_buffer) => _buffer[0].clone(),
+                AudioBuffer::Stereo(_) => panic!(),
+            };
+            let rms = (samples[200..2200].iter().map(|_sample| _sample * _sample).sum::<f64>() / 2000.0).sqrt();
+
+            assert!(rms < 0.01);
+        }
+    }
 }
@@ -1,19 +1,104 @@
 pub mod core {
-    use rand::distributions::{Distribution, Uniform};
-    use rand::prelude::{SliceRandom, ThreadRng};
-    use rand::thread_rng;
-    use serde::Deserialize;
+    use std::f64::consts::PI;
 
-    use crate::granular_synth_config::tools::{BeatConfiguration, SynthConfiguration};
+    use rand::distributions::{Distribution, Uniform, WeightedIndex};
+    use rand::prelude::{Rng, SeedableRng, SliceRandom, StdRng};
+    use serde::{Deserialize, Serialize};
+
+    use crate::audio::tools::AudioBuffer;
+    use crate::descriptors::core::{MosaicTarget, PitchMarks};
+    use crate::events_export::tools::EventList;
+    use crate::granular_synth_config::tools::{
+        BeatConfiguration, Deviation, DeviationDistribution, GuideEnvelope, MarkovChain,
+        MidiSequence, Mosaic, PitchSynchronous, SynthConfiguration, Transition, GRAINS_PITCH_RANGE,
+        HUMANIZATION_PERCENTS_RANGE, MARKOV_BEATS_RANGE, PERCENTAGE_RANGE,
+    };
+    use crate::midi::core::MidiNotes;
+    use crate::timeline::core::Timeline;
 
     // ------------------------------------------------------------------------------------------------------------------------------------------
 
-    // Reprezentacja pojedynczego zdarzenia w sekwencji.
+    // Granulka odtworzona przez zdarzenie przed transpozycją o wysokość zdarzenia: położenie początku w samplu
+    // (w sekundach), długość (w próbkach), wysokość dźwięku granulki i indeks sampla źródłowego, a także długość
+    // granulki po transpozycji (w próbkach, znana dopiero po syntezie).
+    #[derive(Debug, Clone, Serialize)]
+    pub struct EventGrain {
+        pub source_offset_s: f64,
+        pub length: usize,
+        pub pitch: f64,
+        pub source: usize,
+        pub transposed_length: usize,
+    }
+
+    // Reprezentacja pojedynczego zdarzenia w sekwencji, granulka zdarzenia jest znana dopiero po syntezie.
+    // Wysokość dźwięku 'pitch' i długość 'length' są mnożnikami wysokości i długości granulki.
+    // Zdarzenia mozaiki wskazują ramkę dźwięku docelowego, do której dopasowywana jest granulka, a zdarzenia syntezy
+    // synchronicznej z okresem - znacznik okresu sampla, wokół którego wycięto granulkę.
     #[derive(Debug)]
     pub struct Event {
         pub start_index: usize,
         pub panorama: f64,
         pub volume: f64,
+        pub pitch: f64,
+        pub length: f64,
+        pub grain: Option<EventGrain>,
+        pub frame: Option<usize>,
+        pub pitch_mark: Option<usize>,
+    }
+
+    // Obwiednia amplitudy pliku 'guide_file' (wartości RMS kolejnych okien analizy znormalizowane do wartości
+    // największej) wraz z głębokością modulacji gęstości i głośności zdarzeń.
+    #[derive(Debug)]
+    pub struct Envelope {
+        values: Vec<f64>,
+        window_s: f64,
+        density: f64,
+        volume: f64,
+    }
+
+    impl Envelope {
+        // Wczytuje plik dźwiękowy i wyznacza jego obwiednię amplitudy.
+        pub fn load(_guide_file: &String, _guide_envelope: &GuideEnvelope) -> Result<Envelope, String> {
+            let (audio_buffer, sampling_rate) = match AudioBuffer::load_audio(_guide_file) {
+                Ok(_audio) => _audio,
+                Err(_error) => {
+                    return Err(_error);
+                }
+            };
+
+            let window_length =
+                ((_guide_envelope.window_ms / 1000.0) * sampling_rate as f64).round() as usize;
+            let mut values = audio_buffer.envelope(window_length);
+            let peak = values.iter().fold(0.0, |_peak: f64, _value| _peak.max(*_value));
+
+            if values.is_empty() == true || peak <= 0.0 {
+                return Err(format!(
+                    "Błąd danych ->\n\tplik: \'{}\' nie zawiera sygnału, na podstawie którego można wyznaczyć obwiednię :/",
+                    _guide_file
+                ));
+            }
+
+            values.iter_mut().for_each(|_value| *_value /= peak);
+
+            return Ok(Envelope {
+                values,
+                window_s: window_length.max(1) as f64 / sampling_rate as f64,
+                density: _guide_envelope.density_percents / 100.0,
+                volume: _guide_envelope.volume_percents / 100.0,
+            });
+        }
+
+        // Wartość obwiedni w podanej chwili (liczonej od początku pliku, plik jest zapętlany), interpolowana liniowo
+        // pomiędzy środkami okien analizy.
+        pub fn value_at(&self, _time_s: f64) -> f64 {
+            let windows_count = self.values.len();
+            let position = (_time_s / self.window_s - 0.5).rem_euclid(windows_count as f64);
+            let index = position.floor() as usize % windows_count;
+            let fraction = position - position.floor();
+
+            return self.values[index] * (1.0 - fraction)
+                + self.values[(index + 1) % windows_count] * fraction;
+        }
     }
 
     // Struktura reprezentująca sekwencer.
@@ -22,8 +107,12 @@ pub mod core {
         #[serde(skip_deserializing)]
         pub sequence: Vec<Event>,
 
+        // Długość syntezy sekwencji (w próbkach).
         #[serde(skip_deserializing)]
-        randomness_source: ThreadRng,
+        pub length: usize,
+
+        #[serde(skip_deserializing, default = "StdRng::from_entropy")]
+        randomness_source: StdRng,
     }
 
     impl Sequencer {
@@ -31,46 +120,69 @@ pub mod core {
         pub fn default() -> Self {
             return Sequencer {
                 sequence: Vec::with_capacity(2048),
-                randomness_source: thread_rng(),
+                length: 0,
+                randomness_source: StdRng::from_entropy(),
             };
         }
 
+        // Ustawia ziarno generatora liczb losowych, pozwalając odtworzyć wygenerowaną sekwencję.
+        pub fn seed(&mut self, _seed: u64) {
+            self.randomness_source = StdRng::seed_from_u64(_seed);
+        }
+
         // Generuje sekwencję wystąpień granulek w funkcji czasu.
+        // Położenia sub-beatów są wyznaczane w beatach i przeliczane na indeksy próbek zgodnie z mapą tempa.
+        // Obwiednia '_guide' (rozpoczynająca się wraz z pierwszym beatem sekwencji) moduluje gęstość i głośność zdarzeń.
         pub fn generate_sequence(
             &mut self,
             _beat_sequence: &Vec<BeatConfiguration>,
             _synth_configuration: &SynthConfiguration,
+            _guide: Option<&Envelope>,
         ) {
-            let beat_length: usize = (_synth_configuration.engine_sampling_rate as f64
-                * (_synth_configuration.beat_length_ms / 1000.0))
-                .round() as usize;
+            let timeline = Timeline::new(_synth_configuration);
+            let beat_spans = timeline.beat_spans(_beat_sequence);
+
+            self.sequence.clear();
 
             // Pętla po każdym beacie w sekwencji.
-            for (_beat_number, _beat) in _beat_sequence.iter().enumerate() {
-                let sub_beat_length: f64 = beat_length as f64 / _beat.subdivisions as f64;
-                let mut sub_coordinates: Vec<usize> = Vec::with_capacity(_beat.subdivisions);
+            for (_beat_number, (_beat, (_beat_start, _beat_length))) in
+                _beat_sequence.iter().zip(beat_spans.iter()).enumerate()
+            {
+                // Beat przechodzący płynnie w kolejny beat sekwencji.
+                if let (Transition::Linear | Transition::Exponential, Some(_next_beat)) =
+                    (_beat.transition, _beat_sequence.get(_beat_number + 1))
+                {
+                    self.generate_transition(_beat, _next_beat, *_beat_start, *_beat_length, &timeline);
+                    continue;
+                }
 
-                // Określenie położeń sub-beatów z i bez humanizacji.
-                if _beat.humanization_percents != 0.0 {
-                    let max_humanization: f64 =
-                        sub_beat_length * (_beat.humanization_percents / 100.0);
-                    let humanization_distr =
-                        Uniform::<f64>::new(-max_humanization, max_humanization);
+                let sub_beat_length: f64 = _beat_length / _beat.subdivisions as f64;
+                let mut sub_coordinates: Vec<(usize, f64)> = Vec::with_capacity(_beat.subdivisions);
 
+                // Określenie położeń sub-beatów (z uwzględnieniem swingu i szablonu groove) z i bez humanizacji.
+                if _beat.humanization_percents.is_zero() == false {
                     for _index in 0.._beat.subdivisions {
-                        let offset = humanization_distr.sample(&mut self.randomness_source);
-                        let index: usize = (sub_beat_length * _index as f64 + offset).round()
-                            as usize
-                            + (beat_length * (_beat_number + 1));
+                        let (shift, gain) = groove_offset(_beat, _index);
+                        let offset = sub_beat_length
+                            * sample_deviation(
+                                &_beat.humanization_percents,
+                                HUMANIZATION_PERCENTS_RANGE.1,
+                                &mut self.randomness_source,
+                            )
+                            / 100.0;
+                        let index: usize = timeline.sample_index(
+                            _beat_start + sub_beat_length * (_index as f64 + shift) + offset,
+                        );
 
-                        sub_coordinates.push(index);
+                        sub_coordinates.push((index, gain));
                     }
                 } else {
                     for _index in 0.._beat.subdivisions {
-                        let index: usize = (sub_beat_length * _index as f64).round() as usize
-                            + (beat_length * (_beat_number + 1));
+                        let (shift, gain) = groove_offset(_beat, _index);
+                        let index: usize = timeline
+                            .sample_index(_beat_start + sub_beat_length * (_index as f64 + shift));
 
-                        sub_coordinates.push(index);
+                        sub_coordinates.push((index, gain));
                     }
                 }
 
@@ -78,29 +190,29 @@ pub mod core {
                 let chosen_sub_beats_number: usize = (_beat.subdivisions as f64
                     * (_beat.coverage_percentage / 100.0))
                     .round() as usize;
-                let chosen_sub_beats: Vec<usize> = sub_coordinates
+                let chosen_sub_beats: Vec<(usize, f64)> = sub_coordinates
                     .choose_multiple(&mut self.randomness_source, chosen_sub_beats_number)
                     .cloned()
                     .collect();
 
                 // Pętla po każdym wybranym sub-beacie, wyznaczneie panoramy i wzmocnienia.
-                for _sub_beat in chosen_sub_beats.iter() {
-                    let panorama_value: f64 = if _beat.panorama_deviation_percents != 0.0 {
-                        let panorama_max_dev = _beat.panorama_deviation_percents / 100.0;
-                        let panormama_distr =
-                            Uniform::<f64>::new(-panorama_max_dev, panorama_max_dev);
-
-                        panormama_distr.sample(&mut self.randomness_source)
+                for (_sub_beat, _gain) in chosen_sub_beats.iter() {
+                    let panorama_value: f64 = if _beat.panorama_deviation_percents.is_zero() == false {
+                        sample_deviation(
+                            &_beat.panorama_deviation_percents,
+                            PERCENTAGE_RANGE.1,
+                            &mut self.randomness_source,
+                        ) / 100.0
                     } else {
                         0.0
                     };
 
-                    let volume_value: f64 = if _beat.volume_deviation_percents != 0.0 {
-                        let volume_max_dev = _beat.volume_deviation_percents / 100.0;
-                        let volume_distr =
-                            Uniform::<f64>::new(1.0 - volume_max_dev, 1.0 + volume_max_dev);
-
-                        volume_distr.sample(&mut self.randomness_source)
+                    let volume_value: f64 = if _beat.volume_deviation_percents.is_zero() == false {
+                        1.0 + sample_deviation(
+                            &_beat.volume_deviation_percents,
+                            PERCENTAGE_RANGE.1,
+                            &mut self.randomness_source,
+                        ) / 100.0
                     } else {
                         1.0
                     };
@@ -108,14 +220,749 @@ pub mod core {
                     self.sequence.push(Event {
                         start_index: *_sub_beat,
                         panorama: panorama_value,
-                        volume: volume_value,
+                        volume: volume_value * _gain,
+                        pitch: _beat.grains_pitch_multiplier,
+                        length: _beat.grains_length_multiplier,
+                        grain: None,
+                        frame: None,
+                        pitch_mark: None,
                     })
                 }
             }
 
+            if let Some(_guide) = _guide {
+                self.apply_guide(_guide, &timeline, _synth_configuration);
+            }
+
             // Sortowanie zdarzeń zgodnie z roznącym indeksem.
             self.sequence
                 .sort_by(|a, b| a.start_index.cmp(&b.start_index));
+
+            self.length = timeline.render_length(_beat_sequence);
+        }
+
+        // Modulacja sekwencji obwiednią: zdarzenie pozostaje w sekwencji z prawdopodobieństwem 1 - d + d * e(t),
+        // a jego głośność jest mnożona przez 1 - v + v * e(t), gdzie e(t) to wartość obwiedni, d i v - głębokość modulacji.
+        fn apply_guide(
+            &mut self,
+            _guide: &Envelope,
+            _timeline: &Timeline,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+            let guide_start_s = _timeline.time_s(_timeline.sequence_start());
+            let sequence = std::mem::take(&mut self.sequence);
+
+            for mut event in sequence.into_iter() {
+                let envelope_value = _guide.value_at(event.start_index as f64 / sampling_rate - guide_start_s);
+                let keep_probability = 1.0 - _guide.density + _guide.density * envelope_value;
+
+                if keep_probability < 1.0 && self.randomness_source.gen::<f64>() >= keep_probability {
+                    continue;
+                }
+
+                event.volume *= 1.0 - _guide.volume + _guide.volume * envelope_value;
+                self.sequence.push(event);
+            }
+        }
+
+        // Generuje sekwencję na podstawie nut pliku MIDI: prędkość nuty wyznacza głośność, kontroler panoramy - panoramę,
+        // a odległość nuty od 'root_note' (w półtonach) - wysokość dźwięku granulki. Sekwencja nie zawiera losowości,
+        // początek pliku MIDI odpowiada początkowi ścieżki, po ostatnim zdarzeniu pliku dodawany jest jeden beat ciszy.
+        pub fn generate_midi_sequence(
+            &mut self,
+            _midi_notes: &MidiNotes,
+            _midi_sequence: &MidiSequence,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+
+            self.sequence.clear();
+
+            for note in _midi_notes.notes.iter() {
+                let pitch: f64 = if _midi_sequence.follow_pitch == true {
+                    2f64.powf((note.key as f64 - _midi_sequence.root_note as f64) / 12.0)
+                        .max(GRAINS_PITCH_RANGE.0)
+                        .min(GRAINS_PITCH_RANGE.1)
+                } else {
+                    1.0
+                };
+
+                self.sequence.push(Event {
+                    start_index: (note.time_s * sampling_rate).round() as usize,
+                    panorama: note.panorama,
+                    volume: note.velocity as f64 / 127.0,
+                    pitch,
+                    length: 1.0,
+                    grain: None,
+                    frame: None,
+                    pitch_mark: None,
+                });
+            }
+
+            self.sequence.sort_by_key(|_event| _event.start_index);
+
+            self.length = ((_midi_notes.length_s + _synth_configuration.beat_length_ms / 1000.0)
+                * sampling_rate)
+                .round() as usize;
+        }
+
+        // Odtwarza zapisany wykaz zdarzeń: zdarzenia otrzymują granulki o zapisanym położeniu w samplu, długości
+        // i wysokości dźwięku oraz zapisaną wysokość zdarzenia, czasy są przeliczane na indeksy próbek bieżącej
+        // częstotliwości próbkowania silnika.
+        // Długość syntezy pochodzi z wykazu, a gdy jej brak - kończy się wraz z ostatnią granulką.
+        pub fn replay_sequence(
+            &mut self,
+            _event_list: &EventList,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+
+            self.sequence.clear();
+
+            let mut length: usize = 0;
+
+            for recorded_event in _event_list.events.iter() {
+                let start_index = (recorded_event.start_s * sampling_rate).round() as usize;
+                let transposed_length = (recorded_event.length_s * sampling_rate).round() as usize;
+
+                // Wykaz bez granulki sprzed transpozycji odtwarza granulkę o wysokości i długości zdarzenia.
+                let (grain_pitch, grain_length_s, event_pitch) = match (
+                    recorded_event.grain_pitch,
+                    recorded_event.grain_length_s,
+                    recorded_event.event_pitch,
+                ) {
+                    (Some(_grain_pitch), Some(_grain_length_s), Some(_event_pitch)) => {
+                        (_grain_pitch, _grain_length_s, _event_pitch)
+                    }
+                    _ => (recorded_event.pitch, recorded_event.length_s, 1.0),
+                };
+
+                length = length.max(start_index + transposed_length);
+
+                self.sequence.push(Event {
+                    start_index,
+                    panorama: recorded_event.panorama,
+                    volume: recorded_event.volume,
+                    pitch: event_pitch,
+                    length: 1.0,
+                    grain: Some(EventGrain {
+                        source_offset_s: recorded_event.source_offset_s,
+                        length: (grain_length_s * sampling_rate).round() as usize,
+                        pitch: grain_pitch,
+                        source: recorded_event.source,
+                        transposed_length,
+                    }),
+                    frame: None,
+                    pitch_mark: None,
+                });
+            }
+
+            self.sequence.sort_by_key(|_event| _event.start_index);
+
+            self.length = match _event_list.length_s {
+                Some(_length_s) => length.max((_length_s * sampling_rate).round() as usize),
+                None => length,
+            };
+        }
+
+        // Generuje sekwencję mozaiki: jedno zdarzenie na początku każdej ramki dźwięku docelowego, której głośność
+        // nie jest niższa od głośności najgłośniejszej ramki o więcej niż 'silence_db'. Sekwencja nie zawiera losowości,
+        // początek dźwięku docelowego odpowiada początkowi ścieżki, po jego końcu dodawany jest jeden beat ciszy.
+        pub fn generate_mosaic_sequence(
+            &mut self,
+            _mosaic_target: &MosaicTarget,
+            _mosaic: &Mosaic,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+            let loudest_frame = _mosaic_target
+                .frames
+                .iter()
+                .fold(0.0, |_loudest: f64, _frame| _loudest.max(_frame.rms));
+            let silence_threshold = loudest_frame * 10f64.powf(_mosaic.silence_db / 20.0);
+
+            self.sequence.clear();
+
+            for (frame_index, frame) in _mosaic_target.frames.iter().enumerate() {
+                if frame.rms <= 0.0 || frame.rms < silence_threshold {
+                    continue;
+                }
+
+                self.sequence.push(Event {
+                    start_index: (frame_index as f64 * _mosaic_target.hop_s * sampling_rate).round() as usize,
+                    panorama: 0.0,
+                    volume: 1.0,
+                    pitch: 1.0,
+                    length: 1.0,
+                    grain: None,
+                    frame: Some(frame_index),
+                    pitch_mark: None,
+                });
+            }
+
+            self.length = ((_mosaic_target.length_s + _synth_configuration.beat_length_ms / 1000.0)
+                * sampling_rate)
+                .round() as usize;
+        }
+
+        // Generuje zdarzenia syntezy synchronicznej z okresem. Położenie odczytu sampla przesuwa się po kolejnych
+        // znacznikach (każdy obejmuje swój okres), a zdarzenie wskazuje znacznik w bieżącym położeniu odczytu
+        // i rozpoczyna się tak, aby środek granulki (znacznik) przypadał w miejscu zdarzenia.
+        // Kolejne zdarzenia są oddalone o okres znacznika podzielony przez 'pitch_shift' (we fragmentach dźwięcznych),
+        // a położenie odczytu przesuwa się o ten odstęp podzielony przez 'time_stretch'.
+        pub fn generate_pitch_synchronous_sequence(
+            &mut self,
+            _pitch_marks: &PitchMarks,
+            _pitch_synchronous: &PitchSynchronous,
+            _synth_configuration: &SynthConfiguration,
+        ) {
+            let sampling_rate = _synth_configuration.engine_sampling_rate as f64;
+            let marks = &_pitch_marks.marks;
+
+            // Położenia początków okresów znaczników w czasie odczytu sampla.
+            let mut mark_starts: Vec<f64> = Vec::with_capacity(marks.len());
+            let mut read_length: f64 = 0.0;
+
+            for mark in marks.iter() {
+                mark_starts.push(read_length);
+                read_length += mark.period_s;
+            }
+
+            self.sequence.clear();
+
+            let half_grain = |_period_s: f64| -> f64 { 0.5 * _pitch_synchronous.periods as f64 * _period_s };
+
+            let mut time: f64 = marks.first().map(|_mark| half_grain(_mark.period_s)).unwrap_or(0.0);
+            let mut read_position: f64 = 0.0;
+            let mut longest_grain: f64 = 0.0;
+
+            while read_position < read_length {
+                let mark_index = mark_starts.partition_point(|_start| *_start <= read_position) - 1;
+                let mark = &marks[mark_index];
+                let step = if mark.voiced == true {
+                    mark.period_s / _pitch_synchronous.pitch_shift
+                } else {
+                    mark.period_s
+                };
+
+                self.sequence.push(Event {
+                    start_index: ((time - half_grain(mark.period_s)).max(0.0) * sampling_rate).round() as usize,
+                    panorama: 0.0,
+                    volume: 1.0,
+                    pitch: 1.0,
+                    length: 1.0,
+                    grain: None,
+                    frame: None,
+                    pitch_mark: Some(mark_index),
+                });
+
+                longest_grain = longest_grain.max(half_grain(mark.period_s));
+                time += step;
+                read_position += step / _pitch_synchronous.time_stretch;
+            }
+
+            self.length = ((time + longest_grain) * sampling_rate).round() as usize;
+        }
+
+        // Generuje zdarzenia beatu, którego parametry zmieniają się w sposób ciągły w stronę parametrów kolejnego beatu.
+        // Gęstość sub-beatów przechodzi od 'subdivisions' beatu do 'subdivisions' kolejnego beatu, każdy sub-beat jest
+        // wybierany z prawdopodobieństwem równym pokryciu w jego położeniu, odchylenia są losowane z rozkładów obu beatów.
+        fn generate_transition(
+            &mut self,
+            _beat: &BeatConfiguration,
+            _next_beat: &BeatConfiguration,
+            _beat_start: f64,
+            _beat_length: f64,
+            _timeline: &Timeline,
+        ) {
+            let transition = _beat.transition;
+            let (from, to) = (_beat.subdivisions as f64, _next_beat.subdivisions as f64);
+            let sub_beats_number = sub_beats_integral(transition, from, to);
+            let mut index: usize = 0;
+
+            while (index as f64) < sub_beats_number - 1e-9 {
+                let position = sub_beat_position(transition, from, to, index as f64);
+                let density = transition.interpolate(from, to, position);
+                let sub_beat_length = if density > 0.0 {
+                    _beat_length / density
+                } else {
+                    _beat_length * (sub_beat_position(transition, from, to, index as f64 + 1.0) - position)
+                };
+                let (shift, gain) = groove_offset(_beat, index);
+
+                let offset = sub_beat_length
+                    * sample_transition_deviation(
+                        &_beat.humanization_percents,
+                        &_next_beat.humanization_percents,
+                        transition,
+                        position,
+                        HUMANIZATION_PERCENTS_RANGE.1,
+                        &mut self.randomness_source,
+                    )
+                    / 100.0;
+                let start_index = _timeline.sample_index(
+                    _beat_start + _beat_length * position + sub_beat_length * shift + offset,
+                );
+
+                index += 1;
+
+                let coverage_percentage = transition.interpolate(
+                    _beat.coverage_percentage,
+                    _next_beat.coverage_percentage,
+                    position,
+                );
+
+                if coverage_percentage < 100.0
+                    && self.randomness_source.gen::<f64>() * 100.0 >= coverage_percentage
+                {
+                    continue;
+                }
+
+                let panorama_value: f64 = sample_transition_deviation(
+                    &_beat.panorama_deviation_percents,
+                    &_next_beat.panorama_deviation_percents,
+                    transition,
+                    position,
+                    PERCENTAGE_RANGE.1,
+                    &mut self.randomness_source,
+                ) / 100.0;
+
+                let volume_value: f64 = 1.0
+                    + sample_transition_deviation(
+                        &_beat.volume_deviation_percents,
+                        &_next_beat.volume_deviation_percents,
+                        transition,
+                        position,
+                        PERCENTAGE_RANGE.1,
+                        &mut self.randomness_source,
+                    ) / 100.0;
+
+                self.sequence.push(Event {
+                    start_index,
+                    panorama: panorama_value,
+                    volume: volume_value * gain,
+                    pitch: transition.interpolate(
+                        _beat.grains_pitch_multiplier,
+                        _next_beat.grains_pitch_multiplier,
+                        position,
+                    ),
+                    length: transition.interpolate(
+                        _beat.grains_length_multiplier,
+                        _next_beat.grains_length_multiplier,
+                        position,
+                    ),
+                    grain: None,
+                    frame: None,
+                    pitch_mark: None,
+                })
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Generuje sekwencję beatów, przechodząc łańcuch Markowa, zwraca sekwencję i nazwy kolejno odwiedzonych stanów.
+    // Łańcuch korzysta z własnego generatora liczb losowych, więc to samo ziarno odtwarza tę samą ścieżkę.
+    pub fn walk_markov_chain(
+        _markov: &MarkovChain,
+        _synth_configuration: &SynthConfiguration,
+        _seed: u64,
+    ) -> (Vec<BeatConfiguration>, Vec<String>) {
+        let mut randomness_source = StdRng::seed_from_u64(_seed);
+        let timeline = Timeline::new(_synth_configuration);
+
+        let mut beat_sequence: Vec<BeatConfiguration> = Vec::new();
+        let mut path: Vec<String> = Vec::new();
+
+        let states: Vec<&String> = _markov.states.keys().collect();
+
+        if states.is_empty() == true {
+            return (beat_sequence, path);
+        }
+
+        let mut state: String = match &_markov.initial {
+            Some(_initial) => _initial.clone(),
+            None => states[randomness_source.gen_range(0..states.len())].clone(),
+        };
+
+        let mut position = timeline.sequence_start();
+        let end = match &_markov.duration {
+            Some(_duration) => position + timeline.span_length(&Some(*_duration), position),
+            None => f64::INFINITY,
+        };
+        let beats = _markov.beats.unwrap_or(MARKOV_BEATS_RANGE.1).min(MARKOV_BEATS_RANGE.1);
+
+        while beat_sequence.len() < beats && position < end - 1e-9 {
+            let beat = _markov.states[&state].clone();
+
+            position += timeline.span_length(&beat.duration, position);
+            beat_sequence.push(beat);
+            path.push(state.clone());
+
+            // Wybór kolejnego stanu zgodnie z wagami przejść ze stanu bieżącego.
+            let row: Vec<(&String, &f64)> = match _markov.transitions.get(&state) {
+                Some(_row) => _row.iter().collect(),
+                None => {
+                    break;
+                }
+            };
+
+            state = match WeightedIndex::new(row.iter().map(|(_, _probability)| **_probability)) {
+                Ok(_weighted_index) => row[_weighted_index.sample(&mut randomness_source)].0.clone(),
+                Err(_error) => {
+                    break;
+                }
+            };
+        }
+
+        return (beat_sequence, path);
+    }
+
+    // Przesunięcie sub-beatu (w długościach sub-beatu) i mnożnik jego głośności wynikające ze swingu i szablonu groove.
+    // Swing opóźnia parzyste sub-beaty (2., 4., ...), kroki szablonu groove są przypisywane sub-beatom cyklicznie.
+    fn groove_offset(_beat: &BeatConfiguration, _index: usize) -> (f64, f64) {
+        let mut shift: f64 = 0.0;
+        let mut gain: f64 = 1.0;
+
+        if _index % 2 == 1 {
+            shift += _beat.swing_percent / 100.0;
+        }
+
+        if let Some(_groove) = &_beat.groove {
+            if _groove.steps.is_empty() == false {
+                let step = &_groove.steps[_index % _groove.steps.len()];
+
+                shift += step.timing_percent / 100.0;
+                gain *= 1.0 + step.velocity_percent / 100.0;
+            }
+        }
+
+        return (shift, gain);
+    }
+
+    // Liczba sub-beatów beatu przy gęstości zmieniającej się od '_from' do '_to' sub-beatów na beat (całka gęstości).
+    fn sub_beats_integral(_transition: Transition, _from: f64, _to: f64) -> f64 {
+        if _transition == Transition::Exponential && _from > 0.0 && _to > 0.0 && _from != _to {
+            return (_to - _from) / (_to / _from).ln();
+        }
+
+        return (_from + _to) / 2.0;
+    }
+
+    // Położenie (0.0 ... 1.0) sub-beatu o indeksie '_index' przy gęstości zmieniającej się od '_from' do '_to'
+    // (odwrotność całki gęstości).
+    fn sub_beat_position(_transition: Transition, _from: f64, _to: f64, _index: f64) -> f64 {
+        if _transition == Transition::Exponential && _from > 0.0 && _to > 0.0 && _from != _to {
+            let rate = (_to / _from).ln();
+
+            return (1.0 + _index * rate / _from).ln() / rate;
+        }
+
+        if (_to - _from).abs() < 1e-12 {
+            return _index / _from;
+        }
+
+        return (-_from + (_from * _from + 2.0 * (_to - _from) * _index).sqrt()) / (_to - _from);
+    }
+
+    // Losuje odchylenie (w procentach) w położeniu '_position' przejścia między beatami.
+    // Dla dwóch wartości liczbowych interpolowana jest szerokość rozkładu jednostajnego,
+    // w pozostałych przypadkach odchylenie pochodzi z rozkładu kolejnego beatu z prawdopodobieństwem równym jego udziałowi.
+    fn sample_transition_deviation(
+        _from: &Deviation,
+        _to: &Deviation,
+        _transition: Transition,
+        _position: f64,
+        _limit: f64,
+        _randomness_source: &mut StdRng,
+    ) -> f64 {
+        match (_from, _to) {
+            (Deviation::Percents(_from_percents), Deviation::Percents(_to_percents)) => {
+                let percents = _transition.interpolate(*_from_percents, *_to_percents, _position);
+
+                return sample_deviation(&Deviation::Percents(percents), _limit, _randomness_source);
+            }
+            _ => {
+                if _randomness_source.gen::<f64>() < _transition.weight(_position) {
+                    return sample_deviation(_to, _limit, _randomness_source);
+                }
+
+                return sample_deviation(_from, _limit, _randomness_source);
+            }
+        }
+    }
+
+    // Losuje odchylenie (w procentach) zgodnie z rozkładem, wynik mieści się w zakresie +/- '_limit'.
+    fn sample_deviation(
+        _deviation: &Deviation,
+        _limit: f64,
+        _randomness_source: &mut StdRng,
+    ) -> f64 {
+        let percents: f64 = match _deviation {
+            Deviation::Percents(_percents)
+            | Deviation::Distribution(DeviationDistribution::Uniform {
+                percents: _percents,
+            }) => {
+                if *_percents == 0.0 {
+                    return 0.0;
+                }
+
+                Uniform::<f64>::new(-_percents, *_percents).sample(_randomness_source)
+            }
+            Deviation::Distribution(DeviationDistribution::Gaussian { sigma_percents }) => {
+                sigma_percents * sample_standard_normal(_randomness_source)
+            }
+            Deviation::Distribution(DeviationDistribution::Triangular {
+                percents,
+                mode_percents,
+            }) => {
+                if *percents == 0.0 {
+                    return 0.0;
+                }
+
+                // Odwrotność dystrybuanty rozkładu trójkątnego na przedziale [-percents, percents].
+                let (low, high) = (-percents, *percents);
+                let probability: f64 = _randomness_source.gen();
+
+                if probability < (mode_percents - low) / (high - low) {
+                    low + (probability * (high - low) * (mode_percents - low)).sqrt()
+                } else {
+                    high - ((1.0 - probability) * (high - low) * (high - mode_percents)).sqrt()
+                }
+            }
+            Deviation::Distribution(DeviationDistribution::Beta {
+                percents,
+                alpha,
+                beta,
+            }) => {
+                let x = sample_gamma(*alpha, _randomness_source);
+                let y = sample_gamma(*beta, _randomness_source);
+
+                if x + y > 0.0 {
+                    percents * (2.0 * x / (x + y) - 1.0)
+                } else {
+                    0.0
+                }
+            }
+            Deviation::Distribution(DeviationDistribution::Discrete { values }) => {
+                match WeightedIndex::new(values.iter().map(|(_, _weight)| *_weight)) {
+                    Ok(_weighted_index) => values[_weighted_index.sample(_randomness_source)].0,
+                    Err(_error) => 0.0,
+                }
+            }
+        };
+
+        return percents.max(-_limit).min(_limit);
+    }
+
+    // Losuje wartość z rozkładu normalnego N(0, 1) (transformacja Boxa-Mullera).
+    fn sample_standard_normal(_randomness_source: &mut StdRng) -> f64 {
+        let u1: f64 = 1.0 - _randomness_source.gen::<f64>();
+        let u2: f64 = _randomness_source.gen();
+
+        return (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+    }
+
+    // Losuje wartość z rozkładu gamma o podanym kształcie i skali 1.0 (metoda Marsaglii-Tsanga).
+    fn sample_gamma(_shape: f64, _randomness_source: &mut StdRng) -> f64 {
+        if _shape < 1.0 {
+            let u: f64 = _randomness_source.gen();
+
+            return sample_gamma(_shape + 1.0, _randomness_source) * u.powf(1.0 / _shape);
+        }
+
+        let d = _shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+
+        loop {
+            let x = sample_standard_normal(_randomness_source);
+            let v = (1.0 + c * x).powi(3);
+
+            if v <= 0.0 {
+                continue;
+            }
+
+            let u: f64 = 1.0 - _randomness_source.gen::<f64>();
+
+            if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+                return d * v;
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use serde_json::json;
+
+        use super::*;
+
+        const SAMPLES: usize = 20_000;
+
+        fn deviation(_deviation: serde_json::Value) -> Deviation {
+            return serde_json::from_value(_deviation).unwrap();
+        }
+
+        fn samples(_deviation: &Deviation, _limit: f64, _seed: u64) -> Vec<f64> {
+            let mut randomness_source = StdRng::seed_from_u64(_seed);
+
+            return (0..SAMPLES)
+                .map(|_| sample_deviation(_deviation, _limit, &mut randomness_source))
+                .collect();
+        }
+
+        fn mean(_values: &Vec<f64>) -> f64 {
+            return _values.iter().sum::<f64>() / _values.len() as f64;
+        }
+
+        #[test]
+        fn gaussian_deviation_is_clipped_to_limit() {
+            let values = samples(&deviation(json!({ "type": "Gaussian", "sigma_percents": 50.0 })), 20.0, 1);
+
+            assert!(values.iter().all(|_value| _value.abs() <= 20.0));
+            // P(|x| > 20) dla sigma 50 wynosi około 0.69 - te wartości są przycinane do granic zakresu.
+            let clipped = values.iter().filter(|_value| _value.abs() == 20.0).count() as f64 / SAMPLES as f64;
+            assert!((clipped - 0.69).abs() < 0.02);
+
+            let values = samples(&deviation(json!({ "type": "Gaussian", "sigma_percents": 10.0 })), 100.0, 2);
+            let sigma = (values.iter().map(|_value| _value * _value).sum::<f64>() / SAMPLES as f64).sqrt();
+
+            assert!(mean(&values).abs() < 0.3);
+            assert!((sigma - 10.0).abs() < 0.3);
+        }
+
+        #[test]
+        fn beta_deviation_follows_gamma_ratio() {
+            // x / (x + y) ma rozkład Beta(2, 5) o średniej 2/7, odchylenie: 40 * (2 * 2/7 - 1).
+            let values = samples(
+                &deviation(json!({ "type": "Beta", "percents": 40.0, "alpha": 2.0, "beta": 5.0 })),
+                100.0,
+                3,
+            );
+
+            assert!(values.iter().all(|_value| _value.abs() <= 40.0));
+            assert!((mean(&values) - 40.0 * (4.0 / 7.0 - 1.0)).abs() < 0.5);
+
+            // Kształt < 1.0 korzysta z przekształcenia rozkładu gamma o kształcie + 1.
+            let values = samples(
+                &deviation(json!({ "type": "Beta", "percents": 30.0, "alpha": 0.5, "beta": 0.5 })),
+                100.0,
+                4,
+            );
+
+            assert!(values.iter().all(|_value| _value.abs() <= 30.0));
+            assert!(mean(&values).abs() < 0.6);
+
+            let mut randomness_source = StdRng::seed_from_u64(5);
+
+            for _shape in [0.5, 1.0, 3.0].iter() {
+                let gamma: Vec<f64> = (0..SAMPLES)
+                    .map(|_| sample_gamma(*_shape, &mut randomness_source))
+                    .collect();
+
+                assert!(gamma.iter().all(|_value| *_value >= 0.0));
+                assert!((mean(&gamma) - _shape).abs() < 0.05 * _shape.max(1.0));
+            }
+        }
+
+        #[test]
+        fn discrete_deviation_picks_weighted_values() {
+            let values = samples(
+                &deviation(json!({ "type": "Discrete", "values": [[-10.0, 1.0], [5.0, 3.0], [50.0, 0.0]] })),
+                20.0,
+                6,
+            );
+
+            assert!(values.iter().all(|_value| *_value == -10.0 || *_value == 5.0));
+            let share = values.iter().filter(|_value| **_value == 5.0).count() as f64 / SAMPLES as f64;
+            assert!((share - 0.75).abs() < 0.015);
+
+            // Wartość spoza zakresu jest przycinana, a brak dodatnich wag oznacza brak odchylenia.
+            assert!(samples(&deviation(json!({ "type": "Discrete", "values": [[50.0, 1.0]] })), 20.0, 7)
+                .iter()
+                .all(|_value| *_value == 20.0));
+            assert!(samples(&deviation(json!({ "type": "Discrete", "values": [[5.0, 0.0]] })), 20.0, 8)
+                .iter()
+                .all(|_value| *_value == 0.0));
+        }
+
+        #[test]
+        fn same_seed_gives_same_deviations() {
+            let gaussian = deviation(json!({ "type": "Gaussian", "sigma_percents": 10.0 }));
+
+            assert_eq!(samples(&gaussian, 25.0, 9), samples(&gaussian, 25.0, 9));
+            assert_ne!(samples(&gaussian, 25.0, 9), samples(&gaussian, 25.0, 10));
+        }
+
+        fn markov_chain(_markov: serde_json::Value) -> MarkovChain {
+            return serde_json::from_value(_markov).unwrap();
+        }
+
+        fn walk(_markov: &MarkovChain, _seed: u64) -> (Vec<BeatConfiguration>, Vec<String>) {
+            let synth_configuration: SynthConfiguration = serde_json::from_value(json!({})).unwrap();
+
+            return walk_markov_chain(_markov, &synth_configuration, _seed);
+        }
+
+        #[test]
+        fn markov_walk_is_reproducible_and_stops_after_beats() {
+            let markov = markov_chain(json!({
+                "states": { "a": { "subdivisions": 1 }, "b": { "subdivisions": 2 }, "c": { "subdivisions": 3 } },
+                "transitions": { "a": { "b": 1.0 }, "b": { "a": 0.5, "c": 0.5 }, "c": { "a": 1.0, "c": 1.0 } },
+                "initial": "a",
+                "beats": 24
+            }));
+
+            let (beat_sequence, path) = walk(&markov, 11);
+
+            assert_eq!((beat_sequence.len(), path.len()), (24, 24));
+            assert_eq!((path[0].as_str(), path[1].as_str()), ("a", "b"));
+            assert!(beat_sequence
+                .iter()
+                .zip(path.iter())
+                .all(|(_beat, _state)| _beat.subdivisions == markov.states[_state].subdivisions));
+            // Ze stanu 'a' możliwe jest tylko przejście do stanu 'b'.
+            assert!(path.windows(2).all(|_pair| _pair[0] != "a" || _pair[1] == "b"));
+
+            assert_eq!(walk(&markov, 11).1, path);
+            assert_ne!(
+                (12..20).map(|_seed| walk(&markov, _seed).1).collect::<Vec<Vec<String>>>(),
+                vec![path; 8]
+            );
+        }
+
+        #[test]
+        fn markov_walk_stops_after_duration() {
+            // Beaty stanów trwają 2 beaty - sekwencja trwająca co najmniej 5.5 beatu ma 3 beaty.
+            let markov = markov_chain(json!({
+                "states": { "a": { "duration": { "beats": 2.0 } } },
+                "transitions": { "a": { "a": 1.0 } },
+                "duration": { "beats": 5.5 }
+            }));
+
+            assert_eq!(walk(&markov, 1).1, vec!["a", "a", "a"]);
+
+            // Przy obu warunkach sekwencja kończy się po spełnieniu pierwszego z nich.
+            let markov = markov_chain(json!({
+                "states": { "a": {} },
+                "transitions": { "a": { "a": 1.0 } },
+                "beats": 3,
+                "duration": { "bars": 4.0 }
+            }));
+
+            assert_eq!(walk(&markov, 1).0.len(), 3);
+        }
+
+        #[test]
+        fn markov_walk_stops_without_transitions() {
+            let markov = markov_chain(json!({
+                "states": { "a": {}, "b": {} },
+                "transitions": { "a": { "b": 1.0 }, "b": { "a": 0.0 } },
+                "initial": "a",
+                "beats": 10
+            }));
+
+            assert_eq!(walk(&markov, 1).1, vec!["a", "b"]);
         }
     }
 }
@@ -0,0 +1,552 @@
+pub mod tools {
+    use std::fs::write;
+    use std::path::Path;
+
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    use crate::cli::tools::Verbosity;
+    use crate::granular_synth_config::tools::{
+        default_output_file_template, deserialize_value, load_tracks_configurations, ConfigError,
+        SynthConfiguration,
+    };
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    const MAX_SWEEP_POINTS: usize = 10_000;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Zakres wartości liczbowych przeglądu (od 'from' do 'to' włącznie, co 'step').
+    #[derive(Debug, Deserialize)]
+    pub struct SweepRange {
+        pub from: f64,
+        pub to: f64,
+        pub step: f64,
+    }
+
+    impl SweepRange {
+        // Wyznacza kolejne wartości zakresu, zakres o całkowitych granicach i kroku daje wartości całkowite.
+        fn values(&self) -> Vec<Value> {
+            let integer =
+                self.from.fract() == 0.0 && self.to.fract() == 0.0 && self.step.fract() == 0.0;
+            let steps_count = ((self.to - self.from) / self.step + 1e-9).floor() as usize + 1;
+
+            return (0..steps_count)
+                .map(|_step_number| {
+                    let value = self.from + _step_number as f64 * self.step;
+
+                    if integer == true {
+                        return json!(value.round() as i64);
+                    } else {
+                        return json!((value * 1e9).round() / 1e9);
+                    }
+                })
+                .collect();
+        }
+    }
+
+    // Parametr przeglądu: wskaźnik JSON na wartość konfiguracji i lista wartości lub zakres.
+    #[derive(Debug, Deserialize)]
+    pub struct SweepParameter {
+        pub pointer: String,
+
+        #[serde(default)]
+        pub values: Vec<Value>,
+
+        #[serde(default)]
+        pub range: Option<SweepRange>,
+    }
+
+    impl SweepParameter {
+        fn validate(
+            &mut self,
+            _json_file_value: &Value,
+            _parameter_number: usize,
+        ) -> Result<(), Vec<ConfigError>> {
+            let mut errors: Vec<ConfigError> = Vec::new();
+
+            // Wskaźnik może wskazywać także zmienną opcjonalną pominiętą w konfiguracji, jeżeli istnieje jej obiekt nadrzędny.
+            let existing_parent = match split_pointer(&self.pointer) {
+                Some((_parent_pointer, _)) => _json_file_value
+                    .pointer(_parent_pointer)
+                    .is_some_and(|_parent| _parent.is_object()),
+                None => false,
+            };
+
+            if self.pointer.starts_with('/') == false
+                || self.pointer.starts_with("/Sweep") == true
+                || (_json_file_value.pointer(&self.pointer).is_none() == true && existing_parent == false)
+            {
+                errors.push(ConfigError::new(
+                    format!("/Sweep/{}/pointer", _parameter_number),
+                    format!(
+                        "Błąd danych - \'Sweep\' - parametr [{}] ->\n\tnieprawidłowa wartość zmiennej: \'pointer\' (wskaźnik: \'{}\' nie wskazuje istniejącej wartości konfiguracji ani zmiennej istniejącego obiektu) :/",
+                        _parameter_number + 1, self.pointer
+                    ),
+                ));
+            }
+
+            match &self.range {
+                Some(_range) => {
+                    if self.values.is_empty() == false {
+                        errors.push(ConfigError::new(
+                            format!("/Sweep/{}", _parameter_number),
+                            format!(
+                                "Błąd danych - \'Sweep\' - parametr [{}] ->\n\tnależy podać \'values\' albo \'range\', nie obie zmienne jednocześnie :/",
+                                _parameter_number + 1
+                            ),
+                        ));
+                    } else if _range.step <= 0.0 || _range.from > _range.to {
+                        errors.push(ConfigError::new(
+                            format!("/Sweep/{}/range", _parameter_number),
+                            format!(
+                                "Błąd danych - \'Sweep\' - parametr [{}] ->\n\tnieprawidłowa wartość zmiennej: \'range\' (\'from\' <= \'to\', \'step\' > 0.0) :/",
+                                _parameter_number + 1
+                            ),
+                        ));
+                    } else {
+                        self.values = _range.values();
+                    }
+                }
+                None => {
+                    if self.values.is_empty() == true {
+                        errors.push(ConfigError::new(
+                            format!("/Sweep/{}/values", _parameter_number),
+                            format!(
+                                "Błąd danych - \'Sweep\' - parametr [{}] ->\n\tnie podano wartości parametru (wymagana niepusta lista \'values\' albo \'range\') :/",
+                                _parameter_number + 1
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            if errors.is_empty() == true {
+                return Ok(());
+            } else {
+                return Err(errors);
+            }
+        }
+    }
+
+    // Punkt przeglądu: wartości parametrów i skonfigurowany na ich podstawie obiekt Value.
+    #[derive(Debug)]
+    pub struct SweepPoint {
+        pub index: usize,
+        pub parameters: Vec<(String, Value)>,
+        pub json_file_value: Value,
+        pub output_files: Vec<Value>,
+    }
+
+    impl SweepPoint {
+        // Opis wartości parametrów punktu.
+        pub fn label(&self) -> String {
+            return self
+                .parameters
+                .iter()
+                .map(|(_pointer, _value)| format!("{} = {}", _pointer, _value))
+                .collect::<Vec<String>>()
+                .join(", ");
+        }
+
+        // Sprawdza poprawność konfiguracji punktu przed syntezą.
+        pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+            let synth_configuration = match SynthConfiguration::load(&self.json_file_value) {
+                Ok(_synth_configuration) => _synth_configuration,
+                Err(_errors) => {
+                    return Err(_errors);
+                }
+            };
+
+            match load_tracks_configurations(&self.json_file_value, &synth_configuration) {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_errors) => {
+                    return Err(_errors);
+                }
+            }
+        }
+    }
+
+    // Przegląd parametrów: iloczyn kartezjański wartości wszystkich parametrów sekcji 'Sweep'.
+    #[derive(Debug)]
+    pub struct Sweep {
+        pub parameters: Vec<SweepParameter>,
+        pub points: Vec<SweepPoint>,
+    }
+
+    impl Sweep {
+        // Wczytuje sekcję 'Sweep' i rozwija ją na osobne konfiguracje, zwraca None gdy sekcja nie występuje.
+        pub fn load(_json_file_value: &Value) -> Result<Option<Sweep>, Vec<ConfigError>> {
+            if _json_file_value.get("Sweep").is_none() == true {
+                return Ok(None);
+            }
+
+            let mut parameters: Vec<SweepParameter> =
+                match deserialize_value(&_json_file_value["Sweep"], "/Sweep") {
+                    Ok(_parameters) => _parameters,
+                    Err(_error) => {
+                        return Err(vec![_error]);
+                    }
+                };
+
+            let mut errors: Vec<ConfigError> = Vec::new();
+
+            if parameters.is_empty() == true {
+                errors.push(ConfigError::new(
+                    "/Sweep".to_string(),
+                    "Błąd danych - \'Sweep\' ->\n\tnie podano żadnego parametru przeglądu :/"
+                        .to_string(),
+                ));
+            }
+
+            for (_parameter_number, _parameter) in parameters.iter_mut().enumerate() {
+                match _parameter.validate(_json_file_value, _parameter_number) {
+                    Err(mut _errors) => {
+                        errors.append(&mut _errors);
+                    }
+                    _ => {}
+                }
+            }
+
+            if errors.is_empty() == false {
+                return Err(errors);
+            }
+
+            let points_count: usize = parameters.iter().fold(1usize, |_count, _parameter| {
+                _count.saturating_mul(_parameter.values.len())
+            });
+
+            if points_count > MAX_SWEEP_POINTS {
+                return Err(vec![ConfigError::new(
+                    "/Sweep".to_string(),
+                    format!(
+                        "Błąd danych - \'Sweep\' ->\n\tzbyt wiele punktów przeglądu: {} (maksymalnie: {}) :/",
+                        points_count, MAX_SWEEP_POINTS
+                    ),
+                )]);
+            }
+
+            // Obiekt Value bez sekcji 'Sweep', będący podstawą konfiguracji każdego punktu.
+            let mut base_json_file_value = _json_file_value.clone();
+
+            if let Some(_root) = base_json_file_value.as_object_mut() {
+                _root.remove("Sweep");
+            }
+
+            let output_file_template =
+                match _json_file_value["SynthConfiguration"]["output_file_template"].as_str() {
+                    Some(_output_file_template) => _output_file_template.to_string(),
+                    None => default_output_file_template(),
+                };
+
+            let mut points: Vec<SweepPoint> = Vec::with_capacity(points_count);
+            let mut value_indexes: Vec<usize> = vec![0; parameters.len()];
+
+            for _point_number in 0..points_count {
+                let mut json_file_value = base_json_file_value.clone();
+                let mut point_parameters: Vec<(String, Value)> =
+                    Vec::with_capacity(parameters.len());
+
+                for (_parameter, _value_index) in parameters.iter().zip(value_indexes.iter()) {
+                    let value = _parameter.values[*_value_index].clone();
+
+                    match json_file_value.pointer_mut(&_parameter.pointer) {
+                        Some(_target) => {
+                            *_target = value.clone();
+                        }
+                        // Zmienna opcjonalna pominięta w konfiguracji jest dodawana do obiektu nadrzędnego.
+                        None => {
+                            if let Some((_parent_pointer, _key)) = split_pointer(&_parameter.pointer) {
+                                if let Some(_parent) = json_file_value
+                                    .pointer_mut(_parent_pointer)
+                                    .and_then(|_parent| _parent.as_object_mut())
+                                {
+                                    _parent.insert(_key, value.clone());
+                                }
+                            }
+                        }
+                    }
+
+                    point_parameters.push((_parameter.pointer.clone(), value));
+                }
+
+                // Nazwy plików wyjściowych punktu otrzymują przyrostek '_s{numer punktu}'.
+                let mut point_output_file_template = output_file_template.clone();
+                let suffix = format!("_s{:03}", _point_number + 1);
+
+                if point_output_file_template.to_lowercase().ends_with(".wav") == true {
+                    point_output_file_template
+                        .insert_str(point_output_file_template.len() - ".wav".len(), &suffix);
+                } else {
+                    point_output_file_template.push_str(&suffix);
+                }
+
+                if json_file_value["SynthConfiguration"].is_object() == true {
+                    json_file_value["SynthConfiguration"]["output_file_template"] =
+                        json!(point_output_file_template);
+                }
+
+                points.push(SweepPoint {
+                    index: _point_number + 1,
+                    parameters: point_parameters,
+                    json_file_value,
+                    output_files: Vec::new(),
+                });
+
+                // Kolejna kombinacja wartości (ostatni parametr zmienia się najszybciej).
+                for _parameter_number in (0..parameters.len()).rev() {
+                    value_indexes[_parameter_number] += 1;
+
+                    if value_indexes[_parameter_number] < parameters[_parameter_number].values.len()
+                    {
+                        break;
+                    }
+
+                    value_indexes[_parameter_number] = 0;
+                }
+            }
+
+            return Ok(Some(Sweep {
+                parameters,
+                points,
+            }));
+        }
+
+        // Zapisuje indeks przeglądu '.csv': punkt, plik wyjściowy, ziarno i wartości parametrów.
+        pub fn save_index(
+            &self,
+            _output_directory: &String,
+            _verbosity: Verbosity,
+        ) -> Result<(), String> {
+            let index_file_path = format!("{}/sweep.csv", _output_directory);
+
+            let mut header: Vec<String> = vec![
+                "point".to_string(),
+                "track".to_string(),
+                "take".to_string(),
+                "seed".to_string(),
+                "file".to_string(),
+            ];
+
+            header.extend(
+                self.parameters
+                    .iter()
+                    .map(|_parameter| csv_field(&_parameter.pointer)),
+            );
+
+            let mut lines: Vec<String> = vec![header.join(",")];
+
+            for point in self.points.iter() {
+                for output_file in point.output_files.iter() {
+                    let mut fields: Vec<String> = vec![
+                        point.index.to_string(),
+                        csv_field(&csv_value(&output_file["track"])),
+                        csv_value(&output_file["take"]),
+                        csv_value(&output_file["seed"]),
+                        csv_field(&csv_value(&output_file["file"])),
+                    ];
+
+                    fields.extend(
+                        point
+                            .parameters
+                            .iter()
+                            .map(|(_, _value)| csv_field(&csv_value(_value))),
+                    );
+
+                    lines.push(fields.join(","));
+                }
+            }
+
+            match write(Path::new(&index_file_path), lines.join("\n") + "\n") {
+                Err(_system_error) => {
+                    return Err(format!(
+                        "Błąd ->\n\tnie można utworzyć pliku \'{}\'.\n\tSystem error: {} :/",
+                        index_file_path, _system_error
+                    ));
+                }
+                _ => {}
+            }
+
+            if _verbosity >= Verbosity::Normal {
+                println!(
+                    "\nzapisano indeks przeglądu parametrów: \'{}\'",
+                    index_file_path
+                );
+            }
+
+            return Ok(());
+        }
+    }
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Rozdziela wskaźnik JSON na wskaźnik obiektu nadrzędnego i klucz ostatniego segmentu (bez sekwencji '~0', '~1').
+    fn split_pointer(_pointer: &str) -> Option<(&str, String)> {
+        let separator = _pointer.rfind('/')?;
+
+        return Some((
+            &_pointer[..separator],
+            _pointer[separator + 1..].replace("~1", "/").replace("~0", "~"),
+        ));
+    }
+
+    // Tekstowa postać wartości w pliku '.csv' (napisy bez cudzysłowów JSON).
+    fn csv_value(_value: &Value) -> String {
+        match _value {
+            Value::String(_string) => {
+                return _string.clone();
+            }
+            _ => {
+                return _value.to_string();
+            }
+        }
+    }
+
+    // Pole pliku '.csv', ujmowane w cudzysłów gdy zawiera przecinek, cudzysłów lub znak nowej linii.
+    fn csv_field(_field: &String) -> String {
+        if _field.contains([',', '"', '\n']) == true {
+            return format!("\"{}\"", _field.replace('"', "\"\""));
+        }
+
+        return _field.clone();
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn range(_from: f64, _to: f64, _step: f64) -> SweepRange {
+            return SweepRange {
+                from: _from,
+                to: _to,
+                step: _step,
+            };
+        }
+
+        fn configuration(_sweep: Value, _output_file_template: Option<&str>) -> Value {
+            let mut configuration = json!({
+                "SynthConfiguration": { "seed": 1 },
+                "Sweep": _sweep,
+                "Tracks": [{
+                    "track_properties": { "track_name": "a" },
+                    "grains_properties": { "sample_file_path": "a.wav", "grains_count": 16 }
+                }]
+            });
+
+            if let Some(_output_file_template) = _output_file_template {
+                configuration["SynthConfiguration"]["output_file_template"] = json!(_output_file_template);
+            }
+
+            return configuration;
+        }
+
+        #[test]
+        fn integer_range_gives_integer_values() {
+            assert_eq!(range(16.0, 48.0, 16.0).values(), vec![json!(16), json!(32), json!(48)]);
+            assert_eq!(range(1.0, 4.0, 2.0).values(), vec![json!(1), json!(3)]);
+            assert!(range(0.0, 2.0, 1.0).values().iter().all(|_value| _value.is_i64()));
+        }
+
+        #[test]
+        fn float_range_tolerates_rounding_errors() {
+            // 0.1 * 3 nie jest dokładnie równe 0.3 - ostatnia wartość zakresu nie może zostać pominięta.
+            assert_eq!(
+                range(0.0, 0.3, 0.1).values(),
+                vec![json!(0.0), json!(0.1), json!(0.2), json!(0.3)]
+            );
+            assert_eq!(range(0.5, 1.0, 0.25).values(), vec![json!(0.5), json!(0.75), json!(1.0)]);
+            assert_eq!(range(0.2, 0.2, 0.1).values(), vec![json!(0.2)]);
+            assert!(range(0.0, 1.0, 0.5).values().iter().all(|_value| _value.is_f64()));
+        }
+
+        #[test]
+        fn points_follow_cartesian_product_order() {
+            let sweep = Sweep::load(&configuration(
+                json!([
+                    { "pointer": "/Tracks/0/grains_properties/grains_count", "values": [8, 16] },
+                    { "pointer": "/SynthConfiguration/seed", "range": { "from": 1, "to": 3, "step": 1 } }
+                ]),
+                None,
+            ))
+            .unwrap()
+            .unwrap();
+
+            let points: Vec<(i64, i64)> = sweep
+                .points
+                .iter()
+                .map(|_point| {
+                    (
+                        _point.json_file_value["Tracks"][0]["grains_properties"]["grains_count"].as_i64().unwrap(),
+                        _point.json_file_value["SynthConfiguration"]["seed"].as_i64().unwrap(),
+                    )
+                })
+                .collect();
+
+            assert_eq!(points, vec![(8, 1), (8, 2), (8, 3), (16, 1), (16, 2), (16, 3)]);
+            assert_eq!(
+                sweep.points.iter().map(|_point| _point.index).collect::<Vec<usize>>(),
+                vec![1, 2, 3, 4, 5, 6]
+            );
+            assert_eq!(
+                sweep.points[4].label(),
+                "/Tracks/0/grains_properties/grains_count = 16, /SynthConfiguration/seed = 2"
+            );
+            assert!(sweep.points.iter().all(|_point| _point.json_file_value.get("Sweep").is_none()));
+        }
+
+        #[test]
+        fn output_file_template_receives_point_suffix() {
+            let sweep_parameters = json!([{ "pointer": "/SynthConfiguration/seed", "values": [1, 2] }]);
+            let template = |_sweep: &Sweep, _point: usize| -> String {
+                return _sweep.points[_point].json_file_value["SynthConfiguration"]["output_file_template"]
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+            };
+
+            let sweep = Sweep::load(&configuration(sweep_parameters.clone(), Some("{track}_{seed}.WAV")))
+                .unwrap()
+                .unwrap();
+            assert_eq!(template(&sweep, 0), "{track}_{seed}_s001.WAV");
+            assert_eq!(template(&sweep, 1), "{track}_{seed}_s002.WAV");
+
+            let sweep = Sweep::load(&configuration(sweep_parameters, Some("{track}"))).unwrap().unwrap();
+            assert_eq!(template(&sweep, 1), "{track}_s002");
+
+            // Szablon domyślny: '{track}.wav'.
+            let sweep = Sweep::load(&configuration(
+                json!([{ "pointer": "/SynthConfiguration/seed", "values": [1] }]),
+                None,
+            ))
+            .unwrap()
+            .unwrap();
+            assert_eq!(template(&sweep, 0), "{track}_s001.wav");
+        }
+
+        #[test]
+        fn omitted_optional_field_is_inserted() {
+            let sweep = Sweep::load(&configuration(
+                json!([{ "pointer": "/Tracks/0/track_properties/track_panorama", "values": [-0.5] }]),
+                None,
+            ))
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(
+                sweep.points[0].json_file_value["Tracks"][0]["track_properties"]["track_panorama"],
+                json!(-0.5)
+            );
+
+            let errors = Sweep::load(&configuration(
+                json!([{ "pointer": "/Tracks/0/missing/track_panorama", "values": [-0.5] }]),
+                None,
+            ))
+            .unwrap_err();
+
+            assert_eq!(errors[0].pointer, "/Sweep/0/pointer");
+        }
+    }
+}
@@ -0,0 +1,289 @@
+pub mod core {
+    use crate::granular_synth_config::tools::{
+        BeatConfiguration, MusicalDuration, SynthConfiguration,
+    };
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Liczba beatów ciszy przed pierwszym beatem sekwencji i po ostatnim z nich.
+    const LEAD_IN_BEATS: f64 = 1.0;
+    const TAIL_BEATS: f64 = 1.0;
+
+    // ------------------------------------------------------------------------------------------------------------------------------------------
+
+    // Odcinek mapy tempa o stałym lub liniowo zmieniającym się tempie.
+    #[derive(Debug)]
+    struct TempoSegment {
+        start_beat: f64,
+        start_time_s: f64,
+        start_bpm: f64,
+        end_beat: f64,
+        end_bpm: f64,
+    }
+
+    impl TempoSegment {
+        // Przyrost tempa na beat.
+        fn slope(&self) -> f64 {
+            if self.end_beat.is_finite() == true && self.end_beat > self.start_beat {
+                return (self.end_bpm - self.start_bpm) / (self.end_beat - self.start_beat);
+            }
+
+            return 0.0;
+        }
+
+        // Czas trwania (w sekundach) podanej liczby beatów od początku odcinka (całka z 60 / tempo).
+        fn duration_s(&self, _beats: f64) -> f64 {
+            let slope = self.slope();
+
+            if slope.abs() < 1e-12 {
+                return 60.0 * _beats / self.start_bpm;
+            }
+
+            return (60.0 / slope) * ((self.start_bpm + slope * _beats) / self.start_bpm).ln();
+        }
+
+        // Liczba beatów od początku odcinka odpowiadająca podanemu czasowi (odwrotność 'duration_s').
+        fn beats(&self, _time_s: f64) -> f64 {
+            let slope = self.slope();
+
+            if slope.abs() < 1e-12 {
+                return _time_s * self.start_bpm / 60.0;
+            }
+
+            return self.start_bpm * ((slope * _time_s / 60.0).exp() - 1.0) / slope;
+        }
+    }
+
+    // Oś czasu przeliczająca położenie muzyczne (w beatach) na czas i indeksy próbek z uwzględnieniem mapy tempa.
+    #[derive(Debug)]
+    pub struct Timeline {
+        segments: Vec<TempoSegment>,
+        beats_per_bar: f64,
+        sampling_rate: f64,
+    }
+
+    impl Timeline {
+        // Buduje oś czasu na podstawie tempa bazowego ('beat_length_ms'), metrum i mapy tempa.
+        pub fn new(_synth_configuration: &SynthConfiguration) -> Self {
+            let beats_per_bar = _synth_configuration.time_signature.0 as f64;
+
+            let mut segments: Vec<TempoSegment> = Vec::new();
+            let mut current_beat: f64 = 0.0;
+            let mut current_time_s: f64 = 0.0;
+            let mut current_bpm: f64 = 60_000.0 / _synth_configuration.beat_length_ms;
+
+            // Położenia punktów mapy tempa liczone są od pierwszego beatu sekwencji, a nie od początku beatu ciszy.
+            for point in _synth_configuration.tempo_map.iter() {
+                let position = point
+                    .at
+                    .in_beats(beats_per_bar)
+                    .map(|_beats| _beats + LEAD_IN_BEATS)
+                    .unwrap_or(current_beat);
+
+                if position > current_beat {
+                    let segment = TempoSegment {
+                        start_beat: current_beat,
+                        start_time_s: current_time_s,
+                        start_bpm: current_bpm,
+                        end_beat: position,
+                        end_bpm: if point.ramp == true {
+                            point.tempo_bpm
+                        } else {
+                            current_bpm
+                        },
+                    };
+
+                    current_time_s += segment.duration_s(position - current_beat);
+                    segments.push(segment);
+                }
+
+                current_beat = position;
+                current_bpm = point.tempo_bpm;
+            }
+
+            segments.push(TempoSegment {
+                start_beat: current_beat,
+                start_time_s: current_time_s,
+                start_bpm: current_bpm,
+                end_beat: f64::INFINITY,
+                end_bpm: current_bpm,
+            });
+
+            return Timeline {
+                segments,
+                beats_per_bar,
+                sampling_rate: _synth_configuration.engine_sampling_rate as f64,
+            };
+        }
+
+        // Czas (w sekundach) odpowiadający położeniu w beatach.
+        pub fn time_s(&self, _beat: f64) -> f64 {
+            let segment = self
+                .segments
+                .iter()
+                .rev()
+                .find(|_segment| _segment.start_beat <= _beat)
+                .unwrap_or(&self.segments[0]);
+
+            return segment.start_time_s + segment.duration_s(_beat - segment.start_beat);
+        }
+
+        // Położenie w beatach odpowiadające czasowi (w sekundach).
+        pub fn beat_at(&self, _time_s: f64) -> f64 {
+            let segment = self
+                .segments
+                .iter()
+                .rev()
+                .find(|_segment| _segment.start_time_s <= _time_s)
+                .unwrap_or(&self.segments[0]);
+
+            return segment.start_beat + segment.beats(_time_s - segment.start_time_s);
+        }
+
+        // Indeks próbki odpowiadający położeniu w beatach.
+        pub fn sample_index(&self, _beat: f64) -> usize {
+            return (self.time_s(_beat) * self.sampling_rate).round().max(0.0) as usize;
+        }
+
+        // Położenie (w beatach) pierwszego beatu sekwencji.
+        pub fn sequence_start(&self) -> f64 {
+            return LEAD_IN_BEATS;
+        }
+
+        // Długość (w beatach) odcinka o podanym czasie trwania rozpoczynającego się w położeniu '_position',
+        // brak czasu trwania oznacza jeden beat.
+        pub fn span_length(&self, _duration: &Option<MusicalDuration>, _position: f64) -> f64 {
+            match _duration {
+                None => {
+                    return 1.0;
+                }
+                Some(MusicalDuration::Ms(_ms)) => {
+                    return self.beat_at(self.time_s(_position) + _ms / 1000.0) - _position;
+                }
+                Some(_duration) => {
+                    return _duration.in_beats(self.beats_per_bar).unwrap_or(1.0);
+                }
+            }
+        }
+
+        // Położenia początków i długości (w beatach) kolejnych beatów sekwencji.
+        pub fn beat_spans(&self, _beat_sequence: &Vec<BeatConfiguration>) -> Vec<(f64, f64)> {
+            let mut spans: Vec<(f64, f64)> = Vec::with_capacity(_beat_sequence.len());
+            let mut position: f64 = LEAD_IN_BEATS;
+
+            for beat in _beat_sequence.iter() {
+                let length = self.span_length(&beat.duration, position);
+
+                spans.push((position, length));
+                position += length;
+            }
+
+            return spans;
+        }
+
+        // Długość syntezy sekwencji (w próbkach) wraz z beatem ciszy na początku i na końcu.
+        pub fn render_length(&self, _beat_sequence: &Vec<BeatConfiguration>) -> usize {
+            let end = match self.beat_spans(_beat_sequence).last() {
+                Some((_start, _length)) => _start + _length,
+                None => LEAD_IN_BEATS,
+            };
+
+            return self.sample_index(end + TAIL_BEATS);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use serde_json::json;
+
+        use super::*;
+
+        fn synth_configuration(_configuration: serde_json::Value) -> SynthConfiguration {
+            return serde_json::from_value(_configuration).unwrap();
+        }
+
+        fn ramp_segment() -> TempoSegment {
+            return TempoSegment {
+                start_beat: 0.0,
+                start_time_s: 0.0,
+                start_bpm: 60.0,
+                end_beat: 4.0,
+                end_bpm: 120.0,
+            };
+        }
+
+        #[test]
+        fn ramp_duration_matches_numeric_integration() {
+            let segment = ramp_segment();
+            let steps = 100_000;
+            let step = 4.0 / steps as f64;
+            let numeric: f64 = (0..steps)
+                .map(|_step| {
+                    let beat = (_step as f64 + 0.5) * step;
+                    60.0 / (segment.start_bpm + segment.slope() * beat) * step
+                })
+                .sum();
+
+            assert!((segment.duration_s(4.0) - numeric).abs() < 1e-6);
+            assert!((segment.duration_s(4.0) - 4.0 * 2f64.ln()).abs() < 1e-9);
+        }
+
+        #[test]
+        fn ramp_beats_inverts_duration() {
+            let segment = ramp_segment();
+
+            for _beats in [0.0, 0.25, 1.0, 2.5, 4.0].iter() {
+                assert!((segment.beats(segment.duration_s(*_beats)) - _beats).abs() < 1e-9);
+            }
+        }
+
+        #[test]
+        fn timeline_round_trip_with_tempo_map() {
+            let timeline = Timeline::new(&synth_configuration(json!({
+                "beat_length_ms": 1000.0,
+                "tempo_map": [
+                    { "at": { "beats": 0.0 }, "tempo_bpm": 60.0 },
+                    { "at": { "beats": 4.0 }, "tempo_bpm": 120.0, "ramp": true },
+                    { "at": { "beats": 6.0 }, "tempo_bpm": 90.0 }
+                ]
+            })));
+
+            for _beat in [0.0, 1.0, 3.3, 5.0, 6.5, 8.0, 12.0].iter() {
+                assert!((timeline.beat_at(timeline.time_s(*_beat)) - _beat).abs() < 1e-9);
+            }
+        }
+
+        #[test]
+        fn tempo_map_starts_at_first_beat_of_sequence() {
+            let timeline = Timeline::new(&synth_configuration(json!({
+                "beat_length_ms": 1000.0,
+                "tempo_map": [{ "at": { "beats": 0.0 }, "tempo_bpm": 120.0 }]
+            })));
+
+            // Beat ciszy zachowuje tempo bazowe, tempo mapy obowiązuje od pierwszego beatu sekwencji.
+            assert!((timeline.time_s(timeline.sequence_start()) - 1.0).abs() < 1e-9);
+            assert!((timeline.time_s(timeline.sequence_start() + 2.0) - 2.0).abs() < 1e-9);
+        }
+
+        #[test]
+        fn beat_spans_follow_durations() {
+            let timeline = Timeline::new(&synth_configuration(json!({
+                "beat_length_ms": 500.0,
+                "time_signature": [3, 4]
+            })));
+            let beat_sequence: Vec<BeatConfiguration> = serde_json::from_value(json!([
+                {},
+                { "duration": { "bars": 1.0 } },
+                { "duration": { "ms": 250.0 } }
+            ]))
+            .unwrap();
+
+            let spans = timeline.beat_spans(&beat_sequence);
+
+            assert_eq!(spans.len(), 3);
+            assert!((spans[0].0 - LEAD_IN_BEATS).abs() < 1e-9 && (spans[0].1 - 1.0).abs() < 1e-9);
+            assert!((spans[1].0 - (LEAD_IN_BEATS + 1.0)).abs() < 1e-9 && (spans[1].1 - 3.0).abs() < 1e-9);
+            assert!((spans[2].0 - (LEAD_IN_BEATS + 4.0)).abs() < 1e-9 && (spans[2].1 - 0.5).abs() < 1e-9);
+        }
+    }
+}